@@ -1,9 +1,10 @@
 use cosmwasm_std::{
-    log, to_binary, Api, CanonicalAddr, Env, Extern, HandleResponse, HandleResult, HumanAddr,
-    InitResponse, InitResult, Querier, QueryResult, ReadonlyStorage, StdError, StdResult, Storage,
-    Uint128,
+    from_binary, log, to_binary, Api, BankMsg, Binary, CanonicalAddr, Coin, CosmosMsg, Env, Extern,
+    HandleResponse, HandleResult, HumanAddr, InitResponse, InitResult, Querier, QueryResult,
+    ReadonlyStorage, StdError, StdResult, Storage, Uint128,
 };
 use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use sha2::{Digest, Sha256};
 
 use secret_toolkit::{
     permit::{validate, Permit, RevokedPermits},
@@ -11,18 +12,49 @@ use secret_toolkit::{
     utils::{pad_handle_result, pad_query_result, HandleCallback, Query},
 };
 
+use crate::error::ContractError;
 use crate::factory_msgs::FactoryHandleMsg;
-use crate::msg::{HandleAnswer, HandleMsg, InitMsg, QueryAnswer, QueryMsg, ViewerInfo};
+use crate::msg::{
+    CooldownScope, DepositMsg, DrainMode, HandleAnswer, HandleMsg, InitMsg, ListingRecord,
+    MintEventHumanized, MintStats, PrngAuditEntry, QueryAnswer, QueryMsg, Round, StatsLevel,
+    TierWeight, TraitCount, ViewerInfo, WhitelistEntry,
+};
 use crate::rand::{extend_entropy, sha_256, Prng};
 use crate::snip721::{
-    NftDossierForListing, NftDossierResponse, Snip721HandleMsg, Snip721QueryMsg, Transfer,
+    BridgeMsg, NftDossierForListing, NftDossierResponse, Send, Snip721HandleMsg, Snip721QueryMsg,
+    Transfer,
 };
 use crate::state::{
-    Counts, ADMINS_KEY, COLLECTION_KEY, COUNT_KEY, EXAMPLE_KEY, EXPECTED_KEY, MY_ADDRESS_KEY,
-    PREFIX_LIST_REGISTRY, PREFIX_REVOKED_PERMITS, PREFIX_TOKEN_IDS, PREFIX_VIEW_KEY,
-    PREFIX_WHITELIST, PRNG_SEED_KEY,
+    BridgeMode, Counts, EntropyRequirement, ExpectedListing, GumballInfo, LegacyCounts, ListingFee,
+    MintEvent, OracleRandomness, PendingClear, PreviewContract, ReservedToken, StoredListing,
+    StoredWhitelistEntry, ADDR_MINT_LIMIT_KEY, ADMINS_KEY, ADMIN_LAST_MINT_TIME_KEY,
+    ADMIN_MEMO_KEY, ADMIN_MINT_COOLDOWN_KEY, BRIDGE_MODE_KEY, BRIDGE_RELEASED_KEY,
+    BULK_WL_LIMIT_KEY, CAPACITY_PARTIAL_KEY, COLLECTION_KEY, CONTRACT_EXPIRY_KEY,
+    COOLDOWN_SCOPE_KEY, COUNT_KEY, CURRENT_BATCH_ID_KEY, CURRENT_ROUND_KEY,
+    DEFAULT_LISTING_DESC_KEY, DISPLAY_PRICE_KEY, DRAIN_MODE_KEY, ENTROPY_POOL_KEY, EXAMPLE_KEY,
+    EXAMPLE_QUERY_VK_KEY, GUMBALL_INFO_KEY, IS_FINALIZED_KEY, LISTING_ENTROPY_KEY, LISTING_FEE_KEY,
+    LISTING_MEMO_KEY, LISTING_PREVIEW_COUNT_KEY, LIST_COUNT_KEY, LOW_POOL_NOTIFIED_KEY,
+    LOW_POOL_THRESHOLD_KEY, MAX_ADMINS_KEY, MAX_CAPACITY_KEY, MAX_PER_FACTORY_KEY, MAX_PRICE_KEY,
+    MAX_TOKENS_PER_TRANSFER_MSG_KEY, MINTS_SINCE_RESEED_KEY, MINT_COOLDOWN_KEY, MINT_END_KEY,
+    MINT_LOG_HEAD_KEY, MINT_PAUSE_THRESHOLD_KEY, MINT_START_KEY, MINT_STATS_KEY,
+    MIN_ENTROPY_LEN_KEY, MIN_FILL_KEY, MIN_PRICE_KEY, MY_ADDRESS_KEY, ORACLE_RANDOMNESS_KEY,
+    PAUSED_KEY, PENDING_CLEAR_EXPIRY_KEY, PENDING_CLEAR_KEY, POOL_CLEARED_KEY,
+    PREFIX_ADDR_MINT_COUNT, PREFIX_BATCH_TAG, PREFIX_BATCH_WEIGHT, PREFIX_BUYER_CAP,
+    PREFIX_EXPECTED_FACTORIES, PREFIX_FACTORY_LISTING_COUNT, PREFIX_LAST_MINT_BLOCK,
+    PREFIX_LISTING_MINT_COUNT, PREFIX_LISTING_TAG, PREFIX_LIST_REGISTRY,
+    PREFIX_LIST_REGISTRY_INDEX, PREFIX_MINT_LOG, PREFIX_PRNG_AUDIT, PREFIX_REFERRAL_COUNTS,
+    PREFIX_RESERVED, PREFIX_REVOKED_PERMITS, PREFIX_ROUNDS, PREFIX_ROUND_MINT_COUNT,
+    PREFIX_ROUND_WHITELIST, PREFIX_TIER_COUNT, PREFIX_TIER_INDEX, PREFIX_TIER_POSITION,
+    PREFIX_TOKEN_BLACKLIST, PREFIX_TOKEN_DEPOSITOR, PREFIX_TOKEN_IDS, PREFIX_TOKEN_MEMO,
+    PREFIX_TOKEN_METADATA, PREFIX_TOKEN_REVERSE, PREFIX_TOKEN_TAG, PREFIX_VIEW_KEY,
+    PREFIX_WHITELIST, PREFIX_WHITELIST_ORDER, PREFIX_WHITELIST_POSITION, PREVIEW_CONTRACT_KEY,
+    PRIMARY_ADMIN_KEY, PRNG_AUDIT_COUNT_KEY, PRNG_AUDIT_ENABLED_KEY, PRNG_SEED_BLOCK_KEY,
+    PRNG_SEED_KEY, RANDOMNESS_ORACLE_KEY, RELEASED_CAP_KEY, RESERVED_COUNT_KEY, ROUND_COUNT_KEY,
+    SCHEMA_VERSION_KEY, SEQ_HEAD_KEY, STATS_LEVEL_KEY, TIER_WEIGHTS_KEY, TOKEN_PREFIX_KEY,
+    WHITELIST_COUNT_KEY, WHITELIST_MEMO_KEY, WHITELIST_ONLY_KEY,
 };
 use crate::storage::{load, may_load, remove, save};
+use crate::version::{CONTRACT_NAME, CONTRACT_VERSION};
 use crate::viewing_key::{ViewingKey, VIEWING_KEY_SIZE};
 use crate::{
     contract_info::{ContractInfo, StoreContractInfo},
@@ -30,6 +62,28 @@ use crate::{
 };
 
 pub const BLOCK_SIZE: usize = 256;
+/// default maximum number of addresses in a single whitelist update
+pub const DEFAULT_BULK_WL_LIMIT: u32 = 100;
+/// number of times to re-draw when a blacklisted token is selected
+pub const MAX_REDRAWS: u8 = 5;
+/// maximum number of NFTs shown in a listing display
+pub const MAX_PREVIEW_COUNT: u8 = 5;
+/// default maximum number of admins
+pub const DEFAULT_MAX_ADMINS: u32 = 20;
+/// version of the storage layout written by this code
+pub const SCHEMA_VERSION: u32 = 1;
+/// default maximum number of tokens sent in one BatchTransferNft message
+pub const DEFAULT_MAX_TRANSFER_BATCH: u32 = 50;
+/// number of blocks a proposal to clear the pool can be confirmed
+pub const CLEAR_PROPOSAL_BLOCKS: u64 = 50;
+/// number of recent mint events kept in the mint log
+pub const MINT_LOG_SIZE: u64 = 20;
+/// number of recent entries kept in the PRNG audit log
+pub const PRNG_AUDIT_SIZE: u64 = 100;
+/// maximum number of example NFTs kept for the listing display
+pub const MAX_EXAMPLES: usize = 5;
+/// default minimum number of bytes of entropy that must be provided
+pub const DEFAULT_MIN_ENTROPY_LEN: u8 = 16;
 
 ////////////////////////////////////// Init ///////////////////////////////////////
 /// Returns InitResult
@@ -52,13 +106,21 @@ pub fn init<S: Storage, A: Api, Q: Querier>(
         &deps.api.canonical_address(&env.contract.address)?,
     )?;
     let sender_raw = deps.api.canonical_address(&env.message.sender)?;
+    check_entropy(&deps.storage, &msg.entropy)?;
     let prng_seed: Vec<u8> = sha_256(base64::encode(msg.entropy.as_bytes()).as_bytes()).to_vec();
     save(&mut deps.storage, PRNG_SEED_KEY, &prng_seed)?;
-    let admins = vec![sender_raw];
+    save(&mut deps.storage, ENTROPY_POOL_KEY, &prng_seed)?;
+    save(&mut deps.storage, PRNG_SEED_BLOCK_KEY, &env.block.height)?;
+    save(&mut deps.storage, PRIMARY_ADMIN_KEY, &sender_raw)?;
+    let admins = vec![sender_raw.clone()];
     save(&mut deps.storage, ADMINS_KEY, &admins)?;
+    save(&mut deps.storage, MAX_ADMINS_KEY, &DEFAULT_MAX_ADMINS)?;
+    save(&mut deps.storage, SCHEMA_VERSION_KEY, &SCHEMA_VERSION)?;
     let counts = Counts {
         available: 0,
         released: 0,
+        last_deposit_block: 0,
+        last_mint_block: 0,
     };
     save(&mut deps.storage, COUNT_KEY, &counts)?;
     let messages = vec![register_receive_nft_msg(
@@ -71,6 +133,38 @@ pub fn init<S: Storage, A: Api, Q: Querier>(
     )?];
     let contract = msg.nft_contract.into_store(&deps.api)?;
     save(&mut deps.storage, COLLECTION_KEY, &contract)?;
+    save_mint_window(&mut deps.storage, msg.mint_start, msg.mint_end)?;
+    save_price_bounds(
+        &mut deps.storage,
+        msg.min_listing_price,
+        msg.max_listing_price,
+    )?;
+    if let Some(amount) = msg.listing_fee {
+        let denom = msg
+            .listing_fee_denom
+            .ok_or_else(|| StdError::from(ContractError::MissingFeeDenom))?;
+        let recipient = match msg.listing_fee_recipient {
+            Some(recip) => deps.api.canonical_address(&recip)?,
+            None => sender_raw.clone(),
+        };
+        let fee = ListingFee {
+            amount,
+            denom,
+            recipient,
+        };
+        save(&mut deps.storage, LISTING_FEE_KEY, &fee)?;
+    }
+    if let Some(max) = msg.max_capacity {
+        save(&mut deps.storage, MAX_CAPACITY_KEY, &max)?;
+    }
+    if let Some(min_fill) = msg.min_fill_before_listing {
+        save(&mut deps.storage, MIN_FILL_KEY, &min_fill)?;
+    }
+    save(
+        &mut deps.storage,
+        WHITELIST_ONLY_KEY,
+        &msg.whitelist_only.unwrap_or(false),
+    )?;
 
     Ok(InitResponse {
         messages,
@@ -91,18 +185,60 @@ pub fn handle<S: Storage, A: Api, Q: Querier>(
     env: Env,
     msg: HandleMsg,
 ) -> HandleResult {
-    let response = match msg {
-        HandleMsg::BatchReceiveNft { from, token_ids } => {
-            try_batch_receive(deps, &env.message.sender, &from, token_ids)
-        }
-        HandleMsg::ReceiveNft { sender, token_id } => {
-            try_batch_receive(deps, &env.message.sender, &sender, vec![token_id])
+    // a finalized or expired gumball only allows users to manage their own query authentication
+    let expired = may_load::<u64, _>(&deps.storage, CONTRACT_EXPIRY_KEY)?
+        .map(|exp| env.block.time >= exp)
+        .unwrap_or(false);
+    let lock_reason = if may_load::<bool, _>(&deps.storage, IS_FINALIZED_KEY)?.unwrap_or(false) {
+        Some("Gumball is finalized")
+    } else if expired {
+        Some("Contract has expired")
+    } else {
+        None
+    };
+    if let Some(reason) = lock_reason {
+        let allowed = match msg {
+            HandleMsg::CreateViewingKey { .. }
+            | HandleMsg::SetViewingKey { .. }
+            | HandleMsg::RevokePermit { .. }
+            | HandleMsg::RevokePermits { .. }
+            | HandleMsg::ClearMyRevokedPermits { .. } => true,
+            // admins can still get the tokens out of an expired gumball
+            HandleMsg::RemoveTokens { .. }
+            | HandleMsg::RecallTokens { .. }
+            | HandleMsg::CancelReservations { .. }
+            | HandleMsg::ProposeClearPool { .. }
+            | HandleMsg::ConfirmClearPool { .. }
+            | HandleMsg::RetrieveNft { .. } => expired,
+            _ => false,
+        };
+        if !allowed {
+            return pad_handle_result(
+                Err(ContractError::Locked(reason.to_string()).into()),
+                BLOCK_SIZE,
+            );
         }
+    }
+    let response = match msg {
+        HandleMsg::BatchReceiveNft {
+            from,
+            token_ids,
+            msg,
+        } => try_batch_receive(deps, &env, &from, token_ids, msg),
+        HandleMsg::ReceiveNft {
+            sender,
+            token_id,
+            msg,
+        } => try_batch_receive(deps, &env, &sender, vec![token_id], msg),
         HandleMsg::CreateViewingKey { entropy } => try_create_key(deps, &env, &entropy),
         HandleMsg::SetViewingKey { key, .. } => try_set_key(deps, &env.message.sender, key),
         HandleMsg::AddAdmins { admins } => try_add_admins(deps, &env.message.sender, admins),
         HandleMsg::RemoveAdmins { admins } => try_remove_admins(deps, &env.message.sender, admins),
-        HandleMsg::Mint { buyers, entropy } => try_mint(deps, &env, buyers, &entropy),
+        HandleMsg::Mint {
+            buyers,
+            entropy,
+            referrer,
+        } => try_mint(deps, &env, buyers, &entropy, referrer.as_ref()),
         HandleMsg::RegisterListing { listing_address } => {
             try_register_listing(deps, &env.message.sender, &listing_address)
         }
@@ -132,12 +268,32 @@ pub fn handle<S: Storage, A: Api, Q: Querier>(
         HandleMsg::RevokePermit { permit_name } => {
             revoke_permit(&mut deps.storage, &env.message.sender, &permit_name)
         }
-        HandleMsg::AddToWhitelist { addresses } => {
-            try_update_whitelist(deps, &env.message.sender, &addresses, true)
-        }
-        HandleMsg::RemoveFromWhitelist { addresses } => {
-            try_update_whitelist(deps, &env.message.sender, &addresses, false)
-        }
+        HandleMsg::AddToWhitelist {
+            addresses,
+            quantity,
+            round_id,
+            expires_at,
+        } => try_update_whitelist(
+            deps,
+            &env.message.sender,
+            &addresses,
+            true,
+            quantity.unwrap_or(1),
+            round_id,
+            expires_at,
+        ),
+        HandleMsg::RemoveFromWhitelist {
+            addresses,
+            round_id,
+        } => try_update_whitelist(
+            deps,
+            &env.message.sender,
+            &addresses,
+            false,
+            0,
+            round_id,
+            None,
+        ),
         HandleMsg::SetViewingKeyWithCollection {
             nft_contract,
             viewing_key,
@@ -146,6 +302,205 @@ pub fn handle<S: Storage, A: Api, Q: Querier>(
             nft_contract,
             token_ids,
         } => try_retrieve(deps, env, nft_contract, token_ids),
+        HandleMsg::SetAcceptedTokenPrefix { prefix } => {
+            try_set_token_prefix(deps, &env.message.sender, prefix)
+        }
+        HandleMsg::SetStatsResolution { level } => {
+            try_set_stats_level(deps, &env.message.sender, level)
+        }
+        HandleMsg::ForwardListingRegistration {
+            listing_address,
+            factory,
+        } => try_forward_registration(
+            deps,
+            &env.message.sender,
+            &listing_address,
+            factory.as_ref(),
+        ),
+        HandleMsg::SetExampleQueryViewingKey { entropy } => {
+            try_set_example_key(deps, &env, &entropy)
+        }
+        HandleMsg::SetPreviewContract { contract, enabled } => {
+            try_set_preview(deps, &env.message.sender, contract, enabled)
+        }
+        HandleMsg::SetMaxBatchWhitelistUpdate { max } => {
+            try_set_wl_limit(deps, &env.message.sender, max)
+        }
+        HandleMsg::SetRandomnessOracle { contract } => {
+            try_set_oracle(deps, &env.message.sender, contract)
+        }
+        HandleMsg::ReceiveOracleRandomness { round, randomness } => {
+            try_receive_randomness(deps, &env.message.sender, round, randomness)
+        }
+        HandleMsg::BlacklistToken { token_id } => {
+            try_update_blacklist(deps, &env.message.sender, &token_id, true)
+        }
+        HandleMsg::UnblacklistToken { token_id } => {
+            try_update_blacklist(deps, &env.message.sender, &token_id, false)
+        }
+        HandleMsg::ClearMyRevokedPermits { permit_names } => {
+            try_clear_revoked_permits(&mut deps.storage, &env.message.sender, &permit_names)
+        }
+        HandleMsg::SetDefaultListingDescription { description } => {
+            try_set_default_description(deps, &env.message.sender, description)
+        }
+        HandleMsg::SetMaxListingsPerFactory { max } => {
+            try_set_max_per_factory(deps, &env.message.sender, max)
+        }
+        HandleMsg::SetDisplayPrice { price, denom } => {
+            try_set_display_price(deps, &env.message.sender, price, denom)
+        }
+        HandleMsg::FinalizeGumball {} => try_finalize(deps, &env.message.sender),
+        HandleMsg::PushExampleToListings {
+            listings,
+            factory_contract,
+        } => try_push_example(deps, &env.message.sender, listings, factory_contract),
+        HandleMsg::SetContractExpiry { expires_at } => try_set_expiry(deps, &env, expires_at),
+        HandleMsg::SetPrngAuditEnabled { enabled } => {
+            try_set_prng_audit(deps, &env.message.sender, enabled)
+        }
+        HandleMsg::SetBridgeMode {
+            enabled,
+            bridge_contract,
+        } => try_set_bridge_mode(deps, &env.message.sender, bridge_contract, enabled),
+        HandleMsg::SetListingEntropyMode {
+            required,
+            min_length,
+        } => try_set_entropy_mode(deps, &env.message.sender, required, min_length),
+        HandleMsg::SetTokenMemo { token_id, memo } => {
+            try_set_token_memo(deps, &env.message.sender, &token_id, memo)
+        }
+        HandleMsg::SetAdminMintCooldown { seconds } => {
+            try_set_admin_cooldown(deps, &env.message.sender, seconds)
+        }
+        HandleMsg::SetListingCreationFee {
+            amount,
+            denom,
+            recipient,
+        } => try_set_listing_fee(deps, &env.message.sender, amount, denom, &recipient),
+        HandleMsg::TagToken { token_id, tag } => {
+            try_tag_token(deps, &env.message.sender, &token_id, tag)
+        }
+        HandleMsg::SetRequiredTag {
+            listing_address,
+            tag,
+        } => try_set_required_tag(deps, &env.message.sender, &listing_address, tag),
+        HandleMsg::SetMintPauseThreshold { threshold } => {
+            try_set_pause_threshold(deps, &env.message.sender, threshold)
+        }
+        HandleMsg::SetListingPreviewCount { count } => {
+            try_set_preview_count(deps, &env.message.sender, count)
+        }
+        HandleMsg::SetReleasedCap { cap } => try_set_released_cap(deps, &env.message.sender, cap),
+        HandleMsg::StartNewBatch {} => try_start_batch(deps, &env.message.sender),
+        HandleMsg::SetBatchWeight { batch_id, weight } => {
+            try_set_batch_weight(deps, &env.message.sender, batch_id, weight)
+        }
+        HandleMsg::SetPaused { paused } => try_set_paused(deps, &env.message.sender, paused),
+        HandleMsg::SetAddressMintLimit { limit } => {
+            try_set_address_limit(deps, &env.message.sender, limit)
+        }
+        HandleMsg::SetTierWeights { weights } => {
+            try_set_tier_weights(deps, &env.message.sender, weights)
+        }
+        HandleMsg::DeregisterListing { listing_address } => {
+            try_deregister_listing(deps, &env.message.sender, &listing_address)
+        }
+        HandleMsg::SetMintWindow { start, end } => {
+            try_set_mint_window(deps, &env.message.sender, start, end)
+        }
+        HandleMsg::SetCapacityMode { partial_ok } => {
+            try_set_capacity_mode(deps, &env.message.sender, partial_ok)
+        }
+        HandleMsg::ReseedPrng { entropy } => try_reseed_prng(deps, &env, &entropy),
+        HandleMsg::SetMemoTemplate {
+            listing_memo,
+            admin_memo,
+            whitelist_memo,
+        } => try_set_memo_template(
+            deps,
+            &env.message.sender,
+            listing_memo,
+            admin_memo,
+            whitelist_memo,
+        ),
+        HandleMsg::RefreshExample { token_id } => {
+            try_refresh_example(deps, &env.message.sender, token_id)
+        }
+        HandleMsg::SetDrainMode { mode } => try_set_drain_mode(deps, &env.message.sender, mode),
+        HandleMsg::SetMinFill { min_fill } => try_set_min_fill(deps, &env.message.sender, min_fill),
+        HandleMsg::RemoveTokens { token_ids } => try_remove_tokens(deps, &env, token_ids),
+        HandleMsg::SetMintCooldown { blocks } => {
+            try_set_mint_cooldown(deps, &env.message.sender, blocks)
+        }
+        HandleMsg::SetCooldownMode { applies_to } => {
+            try_set_cooldown_mode(deps, &env.message.sender, applies_to)
+        }
+        HandleMsg::ReserveToken {
+            token_id,
+            recipient,
+            expires_at,
+        } => try_reserve_token(deps, &env, token_id, &recipient, expires_at),
+        HandleMsg::SetMaxAdmins { max } => try_set_max_admins(deps, &env.message.sender, max),
+        HandleMsg::TransferPrimaryAdmin { new_admin } => {
+            try_transfer_primary(deps, &env.message.sender, &new_admin)
+        }
+        HandleMsg::SetLowPoolThreshold { threshold } => {
+            try_set_low_pool_threshold(deps, &env.message.sender, threshold)
+        }
+        HandleMsg::RevokePermits { permit_names } => {
+            revoke_permits(&mut deps.storage, &env.message.sender, permit_names)
+        }
+        HandleMsg::UpdateCollectionCodeHash { code_hash } => {
+            try_update_coll_hash(deps, env, code_hash)
+        }
+        HandleMsg::AddRound { round } => try_add_round(deps, &env.message.sender, round),
+        HandleMsg::AdvanceRound {} => try_advance_round(deps, &env.message.sender),
+        HandleMsg::SetWhitelistOnly { enabled } => {
+            try_set_whitelist_only(deps, &env.message.sender, enabled)
+        }
+        HandleMsg::SetMinEntropyLength { length } => {
+            try_set_min_entropy_len(deps, &env.message.sender, length)
+        }
+        HandleMsg::SetGumballInfo {
+            name,
+            description,
+            image_uri,
+        } => try_set_gumball_info(deps, &env.message.sender, name, description, image_uri),
+        HandleMsg::SetListingBuyerCap {
+            listing_address,
+            cap,
+        } => try_set_listing_buyer_cap(deps, &env.message.sender, &listing_address, cap),
+        HandleMsg::AddApprovedFactory { factory } => {
+            try_update_approved_factory(deps, &env.message.sender, &factory, true)
+        }
+        HandleMsg::RemoveApprovedFactory { factory } => {
+            try_update_approved_factory(deps, &env.message.sender, &factory, false)
+        }
+        HandleMsg::SetMaxTransferBatchSize { size } => {
+            try_set_max_transfer_batch(deps, &env.message.sender, size)
+        }
+        HandleMsg::ContributeEntropy { entropy } => {
+            try_contribute_entropy(deps, &env.message.sender, &entropy)
+        }
+        HandleMsg::RecallTokens { token_ids } => try_recall_tokens(deps, &env, token_ids),
+        HandleMsg::RemoveExpired {} => try_remove_expired(deps, &env),
+        HandleMsg::ProposeClearPool { recipient } => try_propose_clear_pool(deps, &env, &recipient),
+        HandleMsg::ConfirmClearPool { proposal_hash } => {
+            try_confirm_clear_pool(deps, &env, &proposal_hash)
+        }
+        HandleMsg::UpdateCollection { new_nft_contract } => {
+            try_update_collection(deps, env, new_nft_contract)
+        }
+        HandleMsg::SetPriceBounds { min, max } => {
+            try_set_price_bounds(deps, &env.message.sender, min, max)
+        }
+        HandleMsg::ClearWhitelist { max_entries } => {
+            try_clear_whitelist(deps, &env.message.sender, max_entries)
+        }
+        HandleMsg::CancelReservations { recipients } => {
+            try_cancel_reservations(deps, &env.message.sender, recipients)
+        }
     };
     pad_handle_result(response, BLOCK_SIZE)
 }
@@ -176,9 +531,7 @@ fn try_set_key_with_coll<S: Storage, A: Api, Q: Querier>(
     let contract =
         load::<StoreContractInfo, _>(&deps.storage, COLLECTION_KEY)?.into_humanized(&deps.api)?;
     if contract.address == nft_contract.address {
-        return Err(StdError::generic_err(
-            "This may not be called on the gumball contract's collection",
-        ));
+        return Err(ContractError::CollectionNotAllowed.into());
     }
     let messages = vec![set_viewing_key_msg(
         viewing_key.clone(),
@@ -194,6 +547,86 @@ fn try_set_key_with_coll<S: Storage, A: Api, Q: Querier>(
     })
 }
 
+/// Returns HandleResult
+///
+/// generates the viewing key used when querying the dossier of the example NFT, and registers it with
+/// the collection contract.  The key is never revealed, so no one can use it to view the gumball's
+/// inventory
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `env` - a reference to the Env of contract's environment
+/// * `entropy` - entropy used to generate the viewing key
+fn try_set_example_key<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    entropy: &str,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    check_entropy(&deps.storage, entropy)?;
+    let prng_seed: Vec<u8> = load(&deps.storage, PRNG_SEED_KEY)?;
+    let viewing_key = ViewingKey::new(env, &prng_seed, entropy.as_bytes()).0;
+    let contract =
+        load::<StoreContractInfo, _>(&deps.storage, COLLECTION_KEY)?.into_humanized(&deps.api)?;
+    save(&mut deps.storage, EXAMPLE_QUERY_VK_KEY, &viewing_key)?;
+    let messages = vec![set_viewing_key_msg(
+        viewing_key,
+        None,
+        BLOCK_SIZE,
+        contract.code_hash,
+        contract.address,
+    )?];
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetExampleQueryViewingKey {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the contract used to display preview metadata before a blind-box reveal
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `contract` - code hash and address of the preview contract
+/// * `enabled` - true if listing displays should use the preview contract
+fn try_set_preview<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    contract: ContractInfo,
+    enabled: bool,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let preview = PreviewContract {
+        contract: contract.into_store(&deps.api)?,
+        enabled,
+    };
+    save(&mut deps.storage, PREVIEW_CONTRACT_KEY, &preview)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetPreviewContract {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
 /// Returns HandleResult
 ///
 /// retrieves nfts sent from the wrong contract.  This can only be called on a contract that is NOT the nft
@@ -220,9 +653,7 @@ fn try_retrieve<S: Storage, A: Api, Q: Querier>(
     let contract =
         load::<StoreContractInfo, _>(&deps.storage, COLLECTION_KEY)?.into_humanized(&deps.api)?;
     if contract.address == nft_contract.address {
-        return Err(StdError::generic_err(
-            "This may not be called on the gumball contract's collection",
-        ));
+        return Err(ContractError::CollectionNotAllowed.into());
     }
     let transfers = vec![Transfer {
         recipient: env.message.sender,
@@ -247,19 +678,17 @@ fn try_retrieve<S: Storage, A: Api, Q: Querier>(
 
 /// Returns HandleResult
 ///
-/// adds/removes addresses to/from the whitelist
+/// sets the prefix that all token IDs must start with in order to be accepted by the gumball
 ///
 /// # Arguments
 ///
 /// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
 /// * `sender` - a reference to the message sender
-/// * `addresses` - list of whitelisted addresses
-/// * `is_add` - true if adding to the whitelist
-fn try_update_whitelist<S: Storage, A: Api, Q: Querier>(
+/// * `prefix` - optional required token ID prefix.  None disables the filter
+fn try_set_token_prefix<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     sender: &HumanAddr,
-    addresses: &[HumanAddr],
-    is_add: bool,
+    prefix: Option<String>,
 ) -> HandleResult {
     // only allow admins to do this
     let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
@@ -267,597 +696,5530 @@ fn try_update_whitelist<S: Storage, A: Api, Q: Querier>(
     if !admins.contains(&sender_raw) {
         return Err(StdError::unauthorized());
     }
-    let mut white_store = PrefixedStorage::new(PREFIX_WHITELIST, &mut deps.storage);
-    for addr in addresses.iter() {
-        let raw = deps.api.canonical_address(addr)?;
-        if is_add {
-            save(&mut white_store, raw.as_slice(), &true)?;
-        } else {
-            remove(&mut white_store, raw.as_slice());
-        }
-    }
-    let status = "success".to_string();
-    let resp = if is_add {
-        HandleAnswer::AddToWhitelist { status }
+    if let Some(pfx) = prefix {
+        save(&mut deps.storage, TOKEN_PREFIX_KEY, &pfx)?;
     } else {
-        HandleAnswer::RemoveFromWhitelist { status }
-    };
+        remove(&mut deps.storage, TOKEN_PREFIX_KEY);
+    }
     Ok(HandleResponse {
         messages: vec![],
         log: vec![],
-        data: Some(to_binary(&resp)?),
+        data: Some(to_binary(&HandleAnswer::SetAcceptedTokenPrefix {
+            status: "success".to_string(),
+        })?),
     })
 }
 
 /// Returns HandleResult
 ///
-/// registers a listing address as a valid address to request minting
+/// sets the level of minting statistics to record
 ///
 /// # Arguments
 ///
 /// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
 /// * `sender` - a reference to the message sender
-/// * `listing_address` - a reference to the address of the listing this contract just created
-fn try_register_listing<S: Storage, A: Api, Q: Querier>(
+/// * `level` - the level of statistics to record
+fn try_set_stats_level<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     sender: &HumanAddr,
-    listing_address: &HumanAddr,
+    level: StatsLevel,
 ) -> HandleResult {
-    let factory: HumanAddr = may_load(&deps.storage, EXPECTED_KEY)?.ok_or_else(|| {
-        StdError::generic_err("RegisterListing can only be called by the expected factory contract")
-    })?;
-    if *sender != factory {
-        return Err(StdError::generic_err(
-            "Message sender does not match the expected factory address",
-        ));
-    }
-    let mut reg_store = PrefixedStorage::new(PREFIX_LIST_REGISTRY, &mut deps.storage);
-    let list_raw = deps.api.canonical_address(listing_address)?;
-    save(&mut reg_store, list_raw.as_slice(), &true)?;
-    remove(&mut deps.storage, EXPECTED_KEY);
-    Ok(HandleResponse::default())
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save(&mut deps.storage, STATS_LEVEL_KEY, &level)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetStatsResolution {
+            status: "success".to_string(),
+        })?),
+    })
 }
 
 /// Returns HandleResult
 ///
-/// handles receiving an NFT to place in the gumball machine
+/// sets the maximum number of addresses that can be in a single whitelist update
 ///
 /// # Arguments
 ///
-/// * `deps` - mutable reference to Extern containing all the contract's external dependencies
-/// * `sender` - a reference to the message sender's address
-/// * `from` - a reference to the address that owned the NFT
-/// * `token_ids` - list of tokens sent
-fn try_batch_receive<S: Storage, A: Api, Q: Querier>(
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `max` - maximum number of addresses per whitelist update
+fn try_set_wl_limit<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     sender: &HumanAddr,
-    from: &HumanAddr,
-    mut token_ids: Vec<String>,
+    max: u32,
 ) -> HandleResult {
-    let contract =
-        load::<StoreContractInfo, _>(&deps.storage, COLLECTION_KEY)?.into_humanized(&deps.api)?;
-    // don't let someone spoof sending the gumball tokens
-    if *sender != contract.address {
-        return Err(StdError::generic_err(
-            "Only the collection contract specified on instantiation may call (Batch)ReceiveNft",
-        ));
-    }
+    // only allow admins to do this
     let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
-    let from_raw = deps.api.canonical_address(from)?;
-    // only allow an admin to add tokens to the gumball
-    if !admins.contains(&from_raw) {
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
         return Err(StdError::unauthorized());
     }
-    // 721 contracts should not be doing a Send if there are no tokens sent, but you never know
-    // what people will code
-    if !token_ids.is_empty() {
-        let mut counts: Counts = load(&deps.storage, COUNT_KEY)?;
-        // use the public info of the first NFT added to an empty gumball machine
-        let save_example = counts.available == 0;
-        let mut id_store = PrefixedStorage::new(PREFIX_TOKEN_IDS, &mut deps.storage);
-        for id in token_ids.iter() {
-            save(&mut id_store, &counts.available.to_le_bytes(), id)?;
-            counts.available = counts.available.checked_add(1).ok_or_else(|| {
-                StdError::generic_err("Gumball contract has reached its maximum number of NFTs")
-            })?;
-        }
-        save(&mut deps.storage, COUNT_KEY, &counts)?;
-        // if the gumball machine was empty
-        if save_example {
-            // query the first token's info
-            let nft_qry = Snip721QueryMsg::NftDossier {
-                token_id: token_ids.swap_remove(0),
-            };
-            let resp: StdResult<NftDossierResponse> =
-                nft_qry.query(&deps.querier, contract.code_hash, contract.address);
-            let nft_doss = resp.map_or(
-                NftDossierForListing {
-                    public_metadata: None,
-                    royalty_info: None,
-                    mint_run_info: None,
-                },
-                |r| r.nft_dossier,
-            );
-            let store_doss = nft_doss.into_stored(&deps.api)?;
-            save(&mut deps.storage, EXAMPLE_KEY, &store_doss)?;
-        }
-    }
-    Ok(HandleResponse::default())
+    save(&mut deps.storage, BULK_WL_LIMIT_KEY, &max)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetMaxBatchWhitelistUpdate {
+            status: "success".to_string(),
+        })?),
+    })
 }
 
 /// Returns HandleResult
 ///
-/// call the factory to create a listing
+/// sets the randomness oracle contract
 ///
 /// # Arguments
 ///
 /// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
-/// * `env` - the Env of contract's environment
-/// * `label` - the String label of the listing to create
-/// * `payment_address` - optional payment address if different than the creator
-/// * `factory_contract` - code hash and address of the factory
-/// * `buy_contract` - ContractInfo of the purchasing token
-/// * `batch_send` - true if the purchasing token implements batch send
-/// * `price` - listing price
-/// * `closes_at` - seconds since 01/01/1970 in which the listing can be closed by the operator
-/// * `description` - optional text description of the listing
-/// * `entropy` - String used for entropy when generating viewing keys
-#[allow(clippy::too_many_arguments)]
-fn try_create_listing<S: Storage, A: Api, Q: Querier>(
+/// * `sender` - a reference to the message sender
+/// * `contract` - code hash and address of the oracle contract
+fn try_set_oracle<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
-    env: Env,
-    label: String,
-    payment_address: Option<HumanAddr>,
-    factory_contract: ContractInfo,
-    buy_contract: ContractInfo,
-    batch_send: bool,
-    price: Uint128,
-    closes_at: u64,
-    description: Option<String>,
-    entropy: String,
+    sender: &HumanAddr,
+    contract: ContractInfo,
 ) -> HandleResult {
     // only allow admins to do this
     let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
-    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
     if !admins.contains(&sender_raw) {
         return Err(StdError::unauthorized());
     }
-    let contract =
-        load::<StoreContractInfo, _>(&deps.storage, COLLECTION_KEY)?.into_humanized(&deps.api)?;
-    save(&mut deps.storage, EXPECTED_KEY, &factory_contract.address)?;
-    let minter_contract = ContractInfo {
-        address: env.contract.address,
-        code_hash: env.contract_code_hash,
-    };
-    let quantity_for_sale = load::<Counts, _>(&deps.storage, COUNT_KEY)?.available;
-    let factory_msg = FactoryHandleMsg::CreateMinterListing {
-        label,
-        creator: env.message.sender,
-        payment_address,
-        quantity_for_sale,
-        minter_contract,
-        option_id: "Gumball".to_string(),
-        buy_contract,
-        batch_send,
-        price,
-        closes_at,
-        description,
-        entropy,
-        nft_contract_address: contract.address,
-        implements_register_listing: true,
-    };
-
+    save(
+        &mut deps.storage,
+        RANDOMNESS_ORACLE_KEY,
+        &contract.into_store(&deps.api)?,
+    )?;
     Ok(HandleResponse {
-        messages: vec![factory_msg.to_cosmos_msg(
-            factory_contract.code_hash,
-            factory_contract.address,
-            None,
-        )?],
+        messages: vec![],
         log: vec![],
-        data: None,
+        data: Some(to_binary(&HandleAnswer::SetRandomnessOracle {
+            status: "success".to_string(),
+        })?),
     })
 }
 
-// type of address calling Mint
-pub enum MintCaller {
-    Listing,
-    Admin,
-    Whitelist,
-}
-
 /// Returns HandleResult
 ///
-/// release a random nft for each buyer
+/// saves randomness supplied by the oracle to be used in the next mint
 ///
 /// # Arguments
 ///
 /// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
-/// * `env` - a reference to the Env of contract's environment
-/// * `buyers` - the nft buyers
-/// * `entropy` - string slice used for entropy
-fn try_mint<S: Storage, A: Api, Q: Querier>(
+/// * `sender` - a reference to the message sender
+/// * `round` - oracle round that produced the randomness
+/// * `randomness` - the random bytes
+fn try_receive_randomness<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
-    env: &Env,
-    buyers: Vec<HumanAddr>,
-    entropy: &str,
+    sender: &HumanAddr,
+    round: u64,
+    randomness: [u8; 32],
 ) -> HandleResult {
-    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
-    let sender_slice = sender_raw.as_slice();
-    let reg_store = ReadonlyPrefixedStorage::new(PREFIX_LIST_REGISTRY, &deps.storage);
-    // check if the caller is a listing this contract created
-    let caller_type = if may_load::<bool, _>(&reg_store, sender_slice)?.is_none() {
-        // check if the caller is a whitelisted address for this template
-        let mut white_store = PrefixedStorage::new(PREFIX_WHITELIST, &mut deps.storage);
-        if may_load::<bool, _>(&white_store, sender_slice)?.is_none() {
-            // check if the caller is an admin
-            let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
-            if !admins.contains(&sender_raw) {
-                return Err(StdError::unauthorized());
-            } else {
-                MintCaller::Admin
-            }
-        } else {
-            // whitelist can only mint one
-            remove(&mut white_store, sender_slice);
-            MintCaller::Whitelist
-        }
-    } else {
-        // listing called
-        MintCaller::Listing
-    };
-    let mint_cnt = buyers.len() as u32;
-    if let MintCaller::Whitelist = caller_type {
-        if mint_cnt != 1 {
-            // whitelisted address must mint exactly 1
-            return Err(StdError::generic_err(
-                "Whitelisted addresses must mint exactly 1 token",
-            ));
-        }
-    }
-    let mut counts: Counts = load(&deps.storage, COUNT_KEY)?;
-    if mint_cnt > counts.available {
-        return Err(StdError::generic_err(format!(
-            "Trying to mint {} tokens, but only {} are available",
-            mint_cnt, counts.available
-        )));
-    }
-    let mut prng_seed: Vec<u8> = load(&deps.storage, PRNG_SEED_KEY)?;
-    let rng_entropy = extend_entropy(env, entropy.as_bytes());
-    let mut rng = Prng::new(&prng_seed, &rng_entropy);
-    let mut transfers: Vec<Transfer> = Vec::new();
-    let mut distributed: Vec<String> = Vec::new();
-    let mut id_store = PrefixedStorage::new(PREFIX_TOKEN_IDS, &mut deps.storage);
-    // transfer an nft to each buyer
-    for buyer in buyers.into_iter() {
-        // draw the winning token
-        let winner = rng.next_u64() % (counts.available as u64);
-        let winner_key = (winner as u32).to_le_bytes();
-        let winner_id: String = may_load(&id_store, &winner_key)?
-            .ok_or_else(|| StdError::generic_err("Token ID pool is corrupt"))?;
-        distributed.push(winner_id.clone());
-        if let Some(xfer) = transfers.iter_mut().find(|t| t.recipient == buyer) {
-            // if this address is already getting tokens, just add this id to its list
-            xfer.token_ids.push(winner_id);
-        } else {
-            // first one this address is getting
-            let memo = if let MintCaller::Listing = caller_type {
-                format!("Purchased from listing {}", &env.message.sender)
-            } else {
-                format!(
-                    "Distributed from gumball contract {}",
-                    &env.contract.address
-                )
-            };
-            transfers.push(Transfer {
-                recipient: buyer,
-                token_ids: vec![winner_id],
-                memo,
-            });
-        }
-        let last_idx = counts.available - 1;
-        let last_key = last_idx.to_le_bytes();
-        // swap_remove if the winner is not at the end
-        if winner != last_idx as u64 {
-            let last: String = may_load(&id_store, &last_key)?
-                .ok_or_else(|| StdError::generic_err("Token ID pool is corrupt"))?;
-            save(&mut id_store, &winner_key, &last)?;
-        }
-        remove(&mut id_store, &last_key);
-        counts.available = counts.available.saturating_sub(1);
-        counts.released = counts.released.saturating_add(1);
+    let oracle: StoreContractInfo = may_load(&deps.storage, RANDOMNESS_ORACLE_KEY)?
+        .ok_or_else(|| StdError::from(ContractError::NoOracle))?;
+    // only the registered oracle may supply randomness
+    if deps.api.canonical_address(sender)? != oracle.address {
+        return Err(StdError::unauthorized());
     }
-    save(&mut deps.storage, COUNT_KEY, &counts)?;
-    prng_seed = rng.rand_bytes().to_vec();
-    save(&mut deps.storage, PRNG_SEED_KEY, &prng_seed)?;
-
-    let stored: StoreContractInfo = load(&deps.storage, COLLECTION_KEY)?;
-    let contract = stored.into_humanized(&deps.api)?;
-    let messages = vec![
-        Snip721HandleMsg::BatchTransferNft { transfers }.to_cosmos_msg(
-            contract.code_hash,
-            contract.address,
-            None,
-        )?,
-    ];
+    save(
+        &mut deps.storage,
+        ORACLE_RANDOMNESS_KEY,
+        &OracleRandomness { round, randomness },
+    )?;
     Ok(HandleResponse {
-        messages,
-        log: vec![log("distributed", format!("{:?}", &distributed))],
-        data: None,
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::ReceiveOracleRandomness {
+            status: "success".to_string(),
+        })?),
     })
 }
 
 /// Returns HandleResult
 ///
-/// remove a list of admins from the list
+/// adds/removes a token ID to/from the blacklist
 ///
 /// # Arguments
 ///
 /// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
 /// * `sender` - a reference to the message sender
-/// * `admins_to_remove` - list of admin addresses to remove
-fn try_remove_admins<S: Storage, A: Api, Q: Querier>(
+/// * `token_id` - the token ID to update
+/// * `is_add` - true if adding to the blacklist
+fn try_update_blacklist<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     sender: &HumanAddr,
-    admins_to_remove: Vec<HumanAddr>,
+    token_id: &str,
+    is_add: bool,
 ) -> HandleResult {
     // only allow admins to do this
-    let mut admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
     let sender_raw = deps.api.canonical_address(sender)?;
     if !admins.contains(&sender_raw) {
         return Err(StdError::unauthorized());
     }
-    let old_len = admins.len();
-    let rem_list = admins_to_remove
-        .iter()
-        .map(|a| deps.api.canonical_address(a))
-        .collect::<StdResult<Vec<CanonicalAddr>>>()?;
-    admins.retain(|a| !rem_list.contains(a));
-    // only save if the list changed
-    if old_len != admins.len() {
-        save(&mut deps.storage, ADMINS_KEY, &admins)?;
-    }
+    let mut black_store = PrefixedStorage::new(PREFIX_TOKEN_BLACKLIST, &mut deps.storage);
+    let status = "success".to_string();
+    let resp = if is_add {
+        save(&mut black_store, token_id.as_bytes(), &true)?;
+        HandleAnswer::BlacklistToken { status }
+    } else {
+        remove(&mut black_store, token_id.as_bytes());
+        HandleAnswer::UnblacklistToken { status }
+    };
     Ok(HandleResponse {
         messages: vec![],
         log: vec![],
-        data: Some(to_binary(&HandleAnswer::AdminsList {
-            admins: admins
-                .iter()
-                .map(|a| deps.api.human_address(a))
-                .collect::<StdResult<Vec<HumanAddr>>>()?,
-        })?),
+        data: Some(to_binary(&resp)?),
     })
 }
 
 /// Returns HandleResult
 ///
-/// adds a list of admins to the list
+/// adds/removes addresses to/from the whitelist
 ///
 /// # Arguments
 ///
 /// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
 /// * `sender` - a reference to the message sender
-/// * `admins_to_add` - list of admin addresses to add
-fn try_add_admins<S: Storage, A: Api, Q: Querier>(
+/// * `addresses` - list of whitelisted addresses
+/// * `is_add` - true if adding to the whitelist
+/// * `quantity` - number of free mints each added address may receive
+/// * `round_id` - optional round whose whitelist is updated instead of the global whitelist
+/// * `expires_at` - optional time after which global whitelist entries can no longer be used
+fn try_update_whitelist<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     sender: &HumanAddr,
-    admins_to_add: Vec<HumanAddr>,
+    addresses: &[HumanAddr],
+    is_add: bool,
+    quantity: u32,
+    round_id: Option<u32>,
+    expires_at: Option<u64>,
 ) -> HandleResult {
     // only allow admins to do this
-    let mut admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
     let sender_raw = deps.api.canonical_address(sender)?;
     if !admins.contains(&sender_raw) {
         return Err(StdError::unauthorized());
     }
-    let mut save_it = false;
-    for admin in admins_to_add.iter() {
-        let raw = deps.api.canonical_address(admin)?;
-        if !admins.contains(&raw) {
-            admins.push(raw);
-            save_it = true;
+    let max_batch: u32 =
+        may_load(&deps.storage, BULK_WL_LIMIT_KEY)?.unwrap_or(DEFAULT_BULK_WL_LIMIT);
+    if addresses.len() > max_batch as usize {
+        return Err(ContractError::WhitelistBatchTooLarge {
+            max: max_batch,
+            sent: addresses.len() as u32,
         }
+        .into());
     }
-    // only save if the list changed
-    if save_it {
-        save(&mut deps.storage, ADMINS_KEY, &admins)?;
+    if let Some(id) = round_id {
+        let round_cnt: u32 = may_load(&deps.storage, ROUND_COUNT_KEY)?.unwrap_or(0);
+        if id >= round_cnt {
+            return Err(ContractError::RoundNotFound(id).into());
+        }
+    }
+    let remaining = if is_add { quantity } else { 0 };
+    for addr in addresses.iter() {
+        let raw = deps.api.canonical_address(addr)?;
+        let round_key = round_id.map(|id| [&id.to_le_bytes()[..], raw.as_slice()].concat());
+        save_whitelist_quantity(
+            &mut deps.storage,
+            round_key.as_deref(),
+            &raw,
+            remaining,
+            expires_at,
+        )?;
     }
+    let status = "success".to_string();
+    let resp = if is_add {
+        HandleAnswer::AddToWhitelist { status }
+    } else {
+        HandleAnswer::RemoveFromWhitelist { status }
+    };
     Ok(HandleResponse {
         messages: vec![],
         log: vec![],
-        data: Some(to_binary(&HandleAnswer::AdminsList {
-            admins: admins
-                .iter()
-                .map(|a| deps.api.human_address(a))
-                .collect::<StdResult<Vec<HumanAddr>>>()?,
-        })?),
+        data: Some(to_binary(&resp)?),
     })
 }
 
 /// Returns HandleResult
 ///
-/// creates a viewing key
+/// registers a listing address as a valid address to request minting
 ///
 /// # Arguments
 ///
-/// * `deps` - mutable reference to Extern containing all the contract's external dependencies
-/// * `env` - a reference to the Env of contract's environment
-/// * `entropy` - string slice of the input String to be used as entropy in randomization
-fn try_create_key<S: Storage, A: Api, Q: Querier>(
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `listing_address` - a reference to the address of the listing this contract just created
+fn try_register_listing<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
-    env: &Env,
-    entropy: &str,
+    sender: &HumanAddr,
+    listing_address: &HumanAddr,
 ) -> HandleResult {
-    let prng_seed: Vec<u8> = load(&deps.storage, PRNG_SEED_KEY)?;
-    let key = ViewingKey::new(env, &prng_seed, entropy.as_ref());
-    let message_sender = &deps.api.canonical_address(&env.message.sender)?;
-    let mut key_store = PrefixedStorage::new(PREFIX_VIEW_KEY, &mut deps.storage);
-    save(&mut key_store, message_sender.as_slice(), &key.to_hashed())?;
-    Ok(HandleResponse {
-        messages: vec![],
-        log: vec![],
-        data: Some(to_binary(&HandleAnswer::ViewingKey { key: key.0 })?),
-    })
+    let factory_raw = deps.api.canonical_address(sender)?;
+    let exp_store = ReadonlyPrefixedStorage::new(PREFIX_EXPECTED_FACTORIES, &deps.storage);
+    let expected: ExpectedListing = may_load(&exp_store, factory_raw.as_slice())?
+        .ok_or_else(|| StdError::from(ContractError::UnexpectedFactory))?;
+    let fact_store = ReadonlyPrefixedStorage::new(PREFIX_FACTORY_LISTING_COUNT, &deps.storage);
+    let fact_count: u32 = may_load(&fact_store, factory_raw.as_slice())?.unwrap_or(0);
+    if let Some(max) = may_load::<u32, _>(&deps.storage, MAX_PER_FACTORY_KEY)? {
+        if fact_count >= max {
+            return Err(ContractError::FactoryListingLimit { max }.into());
+        }
+    }
+    let listing = StoredListing {
+        address: deps.api.canonical_address(listing_address)?,
+        label: expected.label,
+        closes_at: expected.closes_at,
+        factory: Some(factory_raw.clone()),
+    };
+    if add_listing(&mut deps.storage, listing)? {
+        let mut fact_store = PrefixedStorage::new(PREFIX_FACTORY_LISTING_COUNT, &mut deps.storage);
+        save(&mut fact_store, factory_raw.as_slice(), &(fact_count + 1))?;
+    }
+    let mut exp_store = PrefixedStorage::new(PREFIX_EXPECTED_FACTORIES, &mut deps.storage);
+    remove(&mut exp_store, factory_raw.as_slice());
+    Ok(HandleResponse::default())
 }
 
 /// Returns HandleResult
 ///
-/// sets the viewing key to the input String
+/// manually registers a listing address when the factory callback was lost
 ///
 /// # Arguments
 ///
-/// * `deps` - mutable reference to Extern containing all the contract's external dependencies
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
 /// * `sender` - a reference to the message sender
-/// * `key` - String to be used as the viewing key
-fn try_set_key<S: Storage, A: Api, Q: Querier>(
+/// * `listing_address` - a reference to the address of the listing to register
+/// * `factory` - optional address of the factory whose registration was lost
+fn try_forward_registration<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     sender: &HumanAddr,
-    key: String,
+    listing_address: &HumanAddr,
+    factory: Option<&HumanAddr>,
 ) -> HandleResult {
-    let vk = ViewingKey(key.clone());
-    let message_sender = &deps.api.canonical_address(sender)?;
-    let mut key_store = PrefixedStorage::new(PREFIX_VIEW_KEY, &mut deps.storage);
-    save(&mut key_store, message_sender.as_slice(), &vk.to_hashed())?;
-
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    // use the info of the listing the factory was expected to register if there is one
+    let factory_raw = factory.map(|f| deps.api.canonical_address(f)).transpose()?;
+    let expected: Option<ExpectedListing> = if let Some(raw) = factory_raw.as_ref() {
+        let mut exp_store = PrefixedStorage::new(PREFIX_EXPECTED_FACTORIES, &mut deps.storage);
+        let expected = may_load(&exp_store, raw.as_slice())?;
+        remove(&mut exp_store, raw.as_slice());
+        expected
+    } else {
+        None
+    };
+    let listing = StoredListing {
+        address: deps.api.canonical_address(listing_address)?,
+        label: expected.as_ref().and_then(|e| e.label.clone()),
+        closes_at: expected.and_then(|e| e.closes_at),
+        factory: None,
+    };
+    add_listing(&mut deps.storage, listing)?;
     Ok(HandleResponse {
         messages: vec![],
-        log: vec![],
-        data: Some(to_binary(&HandleAnswer::ViewingKey { key })?),
+        log: vec![log("manual_listing_registration", "true")],
+        data: None,
     })
 }
 
-/// Returns HandleResult
+/// Returns StdResult<bool>
 ///
-/// revoke the ability to use a specified permit
+/// adds a listing to the registry and appends it to the ordered registry index.  A listing that
+/// is already registered is left unchanged.  Returns true if the listing was newly registered
 ///
 /// # Arguments
 ///
-/// * `storage` - mutable reference to the contract's storage
-/// * `sender` - a reference to the message sender
-/// * `permit_name` - string slice of the name of the permit to revoke
-fn revoke_permit<S: Storage>(
-    storage: &mut S,
-    sender: &HumanAddr,
-    permit_name: &str,
-) -> HandleResult {
-    RevokedPermits::revoke_permit(storage, PREFIX_REVOKED_PERMITS, sender, permit_name);
-
-    Ok(HandleResponse {
-        messages: vec![],
-        log: vec![],
-        data: Some(to_binary(&HandleAnswer::RevokePermit {
-            status: "success".to_string(),
-        })?),
-    })
+/// * `storage` - a mutable reference to the contract's storage
+/// * `listing` - the listing to register
+fn add_listing<S: Storage>(storage: &mut S, listing: StoredListing) -> StdResult<bool> {
+    let reg_store = ReadonlyPrefixedStorage::new(PREFIX_LIST_REGISTRY, storage);
+    if may_load::<u32, _>(&reg_store, listing.address.as_slice())?.is_some() {
+        return Ok(false);
+    }
+    let count: u32 = may_load(storage, LIST_COUNT_KEY)?.unwrap_or(0);
+    let mut reg_store = PrefixedStorage::new(PREFIX_LIST_REGISTRY, storage);
+    // the registry maps the listing address to its position in the index
+    save(&mut reg_store, listing.address.as_slice(), &count)?;
+    let mut idx_store = PrefixedStorage::new(PREFIX_LIST_REGISTRY_INDEX, storage);
+    save(&mut idx_store, &count.to_le_bytes(), &listing)?;
+    let new_count = count
+        .checked_add(1)
+        .ok_or_else(|| StdError::from(ContractError::ListingRegistryFull))?;
+    save(storage, LIST_COUNT_KEY, &new_count)?;
+    Ok(true)
 }
 
-/////////////////////////////////////// Query /////////////////////////////////////
-/// Returns QueryResult
+/// Returns StdResult<()>
+///
+/// removes the token at the specified pool index by moving the last token in the pool into its
+/// place, keeping all per-index storage in sync
 ///
 /// # Arguments
 ///
-/// * `deps` - reference to Extern containing all the contract's external dependencies
-/// * `msg` - QueryMsg passed in with the query call
-pub fn query<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>, msg: QueryMsg) -> QueryResult {
-    let response = match msg {
-        QueryMsg::Admins { viewer, permit } => query_admins(deps, viewer, permit),
-        QueryMsg::NftListingDisplay {} => query_listing_disp(deps),
-        QueryMsg::Counts {} => query_counts(&deps.storage),
-        QueryMsg::NftContract {} => query_nft_contract(deps),
+/// * `storage` - a mutable reference to the contract's storage
+/// * `idx` - pool index of the token to remove
+/// * `last_idx` - pool index of the last token in the pool
+fn swap_remove_token<S: Storage>(storage: &mut S, idx: u32, last_idx: u32) -> StdResult<()> {
+    let idx_key = idx.to_le_bytes();
+    let last_key = last_idx.to_le_bytes();
+    let mut id_store = PrefixedStorage::new(PREFIX_TOKEN_IDS, storage);
+    let removed: (String, u8) =
+        may_load(&id_store, &idx_key)?.ok_or_else(|| StdError::from(ContractError::PoolCorrupt))?;
+    // swap_remove if the token is not at the end
+    let moved = if idx != last_idx {
+        let last: (String, u8) = may_load(&id_store, &last_key)?
+            .ok_or_else(|| StdError::from(ContractError::PoolCorrupt))?;
+        save(&mut id_store, &idx_key, &last)?;
+        Some(last)
+    } else {
+        None
     };
-    pad_query_result(response, BLOCK_SIZE)
+    remove(&mut id_store, &last_key);
+    let mut meta_store = PrefixedStorage::new(PREFIX_TOKEN_METADATA, storage);
+    remove(&mut meta_store, removed.0.as_bytes());
+    let mut rev_store = PrefixedStorage::new(PREFIX_TOKEN_REVERSE, storage);
+    remove(&mut rev_store, removed.0.as_bytes());
+    if let Some(last) = moved.as_ref() {
+        save(&mut rev_store, last.0.as_bytes(), &idx)?;
+    }
+    unindex_tier(storage, removed.1, idx)?;
+    if let Some(last) = moved.as_ref() {
+        reindex_tier(storage, last.1, last_idx, idx)?;
+    }
+    let mut memo_store = PrefixedStorage::new(PREFIX_TOKEN_MEMO, storage);
+    remove(&mut memo_store, &idx_key);
+    if moved.is_some() {
+        if let Some(memo) = may_load::<String, _>(&memo_store, &last_key)? {
+            save(&mut memo_store, &idx_key, &memo)?;
+        }
+        remove(&mut memo_store, &last_key);
+    }
+    let mut tag_store = PrefixedStorage::new(PREFIX_TOKEN_TAG, storage);
+    remove(&mut tag_store, &idx_key);
+    if moved.is_some() {
+        if let Some(tag) = may_load::<String, _>(&tag_store, &last_key)? {
+            save(&mut tag_store, &idx_key, &tag)?;
+        }
+        remove(&mut tag_store, &last_key);
+    }
+    let mut batch_store = PrefixedStorage::new(PREFIX_BATCH_TAG, storage);
+    remove(&mut batch_store, &idx_key);
+    if moved.is_some() {
+        if let Some(batch) = may_load::<u8, _>(&batch_store, &last_key)? {
+            save(&mut batch_store, &idx_key, &batch)?;
+        }
+        remove(&mut batch_store, &last_key);
+    }
+    let mut dep_store = PrefixedStorage::new(PREFIX_TOKEN_DEPOSITOR, storage);
+    remove(&mut dep_store, &idx_key);
+    if moved.is_some() {
+        if let Some(depositor) = may_load::<CanonicalAddr, _>(&dep_store, &last_key)? {
+            save(&mut dep_store, &idx_key, &depositor)?;
+        }
+        remove(&mut dep_store, &last_key);
+    }
+    Ok(())
 }
 
-/// Returns QueryResult displaying the number of NFTs available and the number of NFTs released
+/// Returns StdResult<()>
+///
+/// removes the token at the specified pool index by shifting every earlier token up one index and
+/// advancing the head of the pool, preserving the deposit order and keeping all per-index storage
+/// in sync.  Removing the token at the head only advances the head
 ///
 /// # Arguments
 ///
-/// * `storage` - a reference to the contract's storage
-fn query_counts<S: ReadonlyStorage>(storage: &S) -> QueryResult {
-    let counts: Counts = load(storage, COUNT_KEY)?;
-
+/// * `storage` - a mutable reference to the contract's storage
+/// * `idx` - pool index of the token to remove
+/// * `last_idx` - pool index of the last token in the pool
+fn shift_remove_token<S: Storage>(storage: &mut S, idx: u32, last_idx: u32) -> StdResult<()> {
+    let head = load_pool_head(storage)?;
+    let mut id_store = PrefixedStorage::new(PREFIX_TOKEN_IDS, storage);
+    let removed: (String, u8) = may_load(&id_store, &idx.to_le_bytes())?
+        .ok_or_else(|| StdError::from(ContractError::PoolCorrupt))?;
+    // tokens moved up one index, nearest the removed token first
+    let mut moved: Vec<(String, u8)> = Vec::new();
+    for i in (head..idx).rev() {
+        let prev: (String, u8) = may_load(&id_store, &i.to_le_bytes())?
+            .ok_or_else(|| StdError::from(ContractError::PoolCorrupt))?;
+        save(&mut id_store, &(i + 1).to_le_bytes(), &prev)?;
+        moved.push(prev);
+    }
+    remove(&mut id_store, &head.to_le_bytes());
+    let mut meta_store = PrefixedStorage::new(PREFIX_TOKEN_METADATA, storage);
+    remove(&mut meta_store, removed.0.as_bytes());
+    let mut rev_store = PrefixedStorage::new(PREFIX_TOKEN_REVERSE, storage);
+    remove(&mut rev_store, removed.0.as_bytes());
+    for (i, tkn) in (head..idx).rev().zip(moved.iter()) {
+        save(&mut rev_store, tkn.0.as_bytes(), &(i + 1))?;
+    }
+    unindex_tier(storage, removed.1, idx)?;
+    for (i, tkn) in (head..idx).rev().zip(moved.iter()) {
+        reindex_tier(storage, tkn.1, i, i + 1)?;
+    }
+    let mut memo_store = PrefixedStorage::new(PREFIX_TOKEN_MEMO, storage);
+    for i in (head..idx).rev() {
+        if let Some(memo) = may_load::<String, _>(&memo_store, &i.to_le_bytes())? {
+            save(&mut memo_store, &(i + 1).to_le_bytes(), &memo)?;
+        } else {
+            remove(&mut memo_store, &(i + 1).to_le_bytes());
+        }
+    }
+    remove(&mut memo_store, &head.to_le_bytes());
+    let mut tag_store = PrefixedStorage::new(PREFIX_TOKEN_TAG, storage);
+    for i in (head..idx).rev() {
+        if let Some(tag) = may_load::<String, _>(&tag_store, &i.to_le_bytes())? {
+            save(&mut tag_store, &(i + 1).to_le_bytes(), &tag)?;
+        } else {
+            remove(&mut tag_store, &(i + 1).to_le_bytes());
+        }
+    }
+    remove(&mut tag_store, &head.to_le_bytes());
+    let mut batch_store = PrefixedStorage::new(PREFIX_BATCH_TAG, storage);
+    for i in (head..idx).rev() {
+        if let Some(batch) = may_load::<u8, _>(&batch_store, &i.to_le_bytes())? {
+            save(&mut batch_store, &(i + 1).to_le_bytes(), &batch)?;
+        } else {
+            remove(&mut batch_store, &(i + 1).to_le_bytes());
+        }
+    }
+    remove(&mut batch_store, &head.to_le_bytes());
+    let mut dep_store = PrefixedStorage::new(PREFIX_TOKEN_DEPOSITOR, storage);
+    for i in (head..idx).rev() {
+        if let Some(depositor) = may_load::<CanonicalAddr, _>(&dep_store, &i.to_le_bytes())? {
+            save(&mut dep_store, &(i + 1).to_le_bytes(), &depositor)?;
+        } else {
+            remove(&mut dep_store, &(i + 1).to_le_bytes());
+        }
+    }
+    remove(&mut dep_store, &head.to_le_bytes());
+    // an emptied pool starts back at index 0
+    if head == last_idx {
+        remove(storage, SEQ_HEAD_KEY);
+        Ok(())
+    } else {
+        save(storage, SEQ_HEAD_KEY, &(head + 1))
+    }
+}
+
+/// Returns StdResult<u32> of the pool index of the first token in the pool
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+fn load_pool_head<S: ReadonlyStorage>(storage: &S) -> StdResult<u32> {
+    Ok(may_load(storage, SEQ_HEAD_KEY)?.unwrap_or(0))
+}
+
+/// Returns StdResult<u32> of the pool index one past the last token in the pool
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+/// * `available` - number of tokens in the pool
+fn pool_end<S: ReadonlyStorage>(storage: &S, available: u32) -> StdResult<u32> {
+    load_pool_head(storage)?
+        .checked_add(available)
+        .ok_or_else(|| StdError::from(ContractError::PoolIndexFull))
+}
+
+/// Returns StdResult<()>
+///
+/// returns a reserved token and its per-token info to the end of the pool
+///
+/// # Arguments
+///
+/// * `storage` - a mutable reference to the contract's storage
+/// * `reserved` - the ReservedToken to return
+fn unreserve_token<S: Storage>(storage: &mut S, reserved: ReservedToken) -> StdResult<()> {
+    let mut counts = load_counts(storage)?;
+    let idx = pool_end(storage, counts.available)?;
+    let mut id_store = PrefixedStorage::new(PREFIX_TOKEN_IDS, storage);
+    save(
+        &mut id_store,
+        &idx.to_le_bytes(),
+        &(reserved.token_id.clone(), reserved.tier),
+    )?;
+    let mut rev_store = PrefixedStorage::new(PREFIX_TOKEN_REVERSE, storage);
+    save(&mut rev_store, reserved.token_id.as_bytes(), &idx)?;
+    index_tier(storage, reserved.tier, idx)?;
+    let idx_key = idx.to_le_bytes();
+    if let Some(memo) = reserved.memo.as_ref() {
+        let mut memo_store = PrefixedStorage::new(PREFIX_TOKEN_MEMO, storage);
+        save(&mut memo_store, &idx_key, memo)?;
+    }
+    if let Some(tag) = reserved.tag.as_ref() {
+        let mut tag_store = PrefixedStorage::new(PREFIX_TOKEN_TAG, storage);
+        save(&mut tag_store, &idx_key, tag)?;
+    }
+    if let Some(batch) = reserved.batch.as_ref() {
+        let mut batch_store = PrefixedStorage::new(PREFIX_BATCH_TAG, storage);
+        save(&mut batch_store, &idx_key, batch)?;
+    }
+    if let Some(depositor) = reserved.depositor.as_ref() {
+        let mut dep_store = PrefixedStorage::new(PREFIX_TOKEN_DEPOSITOR, storage);
+        save(&mut dep_store, &idx_key, depositor)?;
+    }
+    if let Some(metadata) = reserved.metadata.as_ref() {
+        let mut meta_store = PrefixedStorage::new(PREFIX_TOKEN_METADATA, storage);
+        save(&mut meta_store, reserved.token_id.as_bytes(), metadata)?;
+    }
+    counts.available = counts
+        .available
+        .checked_add(1)
+        .ok_or_else(|| StdError::from(ContractError::PoolIndexFull))?;
+    save(storage, COUNT_KEY, &counts)
+}
+
+/// Returns StdResult<()>
+///
+/// removes a recipient's reservation and updates the count of outstanding reservations
+///
+/// # Arguments
+///
+/// * `storage` - a mutable reference to the contract's storage
+/// * `recipient` - a reference to the recipient whose reservation is removed
+fn release_reservation<S: Storage>(storage: &mut S, recipient: &CanonicalAddr) -> StdResult<()> {
+    let mut res_store = PrefixedStorage::new(PREFIX_RESERVED, storage);
+    remove(&mut res_store, recipient.as_slice());
+    let reserved: u32 = may_load(storage, RESERVED_COUNT_KEY)?.unwrap_or(0);
+    save(storage, RESERVED_COUNT_KEY, &reserved.saturating_sub(1))
+}
+
+/// Returns Vec<u8> of the key of a position in a rarity tier's index
+///
+/// # Arguments
+///
+/// * `tier` - the rarity tier
+/// * `pos` - position in the tier's index
+fn tier_key(tier: u8, pos: u32) -> Vec<u8> {
+    [&[tier][..], &pos.to_le_bytes()[..]].concat()
+}
+
+/// Returns StdResult<u32> of the number of tokens in a rarity tier
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+/// * `tier` - the rarity tier
+fn load_tier_count<S: ReadonlyStorage>(storage: &S, tier: u8) -> StdResult<u32> {
+    let cnt_store = ReadonlyPrefixedStorage::new(PREFIX_TIER_COUNT, storage);
+    Ok(may_load(&cnt_store, &[tier])?.unwrap_or(0))
+}
+
+/// Returns StdResult<()>
+///
+/// appends a pool index to the index of its rarity tier
+///
+/// # Arguments
+///
+/// * `storage` - a mutable reference to the contract's storage
+/// * `tier` - the rarity tier of the token
+/// * `idx` - pool index of the token
+fn index_tier<S: Storage>(storage: &mut S, tier: u8, idx: u32) -> StdResult<()> {
+    let count = load_tier_count(storage, tier)?;
+    let mut tier_store = PrefixedStorage::new(PREFIX_TIER_INDEX, storage);
+    save(&mut tier_store, &tier_key(tier, count), &idx)?;
+    let mut pos_store = PrefixedStorage::new(PREFIX_TIER_POSITION, storage);
+    save(&mut pos_store, &idx.to_le_bytes(), &count)?;
+    let mut cnt_store = PrefixedStorage::new(PREFIX_TIER_COUNT, storage);
+    save(&mut cnt_store, &[tier], &count.saturating_add(1))
+}
+
+/// Returns StdResult<()>
+///
+/// swap-removes a pool index from the index of its rarity tier
+///
+/// # Arguments
+///
+/// * `storage` - a mutable reference to the contract's storage
+/// * `tier` - the rarity tier of the token
+/// * `idx` - pool index of the token being removed
+fn unindex_tier<S: Storage>(storage: &mut S, tier: u8, idx: u32) -> StdResult<()> {
+    let mut pos_store = PrefixedStorage::new(PREFIX_TIER_POSITION, storage);
+    let pos = if let Some(p) = may_load::<u32, _>(&pos_store, &idx.to_le_bytes())? {
+        p
+    } else {
+        return Ok(());
+    };
+    remove(&mut pos_store, &idx.to_le_bytes());
+    let last_pos = load_tier_count(storage, tier)?.saturating_sub(1);
+    let mut tier_store = PrefixedStorage::new(PREFIX_TIER_INDEX, storage);
+    let moved: Option<u32> = if pos != last_pos {
+        let last: Option<u32> = may_load(&tier_store, &tier_key(tier, last_pos))?;
+        if let Some(l) = last {
+            save(&mut tier_store, &tier_key(tier, pos), &l)?;
+        }
+        last
+    } else {
+        None
+    };
+    remove(&mut tier_store, &tier_key(tier, last_pos));
+    if let Some(l) = moved {
+        let mut pos_store = PrefixedStorage::new(PREFIX_TIER_POSITION, storage);
+        save(&mut pos_store, &l.to_le_bytes(), &pos)?;
+    }
+    let mut cnt_store = PrefixedStorage::new(PREFIX_TIER_COUNT, storage);
+    save(&mut cnt_store, &[tier], &last_pos)
+}
+
+/// Returns StdResult<()>
+///
+/// updates a rarity tier's index after a token moves to a different pool index
+///
+/// # Arguments
+///
+/// * `storage` - a mutable reference to the contract's storage
+/// * `tier` - the rarity tier of the token
+/// * `from` - pool index the token moved from
+/// * `to` - pool index the token moved to
+fn reindex_tier<S: Storage>(storage: &mut S, tier: u8, from: u32, to: u32) -> StdResult<()> {
+    let mut pos_store = PrefixedStorage::new(PREFIX_TIER_POSITION, storage);
+    let pos: u32 = may_load(&pos_store, &from.to_le_bytes())?
+        .ok_or_else(|| StdError::from(ContractError::PoolCorrupt))?;
+    remove(&mut pos_store, &from.to_le_bytes());
+    save(&mut pos_store, &to.to_le_bytes(), &pos)?;
+    let mut tier_store = PrefixedStorage::new(PREFIX_TIER_INDEX, storage);
+    save(&mut tier_store, &tier_key(tier, pos), &to)
+}
+
+/// Returns StdResult<Option<Vec<u32>>>
+///
+/// loads the selection weight of every batch, indexed by batch ID.  Returns None if no batch
+/// weights have been set
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+fn load_batch_weights<S: ReadonlyStorage>(storage: &S) -> StdResult<Option<Vec<u32>>> {
+    let current: u8 = may_load(storage, CURRENT_BATCH_ID_KEY)?.unwrap_or(0);
+    let weight_store = ReadonlyPrefixedStorage::new(PREFIX_BATCH_WEIGHT, storage);
+    let mut any_set = false;
+    let mut weights: Vec<u32> = Vec::new();
+    for batch in 0..=current {
+        // batches without a weight default to 1
+        let weight = may_load::<u32, _>(&weight_store, &[batch])?.map_or(1, |w| {
+            any_set = true;
+            w
+        });
+        weights.push(weight);
+    }
+    Ok(if any_set { Some(weights) } else { None })
+}
+
+/// Returns HandleResult
+///
+/// removes a listing from the registry
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `listing_address` - a reference to the address of the listing to remove
+fn try_deregister_listing<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    listing_address: &HumanAddr,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let listing_raw = deps.api.canonical_address(listing_address)?;
+    let listing = remove_listing(&mut deps.storage, &listing_raw)?
+        .ok_or_else(|| StdError::from(ContractError::ListingNotFound))?;
+    // free up a registration slot for the factory that registered the listing
+    if let Some(factory) = listing.and_then(|l| l.factory) {
+        let mut fact_store = PrefixedStorage::new(PREFIX_FACTORY_LISTING_COUNT, &mut deps.storage);
+        let fact_count: u32 = may_load(&fact_store, factory.as_slice())?.unwrap_or(0);
+        save(
+            &mut fact_store,
+            factory.as_slice(),
+            &fact_count.saturating_sub(1),
+        )?;
+    }
+    let mut ltag_store = PrefixedStorage::new(PREFIX_LISTING_TAG, &mut deps.storage);
+    remove(&mut ltag_store, listing_raw.as_slice());
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::DeregisterListing {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns StdResult<Option<Option<StoredListing>>>
+///
+/// removes a listing from the registry and swap-removes it from the ordered registry index.
+/// Returns None if the listing was not registered, or Some of the removed index entry, which is
+/// None if the index was out of sync with the registry
+///
+/// # Arguments
+///
+/// * `storage` - a mutable reference to the contract's storage
+/// * `address` - a reference to the listing's address
+fn remove_listing<S: Storage>(
+    storage: &mut S,
+    address: &CanonicalAddr,
+) -> StdResult<Option<Option<StoredListing>>> {
+    let mut reg_store = PrefixedStorage::new(PREFIX_LIST_REGISTRY, storage);
+    let pos = if let Some(p) = may_load::<u32, _>(&reg_store, address.as_slice())? {
+        p
+    } else {
+        return Ok(None);
+    };
+    remove(&mut reg_store, address.as_slice());
+    let count: u32 = may_load(storage, LIST_COUNT_KEY)?.unwrap_or(0);
+    let mut idx_store = PrefixedStorage::new(PREFIX_LIST_REGISTRY_INDEX, storage);
+    // if the index does not agree with the registry, leave the index alone
+    let removed = match may_load::<StoredListing, _>(&idx_store, &pos.to_le_bytes())? {
+        Some(l) if l.address == *address && pos < count => l,
+        _ => return Ok(Some(None)),
+    };
+    let last_pos = count - 1;
+    let moved = if pos != last_pos {
+        let last: Option<StoredListing> = may_load(&idx_store, &last_pos.to_le_bytes())?;
+        if let Some(l) = last.as_ref() {
+            save(&mut idx_store, &pos.to_le_bytes(), l)?;
+        }
+        last
+    } else {
+        None
+    };
+    remove(&mut idx_store, &last_pos.to_le_bytes());
+    if let Some(l) = moved {
+        let mut reg_store = PrefixedStorage::new(PREFIX_LIST_REGISTRY, storage);
+        save(&mut reg_store, l.address.as_slice(), &pos)?;
+    }
+    save(storage, LIST_COUNT_KEY, &last_pos)?;
+    Ok(Some(Some(removed)))
+}
+
+/// Returns StdResult<()>
+///
+/// appends an address to the ordered whitelist index if it is not already in it
+///
+/// # Arguments
+///
+/// * `storage` - a mutable reference to the contract's storage
+/// * `address` - a reference to the whitelisted address
+fn index_whitelisted<S: Storage>(storage: &mut S, address: &CanonicalAddr) -> StdResult<()> {
+    let pos_store = ReadonlyPrefixedStorage::new(PREFIX_WHITELIST_POSITION, storage);
+    if may_load::<u32, _>(&pos_store, address.as_slice())?.is_some() {
+        return Ok(());
+    }
+    let count: u32 = may_load(storage, WHITELIST_COUNT_KEY)?.unwrap_or(0);
+    let mut pos_store = PrefixedStorage::new(PREFIX_WHITELIST_POSITION, storage);
+    save(&mut pos_store, address.as_slice(), &count)?;
+    let mut order_store = PrefixedStorage::new(PREFIX_WHITELIST_ORDER, storage);
+    save(&mut order_store, &count.to_le_bytes(), address)?;
+    save(storage, WHITELIST_COUNT_KEY, &count.saturating_add(1))
+}
+
+/// Returns Vec<Vec<Transfer>> of the transfers grouped so that no group sends more than the
+/// maximum number of tokens
+///
+/// # Arguments
+///
+/// * `transfers` - list of transfers to group
+/// * `max_size` - maximum number of tokens in each group
+fn split_transfers(transfers: Vec<Transfer>, max_size: usize) -> Vec<Vec<Transfer>> {
+    let mut batches: Vec<Vec<Transfer>> = Vec::new();
+    let mut batch: Vec<Transfer> = Vec::new();
+    let mut batch_cnt = 0usize;
+    for mut xfer in transfers.into_iter() {
+        // a transfer of more tokens than fit in the current group is split between groups
+        while !xfer.token_ids.is_empty() {
+            let room = max_size - batch_cnt;
+            let rest = if xfer.token_ids.len() > room {
+                xfer.token_ids.split_off(room)
+            } else {
+                Vec::new()
+            };
+            batch_cnt += xfer.token_ids.len();
+            batch.push(Transfer {
+                recipient: xfer.recipient.clone(),
+                token_ids: std::mem::replace(&mut xfer.token_ids, rest),
+                memo: xfer.memo.clone(),
+            });
+            if batch_cnt == max_size {
+                batches.push(std::mem::take(&mut batch));
+                batch_cnt = 0;
+            }
+        }
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
+
+/// Returns StdResult<()>
+///
+/// saves the number of whitelist mints an address has remaining, removing the entry when none
+/// remain
+///
+/// # Arguments
+///
+/// * `storage` - a mutable reference to the contract's storage
+/// * `round_key` - optional key of a round whitelist entry.  The global whitelist is used if None
+/// * `address` - a reference to the whitelisted address
+/// * `remaining` - number of whitelist mints remaining
+/// * `expires_at` - optional expiration time of a global whitelist entry
+fn save_whitelist_quantity<S: Storage>(
+    storage: &mut S,
+    round_key: Option<&[u8]>,
+    address: &CanonicalAddr,
+    remaining: u32,
+    expires_at: Option<u64>,
+) -> StdResult<()> {
+    if let Some(key) = round_key {
+        let mut rwl_store = PrefixedStorage::new(PREFIX_ROUND_WHITELIST, storage);
+        if remaining == 0 {
+            remove(&mut rwl_store, key);
+        } else {
+            save(&mut rwl_store, key, &remaining)?;
+        }
+        return Ok(());
+    }
+    let mut white_store = PrefixedStorage::new(PREFIX_WHITELIST, storage);
+    if remaining == 0 {
+        remove(&mut white_store, address.as_slice());
+        unindex_whitelisted(storage, address)
+    } else {
+        save(
+            &mut white_store,
+            address.as_slice(),
+            &StoredWhitelistEntry {
+                remaining,
+                expires_at,
+            },
+        )?;
+        index_whitelisted(storage, address)
+    }
+}
+
+/// Returns StdResult<()>
+///
+/// swap-removes an address from the ordered whitelist index
+///
+/// # Arguments
+///
+/// * `storage` - a mutable reference to the contract's storage
+/// * `address` - a reference to the address being removed
+fn unindex_whitelisted<S: Storage>(storage: &mut S, address: &CanonicalAddr) -> StdResult<()> {
+    let mut pos_store = PrefixedStorage::new(PREFIX_WHITELIST_POSITION, storage);
+    let pos = if let Some(p) = may_load::<u32, _>(&pos_store, address.as_slice())? {
+        p
+    } else {
+        return Ok(());
+    };
+    remove(&mut pos_store, address.as_slice());
+    let last_pos = may_load::<u32, _>(storage, WHITELIST_COUNT_KEY)?
+        .unwrap_or(0)
+        .saturating_sub(1);
+    let mut order_store = PrefixedStorage::new(PREFIX_WHITELIST_ORDER, storage);
+    let moved: Option<CanonicalAddr> = if pos != last_pos {
+        let last: Option<CanonicalAddr> = may_load(&order_store, &last_pos.to_le_bytes())?;
+        if let Some(l) = last.as_ref() {
+            save(&mut order_store, &pos.to_le_bytes(), l)?;
+        }
+        last
+    } else {
+        None
+    };
+    remove(&mut order_store, &last_pos.to_le_bytes());
+    if let Some(l) = moved {
+        let mut pos_store = PrefixedStorage::new(PREFIX_WHITELIST_POSITION, storage);
+        save(&mut pos_store, l.as_slice(), &pos)?;
+    }
+    save(storage, WHITELIST_COUNT_KEY, &last_pos)
+}
+
+/// Returns HandleResult
+///
+/// handles receiving an NFT to place in the gumball machine
+///
+/// # Arguments
+///
+/// * `deps` - mutable reference to Extern containing all the contract's external dependencies
+/// * `env` - a reference to the Env of contract's environment
+/// * `from` - a reference to the address that owned the NFT
+/// * `token_ids` - list of tokens sent
+/// * `msg` - optional base64 encoded DepositMsg
+fn try_batch_receive<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    from: &HumanAddr,
+    mut token_ids: Vec<String>,
+    msg: Option<Binary>,
+) -> HandleResult {
+    let sender = &env.message.sender;
+    if may_load::<bool, _>(&deps.storage, PAUSED_KEY)?.unwrap_or(false) {
+        return Err(ContractError::Paused.into());
+    }
+    let contract =
+        load::<StoreContractInfo, _>(&deps.storage, COLLECTION_KEY)?.into_humanized(&deps.api)?;
+    // don't let someone spoof sending the gumball tokens
+    if *sender != contract.address {
+        return Err(ContractError::NotCollection.into());
+    }
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let from_raw = deps.api.canonical_address(from)?;
+    // only allow an admin to add tokens to the gumball
+    if !admins.contains(&from_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let mut messages = Vec::new();
+    if let Some(max) = may_load::<u32, _>(&deps.storage, MAX_CAPACITY_KEY)? {
+        let available = load_counts(&deps.storage)?.available;
+        let room = max.saturating_sub(available) as usize;
+        if token_ids.len() > room {
+            if !may_load::<bool, _>(&deps.storage, CAPACITY_PARTIAL_KEY)?.unwrap_or(false) {
+                return Err(ContractError::PoolFull { max }.into());
+            }
+            // send back the tokens that do not fit
+            let me_raw: CanonicalAddr = load(&deps.storage, MY_ADDRESS_KEY)?;
+            let transfers = vec![Transfer {
+                recipient: from.clone(),
+                token_ids: token_ids.split_off(room),
+                memo: format!(
+                    "Returned from full gumball: {}",
+                    deps.api.human_address(&me_raw)?
+                ),
+            }];
+            messages.push(
+                Snip721HandleMsg::BatchTransferNft { transfers }.to_cosmos_msg(
+                    contract.code_hash.clone(),
+                    contract.address.clone(),
+                    None,
+                )?,
+            );
+        }
+    }
+    // 721 contracts should not be doing a Send if there are no tokens sent, but you never know
+    // what people will code
+    if !token_ids.is_empty() {
+        let mut counts = load_counts(&deps.storage)?;
+        // an empty gumball machine starts a new set of examples
+        let mut examples = if counts.available == 0 {
+            Vec::new()
+        } else {
+            load_examples(&deps.storage)?
+        };
+        let prefix: Option<String> = may_load(&deps.storage, TOKEN_PREFIX_KEY)?;
+        let batch: u8 = may_load(&deps.storage, CURRENT_BATCH_ID_KEY)?.unwrap_or(0);
+        let head = load_pool_head(&deps.storage)?;
+        let tier = msg
+            .map(|m| from_binary::<DepositMsg>(&m))
+            .transpose()?
+            .and_then(|d| d.tier)
+            .unwrap_or(0);
+        for id in token_ids.iter() {
+            // only accept tokens from the desired sub-collection
+            if let Some(pfx) = prefix.as_ref() {
+                if !id.starts_with(pfx.as_str()) {
+                    return Err(ContractError::TokenPrefixRejected {
+                        token_id: id.clone(),
+                        prefix: pfx.clone(),
+                    }
+                    .into());
+                }
+            }
+            // don't let the same token occupy two pool indices
+            let rev_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_REVERSE, &deps.storage);
+            if may_load::<u32, _>(&rev_store, id.as_bytes())?.is_some() {
+                return Err(ContractError::DuplicateToken(id.clone()).into());
+            }
+            let idx = head
+                .checked_add(counts.available)
+                .ok_or_else(|| StdError::from(ContractError::PoolIndexFull))?;
+            let mut rev_store = PrefixedStorage::new(PREFIX_TOKEN_REVERSE, &mut deps.storage);
+            save(&mut rev_store, id.as_bytes(), &idx)?;
+            let mut id_store = PrefixedStorage::new(PREFIX_TOKEN_IDS, &mut deps.storage);
+            save(&mut id_store, &idx.to_le_bytes(), &(id.clone(), tier))?;
+            index_tier(&mut deps.storage, tier, idx)?;
+            let mut dep_store = PrefixedStorage::new(PREFIX_TOKEN_DEPOSITOR, &mut deps.storage);
+            save(&mut dep_store, &idx.to_le_bytes(), &from_raw)?;
+            // tokens without a batch tag belong to batch 0
+            if batch != 0 {
+                let mut batch_store = PrefixedStorage::new(PREFIX_BATCH_TAG, &mut deps.storage);
+                save(&mut batch_store, &idx.to_le_bytes(), &batch)?;
+            }
+            counts.available = counts
+                .available
+                .checked_add(1)
+                .ok_or_else(|| StdError::from(ContractError::PoolIndexFull))?;
+        }
+        counts.last_deposit_block = env.block.height;
+        save(&mut deps.storage, COUNT_KEY, &counts)?;
+        // a refill above the threshold re-arms the low pool alert
+        if let Some(threshold) = may_load::<u32, _>(&deps.storage, LOW_POOL_THRESHOLD_KEY)? {
+            if counts.available > threshold {
+                remove(&mut deps.storage, LOW_POOL_NOTIFIED_KEY);
+            }
+        }
+        // use the example viewing key if one was set
+        let viewer = may_load::<String, _>(&deps.storage, EXAMPLE_QUERY_VK_KEY)?
+            .map(|viewing_key| -> StdResult<ViewerInfo> {
+                let me_raw: CanonicalAddr = load(&deps.storage, MY_ADDRESS_KEY)?;
+                Ok(ViewerInfo {
+                    address: deps.api.human_address(&me_raw)?,
+                    viewing_key,
+                })
+            })
+            .transpose()?;
+        // cache the info of every deposited token
+        let mut first_doss: Option<StoredNftDossierForListing> = None;
+        for id in token_ids.iter() {
+            let nft_qry = Snip721QueryMsg::NftDossier {
+                token_id: id.clone(),
+                viewer: viewer.clone(),
+            };
+            let resp: StdResult<NftDossierResponse> = nft_qry.query(
+                &deps.querier,
+                contract.code_hash.clone(),
+                contract.address.clone(),
+            );
+            if let Ok(r) = resp {
+                let doss = r.nft_dossier.into_stored(&deps.api)?;
+                let mut meta_store = PrefixedStorage::new(PREFIX_TOKEN_METADATA, &mut deps.storage);
+                save(&mut meta_store, id.as_bytes(), &doss)?;
+                if first_doss.is_none() {
+                    first_doss = Some(doss);
+                }
+            }
+        }
+        // keep the public info of the first NFT of each deposit until there are enough examples
+        if examples.len() < MAX_EXAMPLES {
+            let nft_doss = match first_doss {
+                Some(doss) => doss,
+                None => NftDossierForListing {
+                    public_metadata: None,
+                    royalty_info: None,
+                    mint_run_info: None,
+                }
+                .into_stored(&deps.api)?,
+            };
+            examples.push(nft_doss);
+            save(&mut deps.storage, EXAMPLE_KEY, &examples)?;
+        }
+    }
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: None,
+    })
+}
+
+/// Returns HandleResult
+///
+/// call the factory to create a listing
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `env` - the Env of contract's environment
+/// * `label` - the String label of the listing to create
+/// * `payment_address` - optional payment address if different than the creator
+/// * `factory_contract` - code hash and address of the factory
+/// * `buy_contract` - ContractInfo of the purchasing token
+/// * `batch_send` - true if the purchasing token implements batch send
+/// * `price` - listing price
+/// * `closes_at` - seconds since 01/01/1970 in which the listing can be closed by the operator
+/// * `description` - optional text description of the listing.  Uses the default description if None
+/// * `entropy` - String used for entropy when generating viewing keys
+#[allow(clippy::too_many_arguments)]
+fn try_create_listing<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    label: String,
+    payment_address: Option<HumanAddr>,
+    factory_contract: ContractInfo,
+    buy_contract: ContractInfo,
+    batch_send: bool,
+    price: Uint128,
+    closes_at: u64,
+    description: Option<String>,
+    entropy: String,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    if let Some(min_fill) = may_load::<u32, _>(&deps.storage, MIN_FILL_KEY)? {
+        let counts = load_counts(&deps.storage)?;
+        if counts.available < min_fill {
+            return Err(ContractError::MinFillNotMet { min_fill }.into());
+        }
+    }
+    if let Some(min) = may_load::<Uint128, _>(&deps.storage, MIN_PRICE_KEY)? {
+        if price < min {
+            return Err(ContractError::PriceBelowMinimum(min).into());
+        }
+    }
+    if let Some(max) = may_load::<Uint128, _>(&deps.storage, MAX_PRICE_KEY)? {
+        if price > max {
+            return Err(ContractError::PriceAboveMaximum(max).into());
+        }
+    }
+    let mut messages = Vec::new();
+    if let Some(fee) = may_load::<ListingFee, _>(&deps.storage, LISTING_FEE_KEY)? {
+        if fee.amount.u128() > 0 {
+            let paid = env
+                .message
+                .sent_funds
+                .iter()
+                .filter(|c| c.denom == fee.denom)
+                .map(|c| c.amount.u128())
+                .sum::<u128>();
+            if paid < fee.amount.u128() {
+                return Err(ContractError::InsufficientListingFee.into());
+            }
+            // anything beyond the fee would be stranded in the contract
+            if paid > fee.amount.u128()
+                || env
+                    .message
+                    .sent_funds
+                    .iter()
+                    .any(|c| c.denom != fee.denom && c.amount.u128() > 0)
+            {
+                return Err(ContractError::ListingFeeOverpaid.into());
+            }
+            messages.push(CosmosMsg::Bank(BankMsg::Send {
+                from_address: env.contract.address.clone(),
+                to_address: deps.api.human_address(&fee.recipient)?,
+                amount: vec![Coin {
+                    denom: fee.denom,
+                    amount: fee.amount,
+                }],
+            }));
+        }
+    }
+    let contract =
+        load::<StoreContractInfo, _>(&deps.storage, COLLECTION_KEY)?.into_humanized(&deps.api)?;
+    let expected = ExpectedListing {
+        label: Some(label.clone()),
+        closes_at: Some(closes_at),
+    };
+    let factory_raw = deps.api.canonical_address(&factory_contract.address)?;
+    let mut exp_store = PrefixedStorage::new(PREFIX_EXPECTED_FACTORIES, &mut deps.storage);
+    save(&mut exp_store, factory_raw.as_slice(), &expected)?;
+    let minter_contract = ContractInfo {
+        address: env.contract.address,
+        code_hash: env.contract_code_hash,
+    };
+    let quantity_for_sale = load_counts(&deps.storage)?.available;
+    // an explicit description overrides the default
+    let description = match description {
+        Some(desc) => Some(desc),
+        None => may_load(&deps.storage, DEFAULT_LISTING_DESC_KEY)?,
+    };
+    let factory_msg = FactoryHandleMsg::CreateMinterListing {
+        label,
+        creator: env.message.sender,
+        payment_address,
+        quantity_for_sale,
+        minter_contract,
+        option_id: "Gumball".to_string(),
+        buy_contract,
+        batch_send,
+        price,
+        closes_at,
+        description,
+        entropy,
+        nft_contract_address: contract.address,
+        implements_register_listing: true,
+    };
+
+    messages.push(factory_msg.to_cosmos_msg(
+        factory_contract.code_hash,
+        factory_contract.address,
+        None,
+    )?);
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: None,
+    })
+}
+
+// type of address calling Mint
+pub enum MintCaller {
+    Listing,
+    Admin,
+    Whitelist,
+}
+
+/// Returns HandleResult
+///
+/// release a random nft for each buyer
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `env` - a reference to the Env of contract's environment
+/// * `buyers` - the nft buyers
+/// * `entropy` - string slice used for entropy
+/// * `referrer` - optional reference to the address that referred the buyers
+fn try_mint<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    buyers: Vec<HumanAddr>,
+    entropy: &str,
+    referrer: Option<&HumanAddr>,
+) -> HandleResult {
+    if may_load::<bool, _>(&deps.storage, PAUSED_KEY)?.unwrap_or(false) {
+        return Err(ContractError::Paused.into());
+    }
+    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
+    let sender_slice = sender_raw.as_slice();
+    let reg_store = ReadonlyPrefixedStorage::new(PREFIX_LIST_REGISTRY, &deps.storage);
+    // check if the caller is a listing this contract created
+    let mint_cnt = buyers.len() as u32;
+    let active_round = load_current_round(&deps.storage)?;
+    // key of the active round's whitelist entry if the caller is using it
+    let mut round_wl_key: Option<Vec<u8>> = None;
+    // expiration of the caller's global whitelist entry
+    let mut wl_expires_at: Option<u64> = None;
+    let caller_type = if may_load::<u32, _>(&reg_store, sender_slice)?.is_none() {
+        // check if the caller is whitelisted for the active round, then the global whitelist
+        let mut remaining = 0u32;
+        if let Some(rnd) = active_round.as_ref() {
+            let key = [&rnd.id.to_le_bytes()[..], sender_slice].concat();
+            let rwl_store = ReadonlyPrefixedStorage::new(PREFIX_ROUND_WHITELIST, &deps.storage);
+            remaining = may_load(&rwl_store, &key)?.unwrap_or(0);
+            if remaining > 0 {
+                round_wl_key = Some(key);
+            }
+        }
+        // whitelist-only rounds do not accept the global whitelist
+        if remaining == 0
+            && !active_round
+                .as_ref()
+                .map(|r| r.whitelist_only)
+                .unwrap_or(false)
+        {
+            let white_store = ReadonlyPrefixedStorage::new(PREFIX_WHITELIST, &deps.storage);
+            let entry = load_whitelist_entry(&white_store, &sender_raw)?;
+            remaining = entry.remaining;
+            wl_expires_at = entry.expires_at;
+        }
+        let expired = wl_expires_at
+            .map(|exp| exp <= env.block.time)
+            .unwrap_or(false);
+        if remaining == 0 || expired {
+            // check if the caller is an admin
+            let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+            if !admins.contains(&sender_raw) {
+                if remaining > 0 {
+                    return Err(ContractError::WhitelistExpired.into());
+                }
+                return Err(StdError::unauthorized());
+            } else {
+                MintCaller::Admin
+            }
+        } else {
+            // whitelisted addresses can only mint their remaining quantity
+            if mint_cnt > remaining {
+                return Err(ContractError::WhitelistQuantityExceeded { remaining }.into());
+            }
+            save_whitelist_quantity(
+                &mut deps.storage,
+                round_wl_key.as_deref(),
+                &sender_raw,
+                remaining - mint_cnt,
+                wl_expires_at,
+            )?;
+            MintCaller::Whitelist
+        }
+    } else {
+        // listing called
+        MintCaller::Listing
+    };
+    if !matches!(caller_type, MintCaller::Whitelist)
+        && may_load(&deps.storage, WHITELIST_ONLY_KEY)?.unwrap_or(false)
+    {
+        return Err(ContractError::WhitelistOnly.into());
+    }
+    if let MintCaller::Admin = caller_type {
+        let cooldown: u64 = may_load(&deps.storage, ADMIN_MINT_COOLDOWN_KEY)?.unwrap_or(0);
+        if let Some(last) = may_load::<u64, _>(&deps.storage, ADMIN_LAST_MINT_TIME_KEY)? {
+            if env.block.time < last.saturating_add(cooldown) {
+                return Err(ContractError::AdminCooldown { seconds: cooldown }.into());
+            }
+        }
+        save(&mut deps.storage, ADMIN_LAST_MINT_TIME_KEY, &env.block.time)?;
+    }
+    if let MintCaller::Listing = caller_type {
+        // guard against a listing passing an unexpected number of buyers
+        let cap_store = ReadonlyPrefixedStorage::new(PREFIX_BUYER_CAP, &deps.storage);
+        if let Some(cap) = may_load::<u32, _>(&cap_store, sender_slice)? {
+            if mint_cnt > cap {
+                return Err(ContractError::BuyerCapExceeded { cap }.into());
+            }
+        }
+        // admins and whitelisted addresses may mint outside of the window
+        if let Some(start) = may_load::<u64, _>(&deps.storage, MINT_START_KEY)? {
+            if env.block.time < start {
+                return Err(ContractError::MintNotStarted.into());
+            }
+        }
+        if let Some(end) = may_load::<u64, _>(&deps.storage, MINT_END_KEY)? {
+            if env.block.time >= end {
+                return Err(ContractError::MintWindowClosed.into());
+            }
+        }
+        if let Some(req) = may_load::<EntropyRequirement, _>(&deps.storage, LISTING_ENTROPY_KEY)? {
+            // entropy must always be non-empty when required
+            let min_length = req.min_length.max(1);
+            if req.required && entropy.chars().count() < min_length as usize {
+                return Err(ContractError::ListingEntropyRequired { min_length }.into());
+            }
+        }
+    }
+    // admins are not restricted by the active round
+    let round = if let MintCaller::Admin = caller_type {
+        None
+    } else {
+        active_round
+    };
+    if let Some(rnd) = round.as_ref() {
+        if rnd.whitelist_only {
+            if let MintCaller::Listing = caller_type {
+                return Err(ContractError::RoundWhitelistOnly(rnd.id).into());
+            }
+        }
+        if env.block.time < rnd.opens_at {
+            return Err(ContractError::RoundNotOpen(rnd.id).into());
+        }
+        if env.block.time >= rnd.closes_at {
+            return Err(ContractError::RoundClosed(rnd.id).into());
+        }
+    }
+    // return expired reservations to the pool and count the buyers with a token reserved
+    let mut reserved_for: Vec<CanonicalAddr> = Vec::new();
+    for buyer in buyers.iter() {
+        let buyer_raw = deps.api.canonical_address(buyer)?;
+        let res_store = ReadonlyPrefixedStorage::new(PREFIX_RESERVED, &deps.storage);
+        if reserved_for.contains(&buyer_raw) {
+            continue;
+        }
+        if let Some(res) = may_load::<ReservedToken, _>(&res_store, buyer_raw.as_slice())? {
+            if res
+                .expires_at
+                .map(|exp| env.block.time >= exp)
+                .unwrap_or(false)
+            {
+                release_reservation(&mut deps.storage, &buyer_raw)?;
+                unreserve_token(&mut deps.storage, res)?;
+            } else {
+                reserved_for.push(buyer_raw);
+            }
+        }
+    }
+    // number of tokens that must be drawn from the pool
+    let draw_cnt = mint_cnt - reserved_for.len() as u32;
+    let mut counts = load_counts(&deps.storage)?;
+    let start_available = counts.available;
+    if draw_cnt > counts.available {
+        return Err(ContractError::InsufficientSupply {
+            requested: draw_cnt,
+            available: counts.available,
+        }
+        .into());
+    }
+    let released_cap: Option<u64> = may_load(&deps.storage, RELEASED_CAP_KEY)?;
+    if let Some(cap) = released_cap {
+        if counts.released.saturating_add(mint_cnt as u64) > cap {
+            return Err(ContractError::ReleasedCapExceeded {
+                requested: mint_cnt,
+                cap,
+            }
+            .into());
+        }
+    }
+    if let MintCaller::Listing = caller_type {
+        let threshold: u32 = may_load(&deps.storage, MINT_PAUSE_THRESHOLD_KEY)?.unwrap_or(0);
+        if counts.available - draw_cnt < threshold {
+            return Err(ContractError::BelowReserve { threshold }.into());
+        }
+    }
+    let stats_level: StatsLevel =
+        may_load(&deps.storage, STATS_LEVEL_KEY)?.unwrap_or(StatsLevel::Basic);
+    // statistics only kept for display are skipped to minimize writes
+    let record_stats = stats_level != StatsLevel::None;
+    let mut prng_seed: Vec<u8> = load(&deps.storage, PRNG_SEED_KEY)?;
+    // include the user-contributed entropy pool
+    let mut mint_entropy = entropy.as_bytes().to_vec();
+    mint_entropy.extend_from_slice(&load_entropy_pool(&deps.storage)?);
+    let mut rng_entropy = extend_entropy(env, &mint_entropy);
+    let mut logs = Vec::new();
+    // mix in the oracle's randomness if it supplied any since the last mint
+    if let Some(oracle) = may_load::<OracleRandomness, _>(&deps.storage, ORACLE_RANDOMNESS_KEY)? {
+        if rng_entropy.len() < oracle.randomness.len() {
+            rng_entropy.resize(oracle.randomness.len(), 0);
+        }
+        for (ent, rand) in rng_entropy.iter_mut().zip(oracle.randomness.iter()) {
+            *ent ^= rand;
+        }
+        remove(&mut deps.storage, ORACLE_RANDOMNESS_KEY);
+        logs.push(log("oracle_round", oracle.round));
+    }
+    if may_load::<bool, _>(&deps.storage, PRNG_AUDIT_ENABLED_KEY)?.unwrap_or(false) {
+        let entry = PrngAuditEntry {
+            block_height: env.block.height,
+            block_time: env.block.time,
+            entropy_hash: Sha256::digest(&rng_entropy).into(),
+            seed_hash: Sha256::digest(&prng_seed).into(),
+        };
+        let audit_idx: u64 = may_load(&deps.storage, PRNG_AUDIT_COUNT_KEY)?.unwrap_or(0);
+        // only the most recent entries are kept, overwriting the oldest
+        let mut audit_store = PrefixedStorage::new(PREFIX_PRNG_AUDIT, &mut deps.storage);
+        save(
+            &mut audit_store,
+            &(audit_idx % PRNG_AUDIT_SIZE).to_le_bytes(),
+            &entry,
+        )?;
+        save(
+            &mut deps.storage,
+            PRNG_AUDIT_COUNT_KEY,
+            &audit_idx.saturating_add(1),
+        )?;
+    }
+    // listings restricted to a tag may only draw from the pool indices with that tag
+    let required_tag: Option<String> = if let MintCaller::Listing = caller_type {
+        let ltag_store = ReadonlyPrefixedStorage::new(PREFIX_LISTING_TAG, &deps.storage);
+        may_load(&ltag_store, sender_slice)?
+    } else {
+        None
+    };
+    let batch_weights = load_batch_weights(&deps.storage)?;
+    // (pool index, selection weight) of every token that may be drawn, if not all are equal
+    let mut candidates: Option<Vec<(u32, u32)>> = None;
+    if required_tag.is_some() || batch_weights.is_some() {
+        let tag_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_TAG, &deps.storage);
+        let batch_store = ReadonlyPrefixedStorage::new(PREFIX_BATCH_TAG, &deps.storage);
+        let mut cands: Vec<(u32, u32)> = Vec::new();
+        let mut tagged = 0usize;
+        let head = load_pool_head(&deps.storage)?;
+        // collected in pool order, so the front of the queue is always the first candidate
+        for idx in head..pool_end(&deps.storage, counts.available)? {
+            let idx_key = idx.to_le_bytes();
+            if let Some(tag) = required_tag.as_ref() {
+                if may_load::<String, _>(&tag_store, &idx_key)?.as_ref() != Some(tag) {
+                    continue;
+                }
+                tagged += 1;
+            }
+            let weight = if let Some(weights) = batch_weights.as_ref() {
+                let batch: u8 = may_load(&batch_store, &idx_key)?.unwrap_or(0);
+                weights[batch as usize]
+            } else {
+                1
+            };
+            if weight > 0 {
+                cands.push((idx, weight));
+            }
+        }
+        if let Some(tag) = required_tag.as_ref() {
+            if draw_cnt as usize > tagged {
+                return Err(ContractError::InsufficientTagged {
+                    requested: draw_cnt,
+                    available: tagged as u32,
+                    tag: tag.clone(),
+                }
+                .into());
+            }
+        }
+        if draw_cnt as usize > cands.len() {
+            return Err(ContractError::InsufficientEligible {
+                requested: draw_cnt,
+                eligible: cands.len() as u32,
+            }
+            .into());
+        }
+        candidates = Some(cands);
+    }
+    // (tier, weight) of each drawable tier if tier weights are in use
+    let mut tier_pools: Option<Vec<(u8, u32)>> = None;
+    let tier_weights: Vec<(u8, u32)> =
+        may_load(&deps.storage, TIER_WEIGHTS_KEY)?.unwrap_or_default();
+    if candidates.is_none() && !tier_weights.is_empty() {
+        let pools: Vec<(u8, u32)> = tier_weights.into_iter().filter(|t| t.1 > 0).collect();
+        let mut eligible = 0u32;
+        for (tier, _) in pools.iter() {
+            eligible = eligible.saturating_add(load_tier_count(&deps.storage, *tier)?);
+        }
+        if draw_cnt > eligible {
+            return Err(ContractError::InsufficientEligible {
+                requested: draw_cnt,
+                eligible,
+            }
+            .into());
+        }
+        tier_pools = Some(pools);
+    }
+    let addr_limit: Option<u32> = may_load(&deps.storage, ADDR_MINT_LIMIT_KEY)?;
+    let memo_key = match caller_type {
+        MintCaller::Listing => LISTING_MEMO_KEY,
+        MintCaller::Admin => ADMIN_MEMO_KEY,
+        MintCaller::Whitelist => WHITELIST_MEMO_KEY,
+    };
+    let memo_template: Option<String> = may_load(&deps.storage, memo_key)?;
+    let sequential =
+        may_load::<DrainMode, _>(&deps.storage, DRAIN_MODE_KEY)? == Some(DrainMode::Sequential);
+    let scope: CooldownScope =
+        may_load(&deps.storage, COOLDOWN_SCOPE_KEY)?.unwrap_or(CooldownScope::Whitelist);
+    let cooldown_blocks: u64 = may_load(&deps.storage, MINT_COOLDOWN_KEY)?.unwrap_or(0);
+    // every mint is recorded so a later change of scope still sees it
+    let cooldown = match (scope, &caller_type) {
+        (_, MintCaller::Whitelist)
+        | (CooldownScope::WhitelistAndListings, MintCaller::Listing)
+        | (CooldownScope::All, _) => cooldown_blocks,
+        _ => 0,
+    };
+    let mut skipped: Vec<HumanAddr> = Vec::new();
+    let mut rng = Prng::new(&prng_seed, &rng_entropy);
+    let mut transfers: Vec<Transfer> = Vec::new();
+    let mut distributed: Vec<String> = Vec::new();
+    let mut log_head: u64 = may_load(&deps.storage, MINT_LOG_HEAD_KEY)?.unwrap_or(0);
+    let caller_code: u8 = match caller_type {
+        MintCaller::Listing => 0,
+        MintCaller::Admin => 1,
+        MintCaller::Whitelist => 2,
+    };
+    // transfer an nft to each buyer
+    for buyer in buyers.into_iter() {
+        let buyer_raw = deps.api.canonical_address(&buyer)?;
+        if cooldown > 0 {
+            let block_store = ReadonlyPrefixedStorage::new(PREFIX_LAST_MINT_BLOCK, &deps.storage);
+            if let Some(last) = may_load::<u64, _>(&block_store, buyer_raw.as_slice())? {
+                if env.block.height.saturating_sub(last) < cooldown {
+                    // a listing buyer already paid, so revert the purchase instead of skipping
+                    if let MintCaller::Listing = caller_type {
+                        return Err(ContractError::ListingBuyerSkipped(buyer).into());
+                    }
+                    skipped.push(buyer);
+                    continue;
+                }
+            }
+        }
+        // sequential draws advance the head of the pool
+        let head = load_pool_head(&deps.storage)?;
+        // a buyer with a reserved token receives it instead of drawing from the pool
+        let res_store = ReadonlyPrefixedStorage::new(PREFIX_RESERVED, &deps.storage);
+        let mut draw: Option<(Option<u32>, String)> = None;
+        let mut reserved_memo: Option<String> = None;
+        if let Some(res) = may_load::<ReservedToken, _>(&res_store, buyer_raw.as_slice())? {
+            release_reservation(&mut deps.storage, &buyer_raw)?;
+            reserved_memo = res.memo;
+            draw = Some((None, res.token_id));
+        } else {
+            // draw the winning token, re-drawing if a blacklisted token was selected
+            for attempt in 0..=MAX_REDRAWS {
+                let idx = if sequential {
+                    // still advance the PRNG so the next seed does not go stale
+                    rng.next_u64();
+                    // the front of the queue is the lowest eligible index, skipping any blacklisted
+                    // tokens already drawn
+                    let front = if let Some(cands) = candidates.as_ref() {
+                        cands.get(attempt as usize).map(|c| c.0)
+                    } else {
+                        Some(attempt as u32)
+                            .filter(|a| *a < counts.available)
+                            .map(|a| head + a)
+                    };
+                    if let Some(front) = front {
+                        front
+                    } else {
+                        break;
+                    }
+                } else if let Some(cands) = candidates.as_ref() {
+                    let total: u64 = cands.iter().map(|c| c.1 as u64).sum();
+                    if total == 0 {
+                        break;
+                    }
+                    let mut pick = rng.next_u64() % total;
+                    let mut chosen = cands[cands.len() - 1].0;
+                    for (cand, weight) in cands.iter() {
+                        if pick < *weight as u64 {
+                            chosen = *cand;
+                            break;
+                        }
+                        pick -= *weight as u64;
+                    }
+                    chosen
+                } else if let Some(pools) = tier_pools.as_ref() {
+                    // pick a tier by weight among the tiers that still have tokens
+                    let mut stocked: Vec<(u8, u32, u32)> = Vec::new();
+                    for (tier, weight) in pools.iter() {
+                        let count = load_tier_count(&deps.storage, *tier)?;
+                        if count > 0 {
+                            stocked.push((*tier, *weight, count));
+                        }
+                    }
+                    let total: u64 = stocked.iter().map(|p| p.1 as u64).sum();
+                    if total == 0 {
+                        break;
+                    }
+                    let mut pick = rng.next_u64() % total;
+                    let mut chosen = stocked[stocked.len() - 1];
+                    for stock in stocked.iter() {
+                        if pick < stock.1 as u64 {
+                            chosen = *stock;
+                            break;
+                        }
+                        pick -= stock.1 as u64;
+                    }
+                    // then pick a token uniformly within the tier
+                    let pos = (rng.next_u64() % (chosen.2 as u64)) as u32;
+                    let tier_store = ReadonlyPrefixedStorage::new(PREFIX_TIER_INDEX, &deps.storage);
+                    may_load(&tier_store, &tier_key(chosen.0, pos))?
+                        .ok_or_else(|| StdError::from(ContractError::PoolCorrupt))?
+                } else {
+                    head + (rng.next_u64() % (counts.available as u64)) as u32
+                };
+                let id_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_IDS, &deps.storage);
+                let (id, _): (String, u8) = may_load(&id_store, &idx.to_le_bytes())?
+                    .ok_or_else(|| StdError::from(ContractError::PoolCorrupt))?;
+                let black_store =
+                    ReadonlyPrefixedStorage::new(PREFIX_TOKEN_BLACKLIST, &deps.storage);
+                if may_load::<bool, _>(&black_store, id.as_bytes())?.is_none() {
+                    draw = Some((Some(idx), id));
+                    break;
+                }
+            }
+        }
+        let (winner, winner_id) = if let Some(won) = draw {
+            won
+        } else {
+            // a listing buyer already paid, so revert the purchase instead of skipping
+            if let MintCaller::Listing = caller_type {
+                return Err(ContractError::ListingBuyerSkipped(buyer).into());
+            }
+            logs.push(log("buyer_draw_failed", buyer.as_str()));
+            // the buyer is refunded like a skipped one
+            skipped.push(buyer);
+            continue;
+        };
+        // an address limit needs the count even if statistics are not recorded
+        if record_stats || addr_limit.is_some() {
+            let mut cnt_store = PrefixedStorage::new(PREFIX_ADDR_MINT_COUNT, &mut deps.storage);
+            let buyer_cnt = may_load::<u32, _>(&cnt_store, buyer_raw.as_slice())?
+                .unwrap_or(0)
+                .saturating_add(1);
+            if let Some(limit) = addr_limit {
+                if buyer_cnt > limit {
+                    return Err(ContractError::AddressMintLimit(buyer.clone()).into());
+                }
+            }
+            save(&mut cnt_store, buyer_raw.as_slice(), &buyer_cnt)?;
+        }
+        if let Some(rnd) = round.as_ref() {
+            let round_key = [&rnd.id.to_le_bytes()[..], buyer_raw.as_slice()].concat();
+            let mut rcnt_store = PrefixedStorage::new(PREFIX_ROUND_MINT_COUNT, &mut deps.storage);
+            let round_cnt = may_load::<u32, _>(&rcnt_store, &round_key)?
+                .unwrap_or(0)
+                .saturating_add(1);
+            if rnd.max_per_buyer > 0 && round_cnt > rnd.max_per_buyer {
+                return Err(ContractError::RoundBuyerLimit {
+                    buyer: buyer.clone(),
+                    max: rnd.max_per_buyer,
+                    round: rnd.id,
+                }
+                .into());
+            }
+            save(&mut rcnt_store, &round_key, &round_cnt)?;
+        }
+        if cooldown_blocks > 0 {
+            let mut block_store = PrefixedStorage::new(PREFIX_LAST_MINT_BLOCK, &mut deps.storage);
+            save(&mut block_store, buyer_raw.as_slice(), &env.block.height)?;
+        }
+        distributed.push(winner_id.clone());
+        if record_stats {
+            // only the most recent mints are kept, overwriting the oldest
+            let event = MintEvent {
+                recipient: buyer_raw.clone(),
+                token_id: winner_id.clone(),
+                caller_type: caller_code,
+                block_height: env.block.height,
+            };
+            let mut log_store = PrefixedStorage::new(PREFIX_MINT_LOG, &mut deps.storage);
+            save(
+                &mut log_store,
+                &(log_head % MINT_LOG_SIZE).to_le_bytes(),
+                &event,
+            )?;
+            log_head += 1;
+        }
+        let mut memo = if let Some(template) = memo_template.as_ref() {
+            let listing = if let MintCaller::Listing = caller_type {
+                env.message.sender.as_str()
+            } else {
+                ""
+            };
+            template
+                .replace("{listing}", listing)
+                .replace("{contract}", env.contract.address.as_str())
+                .replace("{buyer}", buyer.as_str())
+                .replace("{token_id}", &winner_id)
+        } else if let MintCaller::Listing = caller_type {
+            format!("Purchased from listing {}", &env.message.sender)
+        } else {
+            format!(
+                "Distributed from gumball contract {}",
+                &env.contract.address
+            )
+        };
+        let token_memo = if let Some(idx) = winner {
+            let memo_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_MEMO, &deps.storage);
+            may_load::<String, _>(&memo_store, &idx.to_le_bytes())?
+        } else {
+            reserved_memo
+        };
+        if let Some(token_memo) = token_memo {
+            memo = format!("{}.  {}", memo, token_memo);
+        }
+        if let Some(xfer) = transfers
+            .iter_mut()
+            .find(|t| t.recipient == buyer && t.memo == memo)
+        {
+            // if this address is already getting tokens with this memo, just add this id to its list
+            xfer.token_ids.push(winner_id);
+        } else {
+            // first one this address is getting with this memo
+            transfers.push(Transfer {
+                recipient: buyer,
+                token_ids: vec![winner_id],
+                memo,
+            });
+        }
+        // reserved tokens were already removed from the pool
+        if let Some(winner) = winner {
+            let last_idx = head + counts.available - 1;
+            if sequential {
+                shift_remove_token(&mut deps.storage, winner, last_idx)?;
+                // keep the candidate indices pointing at the same tokens after the shift
+                if let Some(cands) = candidates.as_mut() {
+                    cands.retain(|c| c.0 != winner);
+                    for c in cands.iter_mut().filter(|c| c.0 < winner) {
+                        c.0 += 1;
+                    }
+                }
+            } else {
+                swap_remove_token(&mut deps.storage, winner, last_idx)?;
+                // keep the candidate indices pointing at the same tokens after the swap
+                if let Some(cands) = candidates.as_mut() {
+                    cands.retain(|c| c.0 != winner);
+                    if let Some(moved) = cands.iter_mut().find(|c| c.0 == last_idx) {
+                        moved.0 = winner;
+                    }
+                }
+            }
+            counts.available = counts.available.saturating_sub(1);
+        }
+        counts.released = counts.released.saturating_add(1);
+    }
+    counts.last_mint_block = env.block.height;
+    save(&mut deps.storage, COUNT_KEY, &counts)?;
+    if record_stats {
+        let since_reseed: u64 = may_load(&deps.storage, MINTS_SINCE_RESEED_KEY)?.unwrap_or(0);
+        save(
+            &mut deps.storage,
+            MINTS_SINCE_RESEED_KEY,
+            &since_reseed.saturating_add(distributed.len() as u64),
+        )?;
+    }
+    // only alert the first time the pool drops to the threshold until it is refilled
+    if let Some(threshold) = may_load::<u32, _>(&deps.storage, LOW_POOL_THRESHOLD_KEY)? {
+        if start_available > threshold
+            && counts.available <= threshold
+            && !may_load::<bool, _>(&deps.storage, LOW_POOL_NOTIFIED_KEY)?.unwrap_or(false)
+        {
+            logs.push(log("low_pool_alert", counts.available.to_string()));
+            save(&mut deps.storage, LOW_POOL_NOTIFIED_KEY, &true)?;
+        }
+    }
+    if stats_level == StatsLevel::Full {
+        let mut stats: MintStats = may_load(&deps.storage, MINT_STATS_KEY)?.unwrap_or_default();
+        let minted = distributed.len() as u64;
+        match caller_type {
+            MintCaller::Listing => stats.listing = stats.listing.saturating_add(minted),
+            MintCaller::Admin => stats.admin = stats.admin.saturating_add(minted),
+            MintCaller::Whitelist => stats.whitelist = stats.whitelist.saturating_add(minted),
+        }
+        save(&mut deps.storage, MINT_STATS_KEY, &stats)?;
+    }
+    if record_stats {
+        if let MintCaller::Listing = caller_type {
+            let mut lst_store = PrefixedStorage::new(PREFIX_LISTING_MINT_COUNT, &mut deps.storage);
+            let lst_cnt = may_load::<u64, _>(&lst_store, sender_slice)?
+                .unwrap_or(0)
+                .saturating_add(distributed.len() as u64);
+            save(&mut lst_store, sender_slice, &lst_cnt)?;
+            logs.push(log("listing_mint_count", lst_cnt.to_string()));
+        }
+    }
+    // only attribute referrals for purchases made through a listing
+    if let (Some(refr), MintCaller::Listing) = (referrer, &caller_type) {
+        let refr_raw = deps.api.canonical_address(refr)?;
+        let mut ref_store = PrefixedStorage::new(PREFIX_REFERRAL_COUNTS, &mut deps.storage);
+        let ref_cnt: u32 = may_load(&ref_store, refr_raw.as_slice())?.unwrap_or(0);
+        save(
+            &mut ref_store,
+            refr_raw.as_slice(),
+            &ref_cnt.saturating_add(distributed.len() as u32),
+        )?;
+    }
+    if record_stats {
+        save(&mut deps.storage, MINT_LOG_HEAD_KEY, &log_head)?;
+    }
+    prng_seed = rng.rand_bytes().to_vec();
+    save(&mut deps.storage, PRNG_SEED_KEY, &prng_seed)?;
+
+    let mut messages = Vec::new();
+    // every buyer may have been skipped because of blacklisted draws
+    if !transfers.is_empty() {
+        let bridge =
+            may_load::<BridgeMode, _>(&deps.storage, BRIDGE_MODE_KEY)?.filter(|b| b.enabled);
+        let stored: StoreContractInfo = load(&deps.storage, COLLECTION_KEY)?;
+        let contract = stored.into_humanized(&deps.api)?;
+        let max_size: u32 = may_load(&deps.storage, MAX_TOKENS_PER_TRANSFER_MSG_KEY)?
+            .unwrap_or(DEFAULT_MAX_TRANSFER_BATCH);
+        if let Some(bridge) = bridge {
+            let bridge_address = deps.api.human_address(&bridge.contract.address)?;
+            // the bridge takes custody of the tokens and is told who receives them
+            for batch in split_transfers(transfers, max_size as usize).into_iter() {
+                let sends = batch
+                    .into_iter()
+                    .map(|xfer| -> StdResult<Send> {
+                        Ok(Send {
+                            contract: bridge_address.clone(),
+                            token_ids: xfer.token_ids,
+                            msg: Some(to_binary(&BridgeMsg::BridgeMint {
+                                recipient: xfer.recipient,
+                            })?),
+                            memo: Some(xfer.memo),
+                        })
+                    })
+                    .collect::<StdResult<Vec<Send>>>()?;
+                messages.push(Snip721HandleMsg::BatchSendNft { sends }.to_cosmos_msg(
+                    contract.code_hash.clone(),
+                    contract.address.clone(),
+                    None,
+                )?);
+            }
+            let bridged: u64 = may_load(&deps.storage, BRIDGE_RELEASED_KEY)?.unwrap_or(0);
+            save(
+                &mut deps.storage,
+                BRIDGE_RELEASED_KEY,
+                &bridged.saturating_add(distributed.len() as u64),
+            )?;
+        } else {
+            for batch in split_transfers(transfers, max_size as usize).into_iter() {
+                messages.push(
+                    Snip721HandleMsg::BatchTransferNft { transfers: batch }.to_cosmos_msg(
+                        contract.code_hash.clone(),
+                        contract.address.clone(),
+                        None,
+                    )?,
+                );
+            }
+        }
+    }
+    if !skipped.is_empty() {
+        // whitelisted addresses keep the mints of any skipped recipients
+        if let MintCaller::Whitelist = caller_type {
+            let remaining = if let Some(key) = round_wl_key.as_ref() {
+                let rwl_store = ReadonlyPrefixedStorage::new(PREFIX_ROUND_WHITELIST, &deps.storage);
+                may_load(&rwl_store, key)?.unwrap_or(0)
+            } else {
+                let white_store = ReadonlyPrefixedStorage::new(PREFIX_WHITELIST, &deps.storage);
+                load_whitelist_entry(&white_store, &sender_raw)?.remaining
+            };
+            save_whitelist_quantity(
+                &mut deps.storage,
+                round_wl_key.as_deref(),
+                &sender_raw,
+                remaining.saturating_add(skipped.len() as u32),
+                wl_expires_at,
+            )?;
+        }
+        logs.push(log("skipped", format!("{:?}", &skipped)));
+    }
+    logs.push(log("distributed", format!("{:?}", &distributed)));
+    Ok(HandleResponse {
+        messages,
+        log: logs,
+        data: None,
+    })
+}
+
+/// Returns HandleResult
+///
+/// remove a list of admins from the list
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `admins_to_remove` - list of admin addresses to remove
+fn try_remove_admins<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    admins_to_remove: Vec<HumanAddr>,
+) -> HandleResult {
+    // only allow admins to do this
+    let mut admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let old_len = admins.len();
+    let rem_list = admins_to_remove
+        .iter()
+        .map(|a| deps.api.canonical_address(a))
+        .collect::<StdResult<Vec<CanonicalAddr>>>()?;
+    admins.retain(|a| !rem_list.contains(a));
+    if admins.is_empty() {
+        return Err(ContractError::LastAdminRemoval.into());
+    }
+    // a removed primary admin hands primary status to the admin that removed it
+    if let Some(primary) = may_load::<CanonicalAddr, _>(&deps.storage, PRIMARY_ADMIN_KEY)? {
+        if !admins.contains(&primary) {
+            let new_primary = if admins.contains(&sender_raw) {
+                sender_raw
+            } else {
+                admins[0].clone()
+            };
+            save(&mut deps.storage, PRIMARY_ADMIN_KEY, &new_primary)?;
+        }
+    }
+    // only save if the list changed
+    if old_len != admins.len() {
+        save(&mut deps.storage, ADMINS_KEY, &admins)?;
+    }
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::AdminsList {
+            admins: admins
+                .iter()
+                .map(|a| deps.api.human_address(a))
+                .collect::<StdResult<Vec<HumanAddr>>>()?,
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// adds a list of admins to the list
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `admins_to_add` - list of admin addresses to add
+fn try_add_admins<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    admins_to_add: Vec<HumanAddr>,
+) -> HandleResult {
+    // only allow admins to do this
+    let mut admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let mut save_it = false;
+    for admin in admins_to_add.iter() {
+        let raw = deps.api.canonical_address(admin)?;
+        if !admins.contains(&raw) {
+            admins.push(raw);
+            save_it = true;
+        }
+    }
+    let max_admins: u32 = may_load(&deps.storage, MAX_ADMINS_KEY)?.unwrap_or(DEFAULT_MAX_ADMINS);
+    if admins.len() > max_admins as usize {
+        return Err(ContractError::TooManyAdmins { max: max_admins }.into());
+    }
+    // only save if the list changed
+    if save_it {
+        save(&mut deps.storage, ADMINS_KEY, &admins)?;
+    }
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::AdminsList {
+            admins: admins
+                .iter()
+                .map(|a| deps.api.human_address(a))
+                .collect::<StdResult<Vec<HumanAddr>>>()?,
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// creates a viewing key
+///
+/// # Arguments
+///
+/// * `deps` - mutable reference to Extern containing all the contract's external dependencies
+/// * `env` - a reference to the Env of contract's environment
+/// * `entropy` - string slice of the input String to be used as entropy in randomization
+fn try_create_key<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    entropy: &str,
+) -> HandleResult {
+    check_entropy(&deps.storage, entropy)?;
+    let prng_seed: Vec<u8> = load(&deps.storage, PRNG_SEED_KEY)?;
+    let key = ViewingKey::new(env, &prng_seed, entropy.as_ref());
+    let message_sender = &deps.api.canonical_address(&env.message.sender)?;
+    let mut key_store = PrefixedStorage::new(PREFIX_VIEW_KEY, &mut deps.storage);
+    save(&mut key_store, message_sender.as_slice(), &key.to_hashed())?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::ViewingKey { key: key.0 })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the viewing key to the input String
+///
+/// # Arguments
+///
+/// * `deps` - mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `key` - String to be used as the viewing key
+fn try_set_key<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    key: String,
+) -> HandleResult {
+    let vk = ViewingKey(key.clone());
+    let message_sender = &deps.api.canonical_address(sender)?;
+    let mut key_store = PrefixedStorage::new(PREFIX_VIEW_KEY, &mut deps.storage);
+    save(&mut key_store, message_sender.as_slice(), &vk.to_hashed())?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::ViewingKey { key })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// revoke the ability to use a specified permit
+///
+/// # Arguments
+///
+/// * `storage` - mutable reference to the contract's storage
+/// * `sender` - a reference to the message sender
+/// * `permit_name` - string slice of the name of the permit to revoke
+fn revoke_permit<S: Storage>(
+    storage: &mut S,
+    sender: &HumanAddr,
+    permit_name: &str,
+) -> HandleResult {
+    RevokedPermits::revoke_permit(storage, PREFIX_REVOKED_PERMITS, sender, permit_name);
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RevokePermit {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// removes the message sender's revocation records for the specified permits
+///
+/// # Arguments
+///
+/// * `storage` - a mutable reference to the contract's storage
+/// * `sender` - a reference to the message sender
+/// * `permit_names` - list of names of revoked permits to clear
+fn try_clear_revoked_permits<S: Storage>(
+    storage: &mut S,
+    sender: &HumanAddr,
+    permit_names: &[String],
+) -> HandleResult {
+    let mut cleared = 0u32;
+    for name in permit_names.iter() {
+        // only remove entries that were actually revoked by the sender
+        if RevokedPermits::is_permit_revoked(storage, PREFIX_REVOKED_PERMITS, sender, name) {
+            // same key construction as RevokedPermits
+            let key = PREFIX_REVOKED_PERMITS.to_string() + sender.as_str() + name;
+            storage.remove(key.as_bytes());
+            cleared += 1;
+        }
+    }
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::ClearMyRevokedPermits { cleared })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the description used when creating a listing without one
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `description` - optional default listing description.  None removes the default
+fn try_set_default_description<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    description: Option<String>,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    if let Some(desc) = description {
+        save(&mut deps.storage, DEFAULT_LISTING_DESC_KEY, &desc)?;
+    } else {
+        remove(&mut deps.storage, DEFAULT_LISTING_DESC_KEY);
+    }
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetDefaultListingDescription {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the maximum number of listings a single factory may register
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `max` - maximum number of listings per factory
+fn try_set_max_per_factory<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    max: u32,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save(&mut deps.storage, MAX_PER_FACTORY_KEY, &max)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetMaxListingsPerFactory {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the informational mint price shown in listing displays
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `price` - the displayed price
+/// * `denom` - denomination of the displayed price
+fn try_set_display_price<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    price: Uint128,
+    denom: String,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save(&mut deps.storage, DISPLAY_PRICE_KEY, &(price, denom))?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetDisplayPrice {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// locks the gumball into a read-only state
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+fn try_finalize<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    // nothing can remove tokens from a finalized gumball
+    let reserved: u32 = may_load(&deps.storage, RESERVED_COUNT_KEY)?.unwrap_or(0);
+    let held = load_counts(&deps.storage)?
+        .available
+        .saturating_add(reserved);
+    if held > 0 {
+        return Err(ContractError::FinalizeWithTokens(held).into());
+    }
+    save(&mut deps.storage, IS_FINALIZED_KEY, &true)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::FinalizeGumball {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sends the example NFT info to listings through their factory so their displays stay current
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `listings` - addresses of the listings to update
+/// * `factory_contract` - code hash and address of the factory that relays the update
+fn try_push_example<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    listings: Vec<HumanAddr>,
+    factory_contract: ContractInfo,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let nft_info = load_examples(&deps.storage)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| StdError::from(ContractError::NoExample))?
+        .into_humanized(&deps.api)?;
+    let mut messages = Vec::new();
+    for listing_address in listings.into_iter() {
+        let factory_msg = FactoryHandleMsg::UpdateListingNftInfo {
+            listing_address,
+            nft_info: nft_info.clone(),
+        };
+        messages.push(factory_msg.to_cosmos_msg(
+            factory_contract.code_hash.clone(),
+            factory_contract.address.clone(),
+            None,
+        )?);
+    }
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::PushExampleToListings {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the time after which the contract becomes read-only
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `env` - a reference to the Env of contract's environment
+/// * `expires_at` - seconds since 01/01/1970 after which the contract is read-only
+fn try_set_expiry<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    expires_at: u64,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    if may_load::<u64, _>(&deps.storage, CONTRACT_EXPIRY_KEY)?.is_some() {
+        return Err(ContractError::ExpiryAlreadySet.into());
+    }
+    if expires_at <= env.block.time {
+        return Err(ContractError::ExpiryInPast.into());
+    }
+    save(&mut deps.storage, CONTRACT_EXPIRY_KEY, &expires_at)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetContractExpiry {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// enables or disables recording hashes of the PRNG inputs of every mint
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `enabled` - true if the PRNG inputs should be recorded
+fn try_set_prng_audit<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    enabled: bool,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save(&mut deps.storage, PRNG_AUDIT_ENABLED_KEY, &enabled)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetPrngAuditEnabled {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the bridge contract that receives minted NFTs
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `contract` - code hash and address of the bridge contract
+/// * `enabled` - true if mints should be sent through the bridge
+fn try_set_bridge_mode<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    contract: ContractInfo,
+    enabled: bool,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let bridge = BridgeMode {
+        contract: contract.into_store(&deps.api)?,
+        enabled,
+    };
+    save(&mut deps.storage, BRIDGE_MODE_KEY, &bridge)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetBridgeMode {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets whether listing mints must include buyer-supplied entropy
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `required` - true if listing mints must include entropy
+/// * `min_length` - minimum number of characters of entropy
+fn try_set_entropy_mode<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    required: bool,
+    min_length: u32,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let requirement = EntropyRequirement {
+        required,
+        min_length,
+    };
+    save(&mut deps.storage, LISTING_ENTROPY_KEY, &requirement)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetListingEntropyMode {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets a memo that will be appended to the transfer memo of a token in the pool
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `token_id` - string slice of the token's ID
+/// * `memo` - the token's memo
+fn try_set_token_memo<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    token_id: &str,
+    memo: String,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let rev_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_REVERSE, &deps.storage);
+    let idx: u32 = may_load(&rev_store, token_id.as_bytes())?
+        .ok_or_else(|| StdError::from(ContractError::TokenNotInPool(token_id.to_string())))?;
+    let mut memo_store = PrefixedStorage::new(PREFIX_TOKEN_MEMO, &mut deps.storage);
+    save(&mut memo_store, &idx.to_le_bytes(), &memo)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetTokenMemo {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the minimum number of seconds between admin mints
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `seconds` - minimum number of seconds between admin mints
+fn try_set_admin_cooldown<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    seconds: u64,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save(&mut deps.storage, ADMIN_MINT_COOLDOWN_KEY, &seconds)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetAdminMintCooldown {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the fee that must be sent with CreateListing
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `amount` - fee amount
+/// * `denom` - fee denomination
+/// * `recipient` - a reference to the address that receives the fee
+fn try_set_listing_fee<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    amount: Uint128,
+    denom: String,
+    recipient: &HumanAddr,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let fee = ListingFee {
+        amount,
+        denom,
+        recipient: deps.api.canonical_address(recipient)?,
+    };
+    save(&mut deps.storage, LISTING_FEE_KEY, &fee)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetListingCreationFee {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// tags a token in the pool
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `token_id` - string slice of the token's ID
+/// * `tag` - the token's tag
+fn try_tag_token<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    token_id: &str,
+    tag: String,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let rev_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_REVERSE, &deps.storage);
+    let idx: u32 = may_load(&rev_store, token_id.as_bytes())?
+        .ok_or_else(|| StdError::from(ContractError::TokenNotInPool(token_id.to_string())))?;
+    let mut tag_store = PrefixedStorage::new(PREFIX_TOKEN_TAG, &mut deps.storage);
+    save(&mut tag_store, &idx.to_le_bytes(), &tag)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::TagToken {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// restricts a listing to only draw tokens with the specified tag
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `listing_address` - a reference to the listing's address
+/// * `tag` - the required tag.  An empty tag removes the restriction
+fn try_set_required_tag<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    listing_address: &HumanAddr,
+    tag: String,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let listing_raw = deps.api.canonical_address(listing_address)?;
+    let mut ltag_store = PrefixedStorage::new(PREFIX_LISTING_TAG, &mut deps.storage);
+    if tag.is_empty() {
+        remove(&mut ltag_store, listing_raw.as_slice());
+    } else {
+        save(&mut ltag_store, listing_raw.as_slice(), &tag)?;
+    }
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetRequiredTag {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the number of tokens reserved from listing mints
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `threshold` - number of tokens listing mints may not drop the pool below
+fn try_set_pause_threshold<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    threshold: u32,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save(&mut deps.storage, MINT_PAUSE_THRESHOLD_KEY, &threshold)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetMintPauseThreshold {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets how many NFTs are shown in listing displays
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `count` - number of NFTs to show, including the example
+fn try_set_preview_count<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    count: u8,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    if count == 0 || count > MAX_PREVIEW_COUNT {
+        return Err(ContractError::InvalidPreviewCount {
+            max: MAX_PREVIEW_COUNT,
+        }
+        .into());
+    }
+    save(&mut deps.storage, LISTING_PREVIEW_COUNT_KEY, &count)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetListingPreviewCount {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the maximum number of NFTs the gumball may ever release
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `cap` - maximum number of NFTs that may be released
+fn try_set_released_cap<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    cap: u64,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let counts = load_counts(&deps.storage)?;
+    if counts.released != 0 {
+        return Err(ContractError::ReleasedCapLocked.into());
+    }
+    save(&mut deps.storage, RELEASED_CAP_KEY, &cap)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetReleasedCap {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// starts a new deposit batch
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+fn try_start_batch<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let current: u8 = may_load(&deps.storage, CURRENT_BATCH_ID_KEY)?.unwrap_or(0);
+    let batch_id = current
+        .checked_add(1)
+        .ok_or_else(|| StdError::from(ContractError::BatchLimit))?;
+    save(&mut deps.storage, CURRENT_BATCH_ID_KEY, &batch_id)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::StartNewBatch { batch_id })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the relative weight used when drawing tokens from a batch
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `batch_id` - ID of the batch
+/// * `weight` - the batch's selection weight
+fn try_set_batch_weight<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    batch_id: u8,
+    weight: u32,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let current: u8 = may_load(&deps.storage, CURRENT_BATCH_ID_KEY)?.unwrap_or(0);
+    if batch_id > current {
+        return Err(ContractError::BatchNotStarted(batch_id).into());
+    }
+    let mut weight_store = PrefixedStorage::new(PREFIX_BATCH_WEIGHT, &mut deps.storage);
+    save(&mut weight_store, &[batch_id], &weight)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetBatchWeight {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// pauses or unpauses minting and deposits
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `paused` - true if the gumball should be paused
+fn try_set_paused<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    paused: bool,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save(&mut deps.storage, PAUSED_KEY, &paused)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetPaused {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the maximum number of NFTs any single address may receive
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `limit` - maximum number of NFTs per address
+fn try_set_address_limit<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    limit: u32,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save(&mut deps.storage, ADDR_MINT_LIMIT_KEY, &limit)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetAddressMintLimit {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the selection weight of each rarity tier
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `weights` - list of tier weights
+fn try_set_tier_weights<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    weights: Vec<TierWeight>,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let mut tier_weights: Vec<(u8, u32)> = Vec::new();
+    for tw in weights.into_iter() {
+        if tier_weights.iter().any(|t| t.0 == tw.tier) {
+            return Err(ContractError::DuplicateTierWeight(tw.tier).into());
+        }
+        tier_weights.push((tw.tier, tw.weight));
+    }
+    save(&mut deps.storage, TIER_WEIGHTS_KEY, &tier_weights)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetTierWeights {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the window in which listings may mint
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `start` - optional time minting opens
+/// * `end` - optional time minting closes
+fn try_set_mint_window<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    start: Option<u64>,
+    end: Option<u64>,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save_mint_window(&mut deps.storage, start, end)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetMintWindow {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns StdResult<()>
+///
+/// saves the bounds of the mint window, removing any bound that is None
+///
+/// # Arguments
+///
+/// * `storage` - a mutable reference to the contract's storage
+/// * `start` - optional time minting opens
+/// * `end` - optional time minting closes
+fn save_mint_window<S: Storage>(
+    storage: &mut S,
+    start: Option<u64>,
+    end: Option<u64>,
+) -> StdResult<()> {
+    if let (Some(st), Some(en)) = (start, end) {
+        if st >= en {
+            return Err(ContractError::InvalidMintWindow.into());
+        }
+    }
+    if let Some(st) = start {
+        save(storage, MINT_START_KEY, &st)?;
+    } else {
+        remove(storage, MINT_START_KEY);
+    }
+    if let Some(en) = end {
+        save(storage, MINT_END_KEY, &en)?;
+    } else {
+        remove(storage, MINT_END_KEY);
+    }
+    Ok(())
+}
+
+/// Returns HandleResult
+///
+/// sets whether deposits exceeding the maximum capacity are partially accepted
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `partial_ok` - true if the tokens that fit should be accepted and the rest returned
+fn try_set_capacity_mode<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    partial_ok: bool,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save(&mut deps.storage, CAPACITY_PARTIAL_KEY, &partial_ok)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetCapacityMode {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// mixes fresh entropy into the PRNG seed
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `env` - a reference to the Env of contract's environment
+/// * `entropy` - new entropy String
+fn try_reseed_prng<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    entropy: &str,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    check_entropy(&deps.storage, entropy)?;
+    let mut new_seed: Vec<u8> = load(&deps.storage, PRNG_SEED_KEY)?;
+    new_seed.extend_from_slice(&extend_entropy(env, entropy.as_bytes()));
+    save(
+        &mut deps.storage,
+        PRNG_SEED_KEY,
+        &sha_256(&new_seed).to_vec(),
+    )?;
+    save(&mut deps.storage, PRNG_SEED_BLOCK_KEY, &env.block.height)?;
+    save(&mut deps.storage, MINTS_SINCE_RESEED_KEY, &0u64)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::ReseedPrng {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the memo templates used when transferring minted NFTs
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `listing_memo` - optional template for listing mints
+/// * `admin_memo` - optional template for admin mints
+/// * `whitelist_memo` - optional template for whitelist mints
+fn try_set_memo_template<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    listing_memo: Option<String>,
+    admin_memo: Option<String>,
+    whitelist_memo: Option<String>,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    for (key, template) in [
+        (LISTING_MEMO_KEY, listing_memo),
+        (ADMIN_MEMO_KEY, admin_memo),
+        (WHITELIST_MEMO_KEY, whitelist_memo),
+    ]
+    .iter()
+    {
+        if let Some(tmpl) = template {
+            save(&mut deps.storage, key, tmpl)?;
+        } else {
+            remove(&mut deps.storage, key);
+        }
+    }
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetMemoTemplate {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// re-queries the public info of the example NFT
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `token_id` - optional ID of a token in the pool to use as the example
+fn try_refresh_example<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    token_id: Option<String>,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let token_id = if let Some(id) = token_id {
+        let rev_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_REVERSE, &deps.storage);
+        if may_load::<u32, _>(&rev_store, id.as_bytes())?.is_none() {
+            return Err(ContractError::TokenNotInPool(id.clone()).into());
+        }
+        id
+    } else {
+        let head = load_pool_head(&deps.storage)?;
+        let id_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_IDS, &deps.storage);
+        may_load::<(String, u8), _>(&id_store, &head.to_le_bytes())?
+            .ok_or_else(|| StdError::from(ContractError::PoolEmpty))?
+            .0
+    };
+    let contract =
+        load::<StoreContractInfo, _>(&deps.storage, COLLECTION_KEY)?.into_humanized(&deps.api)?;
+    // use the example viewing key if one was set
+    let viewer = may_load::<String, _>(&deps.storage, EXAMPLE_QUERY_VK_KEY)?
+        .map(|viewing_key| -> StdResult<ViewerInfo> {
+            let me_raw: CanonicalAddr = load(&deps.storage, MY_ADDRESS_KEY)?;
+            Ok(ViewerInfo {
+                address: deps.api.human_address(&me_raw)?,
+                viewing_key,
+            })
+        })
+        .transpose()?;
+    let nft_qry = Snip721QueryMsg::NftDossier { token_id, viewer };
+    let resp: NftDossierResponse =
+        nft_qry.query(&deps.querier, contract.code_hash, contract.address)?;
+    let store_doss = resp.nft_dossier.into_stored(&deps.api)?;
+    // the refreshed example replaces the primary example
+    let mut examples = load_examples(&deps.storage)?;
+    if examples.is_empty() {
+        examples.push(store_doss);
+    } else {
+        examples[0] = store_doss;
+    }
+    save(&mut deps.storage, EXAMPLE_KEY, &examples)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RefreshExample {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the order in which tokens are drawn from the pool
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `mode` - the DrainMode to use
+fn try_set_drain_mode<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    mode: DrainMode,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save(&mut deps.storage, DRAIN_MODE_KEY, &mode)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetDrainMode {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the number of NFTs the pool must hold before a listing may be created
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `min_fill` - minimum number of NFTs in the pool
+fn try_set_min_fill<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    min_fill: u32,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save(&mut deps.storage, MIN_FILL_KEY, &min_fill)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetMinFill {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// removes specific tokens from the pool and sends them to the admin
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `env` - a reference to the Env of contract's environment
+/// * `token_ids` - list of tokens to remove
+fn try_remove_tokens<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    token_ids: Vec<String>,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let mut counts = load_counts(&deps.storage)?;
+    // keep the deposit order if tokens are drawn sequentially
+    let sequential =
+        may_load::<DrainMode, _>(&deps.storage, DRAIN_MODE_KEY)? == Some(DrainMode::Sequential);
+    let mut removed: Vec<String> = Vec::new();
+    let mut not_found: Vec<String> = Vec::new();
+    for id in token_ids.into_iter() {
+        let rev_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_REVERSE, &deps.storage);
+        if let Some(idx) = may_load::<u32, _>(&rev_store, id.as_bytes())? {
+            let last_idx = pool_end(&deps.storage, counts.available)?
+                .checked_sub(1)
+                .ok_or_else(|| StdError::from(ContractError::PoolCorrupt))?;
+            if sequential {
+                shift_remove_token(&mut deps.storage, idx, last_idx)?;
+            } else {
+                swap_remove_token(&mut deps.storage, idx, last_idx)?;
+            }
+            counts.available = counts
+                .available
+                .checked_sub(1)
+                .ok_or_else(|| StdError::from(ContractError::PoolCorrupt))?;
+            removed.push(id);
+        } else {
+            not_found.push(id);
+        }
+    }
+    let mut messages = Vec::new();
+    if !removed.is_empty() {
+        save(&mut deps.storage, COUNT_KEY, &counts)?;
+        // an empty pool has nothing to show as an example
+        if counts.available == 0 {
+            remove(&mut deps.storage, EXAMPLE_KEY);
+        }
+        let contract = load::<StoreContractInfo, _>(&deps.storage, COLLECTION_KEY)?
+            .into_humanized(&deps.api)?;
+        let max_size: u32 = may_load(&deps.storage, MAX_TOKENS_PER_TRANSFER_MSG_KEY)?
+            .unwrap_or(DEFAULT_MAX_TRANSFER_BATCH);
+        let transfers = vec![Transfer {
+            recipient: env.message.sender.clone(),
+            token_ids: removed.clone(),
+            memo: format!("Removed from gumball: {}", env.contract.address),
+        }];
+        for batch in split_transfers(transfers, max_size as usize).into_iter() {
+            messages.push(
+                Snip721HandleMsg::BatchTransferNft { transfers: batch }.to_cosmos_msg(
+                    contract.code_hash.clone(),
+                    contract.address.clone(),
+                    None,
+                )?,
+            );
+        }
+    }
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RemoveTokens {
+            removed,
+            not_found,
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the number of blocks a recipient must wait between mints
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `blocks` - number of blocks between mints
+fn try_set_mint_cooldown<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    blocks: u64,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save(&mut deps.storage, MINT_COOLDOWN_KEY, &blocks)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetMintCooldown {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the types of mint callers the cooldown applies to
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `applies_to` - CooldownScope of the cooldown
+fn try_set_cooldown_mode<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    applies_to: CooldownScope,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save(&mut deps.storage, COOLDOWN_SCOPE_KEY, &applies_to)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetCooldownMode {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// removes a token from the pool and reserves it for a specific recipient
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `env` - a reference to the Env of contract's environment
+/// * `token_id` - ID of the token to reserve
+/// * `recipient` - a reference to the address the token is reserved for
+/// * `expires_at` - optional time after which the token returns to the pool
+fn try_reserve_token<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    token_id: String,
+    recipient: &HumanAddr,
+    expires_at: Option<u64>,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    if expires_at.map(|exp| exp <= env.block.time).unwrap_or(false) {
+        return Err(ContractError::ReservationInPast.into());
+    }
+    let recip_raw = deps.api.canonical_address(recipient)?;
+    let res_store = ReadonlyPrefixedStorage::new(PREFIX_RESERVED, &deps.storage);
+    if may_load::<ReservedToken, _>(&res_store, recip_raw.as_slice())?.is_some() {
+        return Err(ContractError::AlreadyReserved(recipient.clone()).into());
+    }
+    let rev_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_REVERSE, &deps.storage);
+    let idx = may_load::<u32, _>(&rev_store, token_id.as_bytes())?
+        .ok_or_else(|| StdError::from(ContractError::TokenNotInPool(token_id.to_string())))?;
+    let id_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_IDS, &deps.storage);
+    let idx_key = idx.to_le_bytes();
+    let (_, tier): (String, u8) =
+        may_load(&id_store, &idx_key)?.ok_or_else(|| StdError::from(ContractError::PoolCorrupt))?;
+    // keep the per-token info so it can be restored if the token returns to the pool
+    let memo_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_MEMO, &deps.storage);
+    let memo: Option<String> = may_load(&memo_store, &idx_key)?;
+    let tag_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_TAG, &deps.storage);
+    let tag: Option<String> = may_load(&tag_store, &idx_key)?;
+    let batch_store = ReadonlyPrefixedStorage::new(PREFIX_BATCH_TAG, &deps.storage);
+    let batch: Option<u8> = may_load(&batch_store, &idx_key)?;
+    let dep_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_DEPOSITOR, &deps.storage);
+    let depositor: Option<CanonicalAddr> = may_load(&dep_store, &idx_key)?;
+    let meta_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_METADATA, &deps.storage);
+    let metadata: Option<StoredNftDossierForListing> = may_load(&meta_store, token_id.as_bytes())?;
+    let mut counts = load_counts(&deps.storage)?;
+    let last_idx = pool_end(&deps.storage, counts.available)?
+        .checked_sub(1)
+        .ok_or_else(|| StdError::from(ContractError::PoolCorrupt))?;
+    // keep the deposit order if tokens are drawn sequentially
+    if may_load::<DrainMode, _>(&deps.storage, DRAIN_MODE_KEY)? == Some(DrainMode::Sequential) {
+        shift_remove_token(&mut deps.storage, idx, last_idx)?;
+    } else {
+        swap_remove_token(&mut deps.storage, idx, last_idx)?;
+    }
+    counts.available = counts
+        .available
+        .checked_sub(1)
+        .ok_or_else(|| StdError::from(ContractError::PoolCorrupt))?;
+    save(&mut deps.storage, COUNT_KEY, &counts)?;
+    let mut res_store = PrefixedStorage::new(PREFIX_RESERVED, &mut deps.storage);
+    save(
+        &mut res_store,
+        recip_raw.as_slice(),
+        &ReservedToken {
+            token_id,
+            tier,
+            expires_at,
+            memo,
+            tag,
+            batch,
+            depositor,
+            metadata,
+        },
+    )?;
+    let reserved: u32 = may_load(&deps.storage, RESERVED_COUNT_KEY)?.unwrap_or(0);
+    save(
+        &mut deps.storage,
+        RESERVED_COUNT_KEY,
+        &reserved.saturating_add(1),
+    )?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::ReserveToken {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the maximum number of admins
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `max` - maximum number of admins
+fn try_set_max_admins<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    max: u32,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    if (max as usize) < admins.len() {
+        return Err(ContractError::MaxAdminsBelowCount(admins.len() as u32).into());
+    }
+    save(&mut deps.storage, MAX_ADMINS_KEY, &max)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetMaxAdmins {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// transfers primary admin status to a new address, replacing the old primary in the admin list
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `new_admin` - a reference to the new primary admin
+fn try_transfer_primary<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    new_admin: &HumanAddr,
+) -> HandleResult {
+    // only allow the primary admin to do this, and only while still an admin
+    let sender_raw = deps.api.canonical_address(sender)?;
+    let mut admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    if !admins.contains(&sender_raw)
+        || may_load::<CanonicalAddr, _>(&deps.storage, PRIMARY_ADMIN_KEY)?
+            != Some(sender_raw.clone())
+    {
+        return Err(StdError::unauthorized());
+    }
+    let new_raw = deps.api.canonical_address(new_admin)?;
+    if !admins.contains(&new_raw) {
+        admins.push(new_raw.clone());
+    }
+    if new_raw != sender_raw {
+        admins.retain(|a| *a != sender_raw);
+    }
+    save(&mut deps.storage, ADMINS_KEY, &admins)?;
+    save(&mut deps.storage, PRIMARY_ADMIN_KEY, &new_raw)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::AdminsList {
+            admins: admins
+                .iter()
+                .map(|a| deps.api.human_address(a))
+                .collect::<StdResult<Vec<HumanAddr>>>()?,
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the pool size at or below which mints log a low pool alert
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `threshold` - pool size that triggers the alert
+fn try_set_low_pool_threshold<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    threshold: u32,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save(&mut deps.storage, LOW_POOL_THRESHOLD_KEY, &threshold)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetLowPoolThreshold {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// revoke the ability to use several permits
+///
+/// # Arguments
+///
+/// * `storage` - mutable reference to the contract's storage
+/// * `sender` - a reference to the message sender
+/// * `permit_names` - list of names of the permits to revoke
+fn revoke_permits<S: Storage>(
+    storage: &mut S,
+    sender: &HumanAddr,
+    permit_names: Vec<String>,
+) -> HandleResult {
+    if permit_names.is_empty() {
+        return Err(ContractError::NoPermitNames.into());
+    }
+    let mut revoked_count = 0u32;
+    let mut failed: Vec<String> = Vec::new();
+    for name in permit_names.into_iter() {
+        // a blank name could never match a signed permit
+        if name.trim().is_empty() {
+            failed.push(name);
+            continue;
+        }
+        RevokedPermits::revoke_permit(storage, PREFIX_REVOKED_PERMITS, sender, &name);
+        revoked_count += 1;
+    }
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RevokePermits {
+            revoked_count,
+            failed,
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// updates the code hash of the collection contract and re-registers with it
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `env` - the Env of contract's environment
+/// * `code_hash` - new code hash of the collection contract
+fn try_update_coll_hash<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    code_hash: String,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    if code_hash.trim().is_empty() {
+        return Err(ContractError::BlankCodeHash.into());
+    }
+    // only the code hash is replaced, the collection address stays the same
+    let mut stored: StoreContractInfo = load(&deps.storage, COLLECTION_KEY)?;
+    stored.code_hash = code_hash;
+    save(&mut deps.storage, COLLECTION_KEY, &stored)?;
+    let contract = stored.into_humanized(&deps.api)?;
+    let messages = vec![register_receive_nft_msg(
+        env.contract_code_hash,
+        Some(true),
+        None,
+        BLOCK_SIZE,
+        contract.code_hash,
+        contract.address,
+    )?];
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::UpdateCollection {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// adds a minting round
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `round` - the Round to add
+fn try_add_round<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    round: Round,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let count: u32 = may_load(&deps.storage, ROUND_COUNT_KEY)?.unwrap_or(0);
+    if round.id != count {
+        return Err(ContractError::RoundOutOfOrder { next: count }.into());
+    }
+    if round.opens_at >= round.closes_at {
+        return Err(ContractError::InvalidRoundTimes.into());
+    }
+    let mut round_store = PrefixedStorage::new(PREFIX_ROUNDS, &mut deps.storage);
+    save(&mut round_store, &round.id.to_le_bytes(), &round)?;
+    save(&mut deps.storage, ROUND_COUNT_KEY, &count.saturating_add(1))?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::AddRound {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// makes the next minting round active
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+fn try_advance_round<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let count: u32 = may_load(&deps.storage, ROUND_COUNT_KEY)?.unwrap_or(0);
+    // the first advance activates round 0
+    let round_id = may_load::<u32, _>(&deps.storage, CURRENT_ROUND_KEY)?.map_or(0, |r| r + 1);
+    if round_id >= count {
+        return Err(ContractError::NoNextRound.into());
+    }
+    save(&mut deps.storage, CURRENT_ROUND_KEY, &round_id)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::AdvanceRound { round_id })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets whether only whitelisted addresses may mint
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `enabled` - true if only whitelisted addresses may mint
+fn try_set_whitelist_only<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    enabled: bool,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save(&mut deps.storage, WHITELIST_ONLY_KEY, &enabled)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetWhitelistOnly {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the minimum number of bytes of entropy that must be provided
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `length` - minimum entropy length in bytes
+fn try_set_min_entropy_len<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    length: u8,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save(&mut deps.storage, MIN_ENTROPY_LEN_KEY, &length)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetMinEntropyLength {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the gumball's branding info
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `name` - gumball name
+/// * `description` - optional gumball description
+/// * `image_uri` - optional URI of the gumball's image
+fn try_set_gumball_info<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    name: String,
+    description: Option<String>,
+    image_uri: Option<String>,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    if let Some(uri) = image_uri.as_ref() {
+        if !["http://", "https://", "ipfs://", "ar://"]
+            .iter()
+            .any(|scheme| uri.starts_with(scheme))
+        {
+            return Err(ContractError::InvalidImageUri.into());
+        }
+    }
+    let info = GumballInfo {
+        name,
+        description,
+        image_uri,
+    };
+    save(&mut deps.storage, GUMBALL_INFO_KEY, &info)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetGumballInfo {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the maximum number of buyers a listing may pass in one Mint
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `listing` - a reference to the listing address
+/// * `cap` - maximum number of buyers per Mint
+fn try_set_listing_buyer_cap<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    listing: &HumanAddr,
+    cap: u32,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let listing_raw = deps.api.canonical_address(listing)?;
+    let mut cap_store = PrefixedStorage::new(PREFIX_BUYER_CAP, &mut deps.storage);
+    save(&mut cap_store, listing_raw.as_slice(), &cap)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetListingBuyerCap {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// approves a factory to register one listing, or cancels a factory's pending registration
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `factory` - a reference to the factory address
+/// * `is_add` - true if approving the factory
+fn try_update_approved_factory<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    factory: &HumanAddr,
+    is_add: bool,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let factory_raw = deps.api.canonical_address(factory)?;
+    let mut exp_store = PrefixedStorage::new(PREFIX_EXPECTED_FACTORIES, &mut deps.storage);
+    let status = "success".to_string();
+    let resp = if is_add {
+        // don't discard the info of a listing the factory is already creating
+        if may_load::<ExpectedListing, _>(&exp_store, factory_raw.as_slice())?.is_none() {
+            let expected = ExpectedListing {
+                label: None,
+                closes_at: None,
+            };
+            save(&mut exp_store, factory_raw.as_slice(), &expected)?;
+        }
+        HandleAnswer::AddApprovedFactory { status }
+    } else {
+        remove(&mut exp_store, factory_raw.as_slice());
+        HandleAnswer::RemoveApprovedFactory { status }
+    };
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&resp)?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the maximum number of tokens sent in one BatchTransferNft message
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `size` - maximum number of tokens per message
+fn try_set_max_transfer_batch<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    size: u32,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    if size == 0 {
+        return Err(ContractError::InvalidTransferBatchSize.into());
+    }
+    save(&mut deps.storage, MAX_TOKENS_PER_TRANSFER_MSG_KEY, &size)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetMaxTransferBatchSize {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// mixes a user's entropy into the entropy pool included in future mints
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `entropy` - entropy String to contribute
+fn try_contribute_entropy<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    entropy: &str,
+) -> HandleResult {
+    let sender_raw = deps.api.canonical_address(sender)?;
+    let mut pool = load_entropy_pool(&deps.storage)?;
+    pool.extend_from_slice(sender_raw.as_slice());
+    pool.extend_from_slice(entropy.as_bytes());
+    save(
+        &mut deps.storage,
+        ENTROPY_POOL_KEY,
+        &sha_256(&pool).to_vec(),
+    )?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::ContributeEntropy {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns StdResult<Vec<u8>>
+///
+/// loads the entropy pool hash, falling back to the PRNG seed for contracts instantiated before
+/// the pool existed
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+fn load_entropy_pool<S: ReadonlyStorage>(storage: &S) -> StdResult<Vec<u8>> {
+    match may_load(storage, ENTROPY_POOL_KEY)? {
+        Some(pool) => Ok(pool),
+        None => load(storage, PRNG_SEED_KEY),
+    }
+}
+
+/// Returns HandleResult
+///
+/// removes tokens from the pool and returns them to the admin who deposited them
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `env` - a reference to the Env of contract's environment
+/// * `token_ids` - list of tokens to recall
+fn try_recall_tokens<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    token_ids: Vec<String>,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    // verify every token before removing any of them
+    for id in token_ids.iter() {
+        let rev_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_REVERSE, &deps.storage);
+        let idx = may_load::<u32, _>(&rev_store, id.as_bytes())?
+            .ok_or_else(|| StdError::from(ContractError::TokenNotInPool(id.clone())))?;
+        let dep_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_DEPOSITOR, &deps.storage);
+        if may_load::<CanonicalAddr, _>(&dep_store, &idx.to_le_bytes())?.as_ref()
+            != Some(&sender_raw)
+        {
+            return Err(ContractError::NotDepositor(id.clone()).into());
+        }
+    }
+    let mut counts = load_counts(&deps.storage)?;
+    // keep the deposit order if tokens are drawn sequentially
+    let sequential =
+        may_load::<DrainMode, _>(&deps.storage, DRAIN_MODE_KEY)? == Some(DrainMode::Sequential);
+    let mut recalled: Vec<String> = Vec::new();
+    for id in token_ids.into_iter() {
+        let rev_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_REVERSE, &deps.storage);
+        // skip duplicates in the request
+        if let Some(idx) = may_load::<u32, _>(&rev_store, id.as_bytes())? {
+            let last_idx = pool_end(&deps.storage, counts.available)? - 1;
+            if sequential {
+                shift_remove_token(&mut deps.storage, idx, last_idx)?;
+            } else {
+                swap_remove_token(&mut deps.storage, idx, last_idx)?;
+            }
+            counts.available -= 1;
+            recalled.push(id);
+        }
+    }
+    let mut messages = Vec::new();
+    if !recalled.is_empty() {
+        save(&mut deps.storage, COUNT_KEY, &counts)?;
+        // an empty pool has nothing to show as an example
+        if counts.available == 0 {
+            remove(&mut deps.storage, EXAMPLE_KEY);
+        }
+        let contract = load::<StoreContractInfo, _>(&deps.storage, COLLECTION_KEY)?
+            .into_humanized(&deps.api)?;
+        let max_size: u32 = may_load(&deps.storage, MAX_TOKENS_PER_TRANSFER_MSG_KEY)?
+            .unwrap_or(DEFAULT_MAX_TRANSFER_BATCH);
+        let transfers = vec![Transfer {
+            recipient: env.message.sender.clone(),
+            token_ids: recalled.clone(),
+            memo: format!("Recalled from gumball: {}", env.contract.address),
+        }];
+        for batch in split_transfers(transfers, max_size as usize).into_iter() {
+            messages.push(
+                Snip721HandleMsg::BatchTransferNft { transfers: batch }.to_cosmos_msg(
+                    contract.code_hash.clone(),
+                    contract.address.clone(),
+                    None,
+                )?,
+            );
+        }
+    }
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RecallTokens { recalled })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// removes all expired entries from the global whitelist
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `env` - a reference to the Env of contract's environment
+fn try_remove_expired<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let total: u32 = may_load(&deps.storage, WHITELIST_COUNT_KEY)?.unwrap_or(0);
+    let mut removed = 0u32;
+    // scan from the end, so a swap-removal only moves an entry that was already checked
+    for pos in (0..total).rev() {
+        let order_store = ReadonlyPrefixedStorage::new(PREFIX_WHITELIST_ORDER, &deps.storage);
+        let raw = if let Some(r) = may_load::<CanonicalAddr, _>(&order_store, &pos.to_le_bytes())? {
+            r
+        } else {
+            continue;
+        };
+        let white_store = ReadonlyPrefixedStorage::new(PREFIX_WHITELIST, &deps.storage);
+        let entry = load_whitelist_entry(&white_store, &raw)?;
+        if entry
+            .expires_at
+            .map(|exp| exp <= env.block.time)
+            .unwrap_or(false)
+        {
+            save_whitelist_quantity(&mut deps.storage, None, &raw, 0, None)?;
+            removed += 1;
+        }
+    }
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RemoveExpired { removed })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// proposes removing every token from the pool and sending them to the recipient
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `env` - a reference to the Env of contract's environment
+/// * `recipient` - a reference to the address to receive the tokens
+fn try_propose_clear_pool<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    recipient: &HumanAddr,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let recip_raw = deps.api.canonical_address(recipient)?;
+    let mut preimage = recip_raw.as_slice().to_vec();
+    preimage.extend_from_slice(sender_raw.as_slice());
+    preimage.extend_from_slice(&env.block.height.to_le_bytes());
+    let proposal_hash = base64::encode(sha_256(&preimage));
+    let expires_at_block = env.block.height.saturating_add(CLEAR_PROPOSAL_BLOCKS);
+    save(
+        &mut deps.storage,
+        PENDING_CLEAR_KEY,
+        &PendingClear {
+            recipient: recip_raw,
+            proposal_hash: proposal_hash.clone(),
+        },
+    )?;
+    save(
+        &mut deps.storage,
+        PENDING_CLEAR_EXPIRY_KEY,
+        &expires_at_block,
+    )?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::ProposeClearPool {
+            proposal_hash,
+            expires_at_block,
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// confirms a pending proposal, removing every token from the pool and sending them to the
+/// proposal's recipient
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `env` - a reference to the Env of contract's environment
+/// * `proposal_hash` - hash of the proposal being confirmed
+fn try_confirm_clear_pool<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    proposal_hash: &str,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let pending: PendingClear = may_load(&deps.storage, PENDING_CLEAR_KEY)?
+        .ok_or_else(|| StdError::from(ContractError::NoPendingClear))?;
+    if pending.proposal_hash != proposal_hash {
+        return Err(ContractError::ClearProposalMismatch.into());
+    }
+    let expires_at_block: u64 = load(&deps.storage, PENDING_CLEAR_EXPIRY_KEY)?;
+    if env.block.height > expires_at_block {
+        return Err(ContractError::ClearProposalExpired.into());
+    }
+    remove(&mut deps.storage, PENDING_CLEAR_KEY);
+    remove(&mut deps.storage, PENDING_CLEAR_EXPIRY_KEY);
+    let recipient = deps.api.human_address(&pending.recipient)?;
+    let mut counts = load_counts(&deps.storage)?;
+    let mut token_ids: Vec<String> = Vec::new();
+    let mut tiers: Vec<u8> = Vec::new();
+    for idx in load_pool_head(&deps.storage)?..pool_end(&deps.storage, counts.available)? {
+        let key = idx.to_le_bytes();
+        let mut id_store = PrefixedStorage::new(PREFIX_TOKEN_IDS, &mut deps.storage);
+        let (id, tier): (String, u8) =
+            may_load(&id_store, &key)?.ok_or_else(|| StdError::from(ContractError::PoolCorrupt))?;
+        remove(&mut id_store, &key);
+        let mut rev_store = PrefixedStorage::new(PREFIX_TOKEN_REVERSE, &mut deps.storage);
+        remove(&mut rev_store, id.as_bytes());
+        let mut meta_store = PrefixedStorage::new(PREFIX_TOKEN_METADATA, &mut deps.storage);
+        remove(&mut meta_store, id.as_bytes());
+        let mut memo_store = PrefixedStorage::new(PREFIX_TOKEN_MEMO, &mut deps.storage);
+        remove(&mut memo_store, &key);
+        let mut tag_store = PrefixedStorage::new(PREFIX_TOKEN_TAG, &mut deps.storage);
+        remove(&mut tag_store, &key);
+        let mut batch_store = PrefixedStorage::new(PREFIX_BATCH_TAG, &mut deps.storage);
+        remove(&mut batch_store, &key);
+        let mut dep_store = PrefixedStorage::new(PREFIX_TOKEN_DEPOSITOR, &mut deps.storage);
+        remove(&mut dep_store, &key);
+        let mut pos_store = PrefixedStorage::new(PREFIX_TIER_POSITION, &mut deps.storage);
+        if let Some(pos) = may_load::<u32, _>(&pos_store, &key)? {
+            remove(&mut pos_store, &key);
+            let mut tier_store = PrefixedStorage::new(PREFIX_TIER_INDEX, &mut deps.storage);
+            remove(&mut tier_store, &tier_key(tier, pos));
+        }
+        if !tiers.contains(&tier) {
+            tiers.push(tier);
+        }
+        token_ids.push(id);
+    }
+    let mut cnt_store = PrefixedStorage::new(PREFIX_TIER_COUNT, &mut deps.storage);
+    for tier in tiers.into_iter() {
+        remove(&mut cnt_store, &[tier]);
+    }
+    let cleared = counts.available;
+    counts.available = 0;
+    save(&mut deps.storage, COUNT_KEY, &counts)?;
+    remove(&mut deps.storage, SEQ_HEAD_KEY);
+    remove(&mut deps.storage, EXAMPLE_KEY);
+    save(&mut deps.storage, POOL_CLEARED_KEY, &true)?;
+    let mut messages = Vec::new();
+    if !token_ids.is_empty() {
+        let contract = load::<StoreContractInfo, _>(&deps.storage, COLLECTION_KEY)?
+            .into_humanized(&deps.api)?;
+        let max_size: u32 = may_load(&deps.storage, MAX_TOKENS_PER_TRANSFER_MSG_KEY)?
+            .unwrap_or(DEFAULT_MAX_TRANSFER_BATCH);
+        let transfers = vec![Transfer {
+            recipient,
+            token_ids,
+            memo: format!("Cleared from gumball: {}", env.contract.address),
+        }];
+        for batch in split_transfers(transfers, max_size as usize).into_iter() {
+            messages.push(
+                Snip721HandleMsg::BatchTransferNft { transfers: batch }.to_cosmos_msg(
+                    contract.code_hash.clone(),
+                    contract.address.clone(),
+                    None,
+                )?,
+            );
+        }
+    }
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::ConfirmClearPool { cleared })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// changes the NFT collection contract and registers to receive its tokens
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `env` - Env of contract's environment
+/// * `new_nft_contract` - code hash and address of the new collection contract
+fn try_update_collection<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    new_nft_contract: ContractInfo,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    // the pooled and reserved token IDs belong to the current collection
+    if load_counts(&deps.storage)?.available > 0 {
+        return Err(ContractError::PoolNotEmpty.into());
+    }
+    let reserved: u32 = may_load(&deps.storage, RESERVED_COUNT_KEY)?.unwrap_or(0);
+    if reserved > 0 {
+        return Err(ContractError::ReservationsOutstanding(reserved).into());
+    }
+    let mut messages = vec![register_receive_nft_msg(
+        env.contract_code_hash,
+        Some(true),
+        None,
+        BLOCK_SIZE,
+        new_nft_contract.code_hash.clone(),
+        new_nft_contract.address.clone(),
+    )?];
+    // the example query key must be known to the new collection
+    if let Some(viewing_key) = may_load::<String, _>(&deps.storage, EXAMPLE_QUERY_VK_KEY)? {
+        messages.push(set_viewing_key_msg(
+            viewing_key,
+            None,
+            BLOCK_SIZE,
+            new_nft_contract.code_hash.clone(),
+            new_nft_contract.address.clone(),
+        )?);
+    }
+    // the examples belong to the old collection
+    remove(&mut deps.storage, EXAMPLE_KEY);
+    let new_address = new_nft_contract.address.to_string();
+    let contract = new_nft_contract.into_store(&deps.api)?;
+    save(&mut deps.storage, COLLECTION_KEY, &contract)?;
+    Ok(HandleResponse {
+        messages,
+        log: vec![log("collection_updated", new_address)],
+        data: Some(to_binary(&HandleAnswer::UpdateCollection {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// sets the bounds of listing prices
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `min` - optional minimum listing price
+/// * `max` - optional maximum listing price
+fn try_set_price_bounds<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    min: Option<Uint128>,
+    max: Option<Uint128>,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    save_price_bounds(&mut deps.storage, min, max)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetPriceBounds {
+            status: "success".to_string(),
+        })?),
+    })
+}
+
+/// Returns StdResult<()>
+///
+/// saves the bounds of listing prices, removing any bound that is None
+///
+/// # Arguments
+///
+/// * `storage` - a mutable reference to the contract's storage
+/// * `min` - optional minimum listing price
+/// * `max` - optional maximum listing price
+fn save_price_bounds<S: Storage>(
+    storage: &mut S,
+    min: Option<Uint128>,
+    max: Option<Uint128>,
+) -> StdResult<()> {
+    if let (Some(mn), Some(mx)) = (min, max) {
+        if mn > mx {
+            return Err(ContractError::InvalidPriceBounds.into());
+        }
+    }
+    if let Some(mn) = min {
+        save(storage, MIN_PRICE_KEY, &mn)?;
+    } else {
+        remove(storage, MIN_PRICE_KEY);
+    }
+    if let Some(mx) = max {
+        save(storage, MAX_PRICE_KEY, &mx)?;
+    } else {
+        remove(storage, MAX_PRICE_KEY);
+    }
+    Ok(())
+}
+
+/// Returns HandleResult
+///
+/// removes entries from the global whitelist, starting with the most recently added
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `max_entries` - optional maximum number of entries to remove
+fn try_clear_whitelist<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    max_entries: Option<u32>,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let total: u32 = may_load(&deps.storage, WHITELIST_COUNT_KEY)?.unwrap_or(0);
+    let cleared_count = max_entries.map_or(total, |max| max.min(total));
+    let new_total = total - cleared_count;
+    // removing from the end of the index leaves the remaining positions unchanged
+    for pos in new_total..total {
+        let mut order_store = PrefixedStorage::new(PREFIX_WHITELIST_ORDER, &mut deps.storage);
+        let raw: Option<CanonicalAddr> = may_load(&order_store, &pos.to_le_bytes())?;
+        remove(&mut order_store, &pos.to_le_bytes());
+        if let Some(addr) = raw {
+            let mut white_store = PrefixedStorage::new(PREFIX_WHITELIST, &mut deps.storage);
+            remove(&mut white_store, addr.as_slice());
+            let mut pos_store = PrefixedStorage::new(PREFIX_WHITELIST_POSITION, &mut deps.storage);
+            remove(&mut pos_store, addr.as_slice());
+        }
+    }
+    save(&mut deps.storage, WHITELIST_COUNT_KEY, &new_total)?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::ClearWhitelist { cleared_count })?),
+    })
+}
+
+/// Returns HandleResult
+///
+/// returns the tokens reserved for the recipients to the pool
+///
+/// # Arguments
+///
+/// * `deps` - a mutable reference to Extern containing all the contract's external dependencies
+/// * `sender` - a reference to the message sender
+/// * `recipients` - list of recipients whose reservations are cancelled
+fn try_cancel_reservations<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    sender: &HumanAddr,
+    recipients: Vec<HumanAddr>,
+) -> HandleResult {
+    // only allow admins to do this
+    let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+    let sender_raw = deps.api.canonical_address(sender)?;
+    if !admins.contains(&sender_raw) {
+        return Err(StdError::unauthorized());
+    }
+    let mut cancelled: Vec<String> = Vec::new();
+    let mut not_found: Vec<HumanAddr> = Vec::new();
+    for recipient in recipients.into_iter() {
+        let recip_raw = deps.api.canonical_address(&recipient)?;
+        let res_store = ReadonlyPrefixedStorage::new(PREFIX_RESERVED, &deps.storage);
+        if let Some(res) = may_load::<ReservedToken, _>(&res_store, recip_raw.as_slice())? {
+            cancelled.push(res.token_id.clone());
+            release_reservation(&mut deps.storage, &recip_raw)?;
+            unreserve_token(&mut deps.storage, res)?;
+        } else {
+            not_found.push(recipient);
+        }
+    }
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::CancelReservations {
+            cancelled,
+            not_found,
+        })?),
+    })
+}
+
+/////////////////////////////////////// Query /////////////////////////////////////
+/// Returns QueryResult
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+/// * `msg` - QueryMsg passed in with the query call
+pub fn query<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>, msg: QueryMsg) -> QueryResult {
+    let response = match msg {
+        QueryMsg::Admins { viewer, permit } => query_admins(deps, viewer, permit),
+        QueryMsg::NftListingDisplay {} => query_listing_disp(deps),
+        QueryMsg::Counts {} => query_counts(&deps.storage),
+        QueryMsg::NftContract {} => query_nft_contract(deps),
+        QueryMsg::ListingRegistry {
+            viewer,
+            permit,
+            page,
+            page_size,
+        } => query_listing_registry(deps, viewer, permit, page, page_size),
+        QueryMsg::ReferralCount { referrer } => query_referral_count(deps, &referrer),
+        QueryMsg::PrngAuditLog {
+            start,
+            limit,
+            viewer,
+            permit,
+        } => query_prng_audit(deps, viewer, permit, start, limit),
+        QueryMsg::BridgeStats {} => query_bridge_stats(&deps.storage),
+        QueryMsg::TaggedTokenCount {
+            tag,
+            viewer,
+            permit,
+        } => query_tagged_count(deps, viewer, permit, &tag),
+        QueryMsg::PoolTokens {
+            page,
+            page_size,
+            viewer,
+            permit,
+        } => query_pool_tokens(deps, viewer, permit, page, page_size),
+        QueryMsg::IsPaused {} => query_is_paused(&deps.storage),
+        QueryMsg::AddressMintCount {
+            address,
+            viewer,
+            permit,
+        } => query_address_count(deps, viewer, permit, &address),
+        QueryMsg::RegisteredListings {
+            page,
+            page_size,
+            viewer,
+            permit,
+        } => query_registered_listings(deps, viewer, permit, page, page_size),
+        QueryMsg::IsWhitelisted {
+            address,
+            viewer,
+            permit,
+        } => query_is_whitelisted(deps, viewer, permit, &address),
+        QueryMsg::WhitelistSnapshot {
+            page,
+            page_size,
+            viewer,
+            permit,
+        } => query_whitelist_snapshot(deps, viewer, permit, page, page_size),
+        QueryMsg::SchemaVersion {} => query_schema_version(&deps.storage),
+        QueryMsg::ContractInfo {} => query_contract_info(&deps.storage),
+        QueryMsg::CurrentRound {} => query_current_round(&deps.storage),
+        QueryMsg::WhitelistOnly {} => query_whitelist_only(&deps.storage),
+        QueryMsg::PrngFreshness { viewer, permit } => query_prng_freshness(deps, viewer, permit),
+        QueryMsg::PoolIntegrity { viewer, permit } => query_pool_integrity(deps, viewer, permit),
+        QueryMsg::GumballInfo {} => query_gumball_info(&deps.storage),
+        QueryMsg::Status {} => query_status(deps),
+        QueryMsg::TokensInRange {
+            start_index,
+            end_index,
+            viewer,
+            permit,
+        } => query_tokens_in_range(deps, viewer, permit, start_index, end_index),
+        QueryMsg::ListingMintCount {
+            listing_address,
+            viewer,
+            permit,
+        } => query_listing_mint_count(deps, viewer, permit, listing_address),
+        QueryMsg::MintLog { viewer, permit } => query_mint_log(deps, viewer, permit),
+        QueryMsg::TokenAtIndex {
+            index,
+            viewer,
+            permit,
+        } => query_token_at_index(deps, viewer, permit, index),
+        QueryMsg::TokenMetadata {
+            token_id,
+            viewer,
+            permit,
+        } => query_token_metadata(deps, viewer, permit, token_id),
+        QueryMsg::TraitAggregates {
+            trait_type,
+            viewer,
+            permit,
+        } => query_trait_aggregates(deps, viewer, permit, trait_type),
+    };
+    pad_query_result(response, BLOCK_SIZE)
+}
+
+/// Returns QueryResult displaying the number of NFTs available and the number of NFTs released
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+fn query_counts<S: ReadonlyStorage>(storage: &S) -> QueryResult {
+    let counts = load_counts(storage)?;
+    let stats_level: StatsLevel = may_load(storage, STATS_LEVEL_KEY)?.unwrap_or(StatsLevel::Basic);
+    let mint_stats = if stats_level == StatsLevel::Full {
+        Some(may_load::<MintStats, _>(storage, MINT_STATS_KEY)?.unwrap_or_default())
+    } else {
+        None
+    };
+
     to_binary(&QueryAnswer::Counts {
         available: counts.available,
         released: counts.released,
+        mint_stats,
+        released_cap: may_load(storage, RELEASED_CAP_KEY)?,
+        last_deposit_block: counts.last_deposit_block,
+        last_mint_block: counts.last_mint_block,
+    })
+}
+
+/// Returns QueryResult displaying code hash and address of the nft contract this gumball is used with
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+fn query_nft_contract<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> QueryResult {
+    let contract =
+        load::<StoreContractInfo, _>(&deps.storage, COLLECTION_KEY)?.into_humanized(&deps.api)?;
+
+    to_binary(&QueryAnswer::NftContract {
+        code_hash: contract.code_hash,
+        address: contract.address,
+    })
+}
+
+/// Returns QueryResult displaying an example NFT's public information
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+fn query_listing_disp<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> QueryResult {
+    let contr_strd: StoreContractInfo = load(&deps.storage, COLLECTION_KEY)?;
+    let counts = load_counts(&deps.storage)?;
+    // if a blind-box preview is enabled, display the preview contract's version of a pool token
+    let mut preview_doss: Option<NftDossierForListing> = None;
+    if let Some(preview) = may_load::<PreviewContract, _>(&deps.storage, PREVIEW_CONTRACT_KEY)? {
+        if preview.enabled && counts.available > 0 {
+            let id_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_IDS, &deps.storage);
+            if let Some((token_id, _)) = may_load::<(String, u8), _>(
+                &id_store,
+                &load_pool_head(&deps.storage)?.to_le_bytes(),
+            )? {
+                let prev_contract = preview.contract.into_humanized(&deps.api)?;
+                let nft_qry = Snip721QueryMsg::NftDossier {
+                    token_id,
+                    viewer: None,
+                };
+                let resp: StdResult<NftDossierResponse> = nft_qry.query(
+                    &deps.querier,
+                    prev_contract.code_hash,
+                    prev_contract.address,
+                );
+                preview_doss = resp.ok().map(|r| r.nft_dossier);
+            }
+        }
+    }
+    let nft_infos = load_examples(&deps.storage)?
+        .into_iter()
+        .map(|doss| doss.into_humanized(&deps.api))
+        .collect::<StdResult<Vec<NftDossierForListing>>>()?;
+    let nft_info = if let Some(doss) = preview_doss {
+        doss
+    } else {
+        nft_infos.first().cloned().unwrap_or(NftDossierForListing {
+            public_metadata: None,
+            royalty_info: None,
+            mint_run_info: None,
+        })
+    };
+    // show the cached info of other pool tokens if more than one example is wanted
+    let preview_count: u8 = may_load(&deps.storage, LISTING_PREVIEW_COUNT_KEY)?.unwrap_or(1);
+    let head = load_pool_head(&deps.storage)?;
+    let end = head + (preview_count as u32).min(counts.available);
+    let id_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_IDS, &deps.storage);
+    let meta_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_METADATA, &deps.storage);
+    let mut additional_examples: Vec<NftDossierForListing> = Vec::new();
+    for idx in head + 1..end {
+        if let Some((token_id, _)) = may_load::<(String, u8), _>(&id_store, &idx.to_le_bytes())? {
+            if let Some(doss) =
+                may_load::<StoredNftDossierForListing, _>(&meta_store, token_id.as_bytes())?
+            {
+                additional_examples.push(doss.into_humanized(&deps.api)?);
+            }
+        }
+    }
+    to_binary(&QueryAnswer::NftListingDisplay {
+        nft_info,
+        nft_infos,
+        name: may_load::<GumballInfo, _>(&deps.storage, GUMBALL_INFO_KEY)?.map(|i| i.name),
+        nft_contract_address: deps.api.human_address(&contr_strd.address)?,
+        mintable: counts.available > 0,
+        price: may_load(&deps.storage, DISPLAY_PRICE_KEY)?,
+        additional_examples,
+    })
+}
+
+/// Returns QueryResult displaying the admin list
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+/// * `viewer` - optional address and key making an authenticated query request
+/// * `permit` - optional permit with "owner" permission
+fn query_admins<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewer: Option<ViewerInfo>,
+    permit: Option<Permit>,
+) -> QueryResult {
+    // only allow admins to do this
+    let admins = authenticate_admin(deps, viewer, permit)?;
+    let max_admins: u32 = may_load(&deps.storage, MAX_ADMINS_KEY)?.unwrap_or(DEFAULT_MAX_ADMINS);
+    to_binary(&QueryAnswer::Admins {
+        admins: admins
+            .iter()
+            .map(|a| deps.api.human_address(a))
+            .collect::<StdResult<Vec<HumanAddr>>>()?,
+        max_admins,
+    })
+}
+
+/// Returns QueryResult displaying a page of the registered listings
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+/// * `viewer` - optional address and key making an authenticated query request
+/// * `permit` - optional permit with "owner" permission
+/// * `page` - page number to display
+/// * `page_size` - number of listings per page
+fn query_listing_registry<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewer: Option<ViewerInfo>,
+    permit: Option<Permit>,
+    page: u32,
+    page_size: u32,
+) -> QueryResult {
+    // only allow admins to do this
+    authenticate_admin(deps, viewer, permit)?;
+    let total: u32 = may_load(&deps.storage, LIST_COUNT_KEY)?.unwrap_or(0);
+    let start = page.saturating_mul(page_size);
+    let end = start.saturating_add(page_size).min(total);
+    let idx_store = ReadonlyPrefixedStorage::new(PREFIX_LIST_REGISTRY_INDEX, &deps.storage);
+    let cnt_store = ReadonlyPrefixedStorage::new(PREFIX_LISTING_MINT_COUNT, &deps.storage);
+    let mut listings: Vec<ListingRecord> = Vec::new();
+    for idx in start..end {
+        // skip any index entries that are missing instead of failing the whole page
+        if let Some(stored) = may_load::<StoredListing, _>(&idx_store, &idx.to_le_bytes())? {
+            let minted_count: u64 = may_load(&cnt_store, stored.address.as_slice())?.unwrap_or(0);
+            listings.push(ListingRecord {
+                address: deps.api.human_address(&stored.address)?,
+                label: stored.label,
+                closes_at: stored.closes_at,
+                minted_count,
+            });
+        }
+    }
+    to_binary(&QueryAnswer::ListingRegistry {
+        listings,
+        total,
+        page,
+    })
+}
+
+/// Returns QueryResult displaying the number of tokens minted by listings on behalf of a referrer
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+/// * `referrer` - a reference to the referrer's address
+fn query_referral_count<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    referrer: &HumanAddr,
+) -> QueryResult {
+    let refr_raw = deps.api.canonical_address(referrer)?;
+    let ref_store = ReadonlyPrefixedStorage::new(PREFIX_REFERRAL_COUNTS, &deps.storage);
+    let count: u32 = may_load(&ref_store, refr_raw.as_slice())?.unwrap_or(0);
+
+    to_binary(&QueryAnswer::ReferralCount { count })
+}
+
+/// Returns QueryResult displaying entries from the PRNG audit log
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+/// * `viewer` - optional address and key making an authenticated query request
+/// * `permit` - optional permit with "owner" permission
+/// * `start` - index of the first entry to display
+/// * `limit` - maximum number of entries to display
+fn query_prng_audit<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewer: Option<ViewerInfo>,
+    permit: Option<Permit>,
+    start: u64,
+    limit: u8,
+) -> QueryResult {
+    // only allow admins to do this
+    authenticate_admin(deps, viewer, permit)?;
+    let total: u64 = may_load(&deps.storage, PRNG_AUDIT_COUNT_KEY)?.unwrap_or(0);
+    // entries older than the ring buffer have been overwritten
+    let start = start.max(total.saturating_sub(PRNG_AUDIT_SIZE));
+    let end = start.saturating_add(limit as u64).min(total);
+    let audit_store = ReadonlyPrefixedStorage::new(PREFIX_PRNG_AUDIT, &deps.storage);
+    let mut entries: Vec<PrngAuditEntry> = Vec::new();
+    for idx in start..end {
+        entries.push(
+            may_load(&audit_store, &(idx % PRNG_AUDIT_SIZE).to_le_bytes())?
+                .ok_or_else(|| StdError::from(ContractError::AuditLogCorrupt))?,
+        );
+    }
+    to_binary(&QueryAnswer::PrngAuditLog { entries, total })
+}
+
+/// Returns QueryResult displaying the number of NFTs released and how many were bridged
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+fn query_bridge_stats<S: ReadonlyStorage>(storage: &S) -> QueryResult {
+    let counts = load_counts(storage)?;
+    let bridged: u64 = may_load(storage, BRIDGE_RELEASED_KEY)?.unwrap_or(0);
+
+    to_binary(&QueryAnswer::BridgeStats {
+        minted: counts.released,
+        bridged,
+    })
+}
+
+/// Returns QueryResult displaying the number of tokens in the pool with a tag
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+/// * `viewer` - optional address and key making an authenticated query request
+/// * `permit` - optional permit with "owner" permission
+/// * `tag` - string slice of the tag to count
+fn query_tagged_count<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewer: Option<ViewerInfo>,
+    permit: Option<Permit>,
+    tag: &str,
+) -> QueryResult {
+    // only allow admins to do this
+    authenticate_admin(deps, viewer, permit)?;
+    let counts = load_counts(&deps.storage)?;
+    let tag_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_TAG, &deps.storage);
+    let mut count = 0u32;
+    for idx in load_pool_head(&deps.storage)?..pool_end(&deps.storage, counts.available)? {
+        if may_load::<String, _>(&tag_store, &idx.to_le_bytes())?.as_deref() == Some(tag) {
+            count += 1;
+        }
+    }
+    to_binary(&QueryAnswer::TaggedTokenCount { count })
+}
+
+/// Returns QueryResult displaying a page of the token IDs in the pool
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+/// * `viewer` - optional address and key making an authenticated query request
+/// * `permit` - optional permit with "owner" permission
+/// * `page` - page number to display
+/// * `page_size` - number of token IDs per page
+fn query_pool_tokens<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewer: Option<ViewerInfo>,
+    permit: Option<Permit>,
+    page: u32,
+    page_size: u32,
+) -> QueryResult {
+    // only allow admins to do this
+    authenticate_admin(deps, viewer, permit)?;
+    let counts = load_counts(&deps.storage)?;
+    let start = page.saturating_mul(page_size);
+    let end = start.saturating_add(page_size).min(counts.available);
+    let head = load_pool_head(&deps.storage)?;
+    let id_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_IDS, &deps.storage);
+    let mut token_ids: Vec<String> = Vec::new();
+    for idx in start..end {
+        let (token_id, _): (String, u8) = may_load(&id_store, &(head + idx).to_le_bytes())?
+            .ok_or_else(|| StdError::from(ContractError::PoolCorrupt))?;
+        token_ids.push(token_id);
+    }
+    to_binary(&QueryAnswer::PoolTokens {
+        token_ids,
+        total: counts.available,
+    })
+}
+
+/// Returns QueryResult displaying whether the gumball is paused
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+fn query_is_paused<S: ReadonlyStorage>(storage: &S) -> QueryResult {
+    let paused: bool = may_load(storage, PAUSED_KEY)?.unwrap_or(false);
+
+    to_binary(&QueryAnswer::IsPaused { paused })
+}
+
+/// Returns QueryResult displaying the number of NFTs an address has received
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+/// * `viewer` - optional address and key making an authenticated query request
+/// * `permit` - optional permit with "owner" permission
+/// * `address` - a reference to the address to display
+fn query_address_count<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewer: Option<ViewerInfo>,
+    permit: Option<Permit>,
+    address: &HumanAddr,
+) -> QueryResult {
+    // only allow admins to do this
+    authenticate_admin(deps, viewer, permit)?;
+    let addr_raw = deps.api.canonical_address(address)?;
+    let cnt_store = ReadonlyPrefixedStorage::new(PREFIX_ADDR_MINT_COUNT, &deps.storage);
+    let count: u32 = may_load(&cnt_store, addr_raw.as_slice())?.unwrap_or(0);
+
+    to_binary(&QueryAnswer::AddressMintCount { count })
+}
+
+/// Returns QueryResult displaying a page of registered listing addresses
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+/// * `viewer` - optional address and key making an authenticated query request
+/// * `permit` - optional permit with "owner" permission
+/// * `page` - page number to display
+/// * `page_size` - number of listings per page
+fn query_registered_listings<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewer: Option<ViewerInfo>,
+    permit: Option<Permit>,
+    page: u32,
+    page_size: u32,
+) -> QueryResult {
+    // only allow admins to do this
+    authenticate_admin(deps, viewer, permit)?;
+    let total: u32 = may_load(&deps.storage, LIST_COUNT_KEY)?.unwrap_or(0);
+    let start = page.saturating_mul(page_size);
+    let end = start.saturating_add(page_size).min(total);
+    let idx_store = ReadonlyPrefixedStorage::new(PREFIX_LIST_REGISTRY_INDEX, &deps.storage);
+    let mut listings: Vec<HumanAddr> = Vec::new();
+    for idx in start..end {
+        // skip any index entries that are missing instead of failing the whole page
+        if let Some(stored) = may_load::<StoredListing, _>(&idx_store, &idx.to_le_bytes())? {
+            listings.push(deps.api.human_address(&stored.address)?);
+        }
+    }
+    to_binary(&QueryAnswer::RegisteredListings { listings, total })
+}
+
+/// Returns QueryResult displaying whether an address is whitelisted
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+/// * `viewer` - optional address and key making an authenticated query request
+/// * `permit` - optional permit with "owner" permission
+/// * `address` - a reference to the address to check
+fn query_is_whitelisted<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewer: Option<ViewerInfo>,
+    permit: Option<Permit>,
+    address: &HumanAddr,
+) -> QueryResult {
+    let querier = authenticate_viewer(deps, viewer, permit)?;
+    let addr_raw = deps.api.canonical_address(address)?;
+    // only the address itself or an admin may check
+    if querier != addr_raw {
+        let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
+        if !admins.contains(&querier) {
+            return Err(StdError::unauthorized());
+        }
+    }
+    let white_store = ReadonlyPrefixedStorage::new(PREFIX_WHITELIST, &deps.storage);
+    let entry = load_whitelist_entry(&white_store, &addr_raw)?;
+
+    to_binary(&QueryAnswer::IsWhitelisted {
+        whitelisted: entry.remaining > 0,
+        remaining_quantity: entry.remaining,
+        expires_at: entry.expires_at,
     })
 }
 
-/// Returns QueryResult displaying code hash and address of the nft contract this gumball is used with
+/// Returns StdResult<()>
+///
+/// verifies that entropy is at least the minimum number of bytes long
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+/// * `entropy` - entropy string slice
+fn check_entropy<S: ReadonlyStorage>(storage: &S, entropy: &str) -> StdResult<()> {
+    let min_len: u8 = may_load(storage, MIN_ENTROPY_LEN_KEY)?.unwrap_or(DEFAULT_MIN_ENTROPY_LEN);
+    // len() is the byte length, so multi-byte characters count for each of their bytes
+    if entropy.len() < min_len as usize {
+        return Err(ContractError::InvalidEntropy {
+            min_length: min_len,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Returns StdResult<Vec<StoredNftDossierForListing>> of the example NFTs.  An example saved
+/// by earlier versions is returned as the only example
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+fn load_examples<S: ReadonlyStorage>(storage: &S) -> StdResult<Vec<StoredNftDossierForListing>> {
+    if let Ok(Some(examples)) = may_load::<Vec<StoredNftDossierForListing>, _>(storage, EXAMPLE_KEY)
+    {
+        return Ok(examples);
+    }
+    Ok(
+        may_load::<StoredNftDossierForListing, _>(storage, EXAMPLE_KEY)?
+            .into_iter()
+            .collect(),
+    )
+}
+
+/// Returns StdResult<Counts> of the pool counts.  Counts saved by earlier versions have their
+/// block heights set to 0
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+fn load_counts<S: ReadonlyStorage>(storage: &S) -> StdResult<Counts> {
+    if let Ok(counts) = load::<Counts, _>(storage, COUNT_KEY) {
+        return Ok(counts);
+    }
+    let legacy: LegacyCounts = load(storage, COUNT_KEY)?;
+    Ok(Counts {
+        available: legacy.available,
+        released: legacy.released,
+        last_deposit_block: 0,
+        last_mint_block: 0,
+    })
+}
+
+/// Returns StdResult<StoredWhitelistEntry> of an address' whitelist entry, with no mints
+/// remaining if the address is not whitelisted.  Entries saved as a quantity or a bool flag by
+/// earlier versions never expire, and a bool flag counts as a single mint
+///
+/// # Arguments
+///
+/// * `white_store` - a reference to the whitelist storage
+/// * `address` - a reference to the address' canonical form
+fn load_whitelist_entry<S: ReadonlyStorage>(
+    white_store: &S,
+    address: &CanonicalAddr,
+) -> StdResult<StoredWhitelistEntry> {
+    if let Ok(Some(entry)) = may_load::<StoredWhitelistEntry, _>(white_store, address.as_slice()) {
+        return Ok(entry);
+    }
+    let remaining = if let Ok(Some(quantity)) = may_load::<u32, _>(white_store, address.as_slice())
+    {
+        quantity
+    } else {
+        may_load::<bool, _>(white_store, address.as_slice())?.map_or(0, |w| w as u32)
+    };
+    Ok(StoredWhitelistEntry {
+        remaining,
+        expires_at: None,
+    })
+}
+
+/// Returns QueryResult displaying a page of whitelisted addresses
 ///
 /// # Arguments
 ///
 /// * `deps` - reference to Extern containing all the contract's external dependencies
-fn query_nft_contract<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> QueryResult {
+/// * `viewer` - optional address and key making an authenticated query request
+/// * `permit` - optional permit with "owner" permission
+/// * `page` - page number to display
+/// * `page_size` - number of addresses per page
+fn query_whitelist_snapshot<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewer: Option<ViewerInfo>,
+    permit: Option<Permit>,
+    page: u32,
+    page_size: u32,
+) -> QueryResult {
+    // only allow admins to do this
+    authenticate_admin(deps, viewer, permit)?;
+    let total: u32 = may_load(&deps.storage, WHITELIST_COUNT_KEY)?.unwrap_or(0);
+    let start = page.saturating_mul(page_size);
+    let end = start.saturating_add(page_size).min(total);
+    let order_store = ReadonlyPrefixedStorage::new(PREFIX_WHITELIST_ORDER, &deps.storage);
+    let white_store = ReadonlyPrefixedStorage::new(PREFIX_WHITELIST, &deps.storage);
+    let mut entries: Vec<WhitelistEntry> = Vec::new();
+    for idx in start..end {
+        if let Some(raw) = may_load::<CanonicalAddr, _>(&order_store, &idx.to_le_bytes())? {
+            let entry = load_whitelist_entry(&white_store, &raw)?;
+            entries.push(WhitelistEntry {
+                address: deps.api.human_address(&raw)?,
+                remaining: entry.remaining,
+                expires_at: entry.expires_at,
+            });
+        }
+    }
+    to_binary(&QueryAnswer::WhitelistSnapshot { entries, total })
+}
+
+/// Returns QueryResult displaying the version of the storage layout
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+fn query_schema_version<S: ReadonlyStorage>(storage: &S) -> QueryResult {
+    let version: u32 = may_load(storage, SCHEMA_VERSION_KEY)?.unwrap_or(SCHEMA_VERSION);
+
+    to_binary(&QueryAnswer::SchemaVersion { version })
+}
+
+/// Returns QueryResult displaying the name and version of this contract
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+fn query_contract_info<S: ReadonlyStorage>(storage: &S) -> QueryResult {
+    let schema_version: u32 = may_load(storage, SCHEMA_VERSION_KEY)?.unwrap_or(SCHEMA_VERSION);
+
+    to_binary(&QueryAnswer::ContractInfo {
+        name: CONTRACT_NAME.to_string(),
+        version: CONTRACT_VERSION.to_string(),
+        schema_version,
+    })
+}
+
+/// Returns QueryResult displaying the active minting round
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+fn query_current_round<S: ReadonlyStorage>(storage: &S) -> QueryResult {
+    let round = load_current_round(storage)?;
+
+    to_binary(&QueryAnswer::CurrentRound { round })
+}
+
+/// Returns StdResult<Option<Round>>
+///
+/// loads the active minting round, if any
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+fn load_current_round<S: ReadonlyStorage>(storage: &S) -> StdResult<Option<Round>> {
+    if let Some(round_id) = may_load::<u32, _>(storage, CURRENT_ROUND_KEY)? {
+        let round_store = ReadonlyPrefixedStorage::new(PREFIX_ROUNDS, storage);
+        may_load(&round_store, &round_id.to_le_bytes())
+    } else {
+        Ok(None)
+    }
+}
+
+/// Returns QueryResult displaying whether only whitelisted addresses may mint
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+fn query_whitelist_only<S: ReadonlyStorage>(storage: &S) -> QueryResult {
+    let enabled: bool = may_load(storage, WHITELIST_ONLY_KEY)?.unwrap_or(false);
+
+    to_binary(&QueryAnswer::WhitelistOnly { enabled })
+}
+
+/// Returns QueryResult displaying how long it has been since the PRNG was seeded
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+/// * `viewer` - optional address and key making an authenticated query request
+/// * `permit` - optional permit with "owner" permission
+fn query_prng_freshness<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewer: Option<ViewerInfo>,
+    permit: Option<Permit>,
+) -> QueryResult {
+    // only allow admins to do this
+    authenticate_admin(deps, viewer, permit)?;
+    let counts = load_counts(&deps.storage)?;
+
+    to_binary(&QueryAnswer::PrngFreshness {
+        last_seeded_at_block: may_load(&deps.storage, PRNG_SEED_BLOCK_KEY)?.unwrap_or(0),
+        mints_since_reseed: may_load(&deps.storage, MINTS_SINCE_RESEED_KEY)?.unwrap_or(0),
+        total_mints: counts.released,
+    })
+}
+
+/// Returns QueryResult displaying whether the stored pool count matches the token index
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+/// * `viewer` - optional address and key making an authenticated query request
+/// * `permit` - optional permit with "owner" permission
+fn query_pool_integrity<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewer: Option<ViewerInfo>,
+    permit: Option<Permit>,
+) -> QueryResult {
+    // only allow admins to do this
+    authenticate_admin(deps, viewer, permit)?;
+    let counts = load_counts(&deps.storage)?;
+    let id_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_IDS, &deps.storage);
+    let head = load_pool_head(&deps.storage)?;
+    let actual_count = (0..counts.available)
+        .filter(|idx| id_store.get(&(head + idx).to_le_bytes()).is_some())
+        .count() as u32;
+    let discrepancy = counts.available as i64 - actual_count as i64;
+
+    to_binary(&QueryAnswer::PoolIntegrity {
+        stored_count: counts.available,
+        actual_count,
+        discrepancy,
+        is_consistent: discrepancy == 0,
+    })
+}
+
+/// Returns QueryResult displaying the gumball's branding info
+///
+/// # Arguments
+///
+/// * `storage` - a reference to the contract's storage
+fn query_gumball_info<S: ReadonlyStorage>(storage: &S) -> QueryResult {
+    let info: GumballInfo = may_load(storage, GUMBALL_INFO_KEY)?.unwrap_or(GumballInfo {
+        name: String::new(),
+        description: None,
+        image_uri: None,
+    });
+
+    to_binary(&QueryAnswer::GumballInfo {
+        name: info.name,
+        description: info.description,
+        image_uri: info.image_uri,
+    })
+}
+
+/// Returns QueryResult displaying the gumball's counts, collection, and operational flags
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+fn query_status<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> QueryResult {
+    let counts = load_counts(&deps.storage)?;
     let contract =
         load::<StoreContractInfo, _>(&deps.storage, COLLECTION_KEY)?.into_humanized(&deps.api)?;
+    let paused: bool = may_load(&deps.storage, PAUSED_KEY)?.unwrap_or(false);
+    // a finalized gumball will never accept more deposits
+    let pool_sealed: bool = may_load(&deps.storage, IS_FINALIZED_KEY)?.unwrap_or(false);
 
-    to_binary(&QueryAnswer::NftContract {
-        code_hash: contract.code_hash,
-        address: contract.address,
+    to_binary(&QueryAnswer::Status {
+        available: counts.available,
+        released: counts.released,
+        nft_contract_address: contract.address,
+        nft_contract_code_hash: contract.code_hash,
+        paused,
+        pool_sealed,
+        whitelist_only: may_load(&deps.storage, WHITELIST_ONLY_KEY)?.unwrap_or(false),
+        mintable: counts.available > 0 && !paused && !pool_sealed,
     })
 }
 
-/// Returns QueryResult displaying an example NFT's public information
+/// Returns QueryResult displaying the token IDs in a range of pool indices
 ///
 /// # Arguments
 ///
 /// * `deps` - reference to Extern containing all the contract's external dependencies
-fn query_listing_disp<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> QueryResult {
-    let contr_strd: StoreContractInfo = load(&deps.storage, COLLECTION_KEY)?;
-    let counts: Counts = load(&deps.storage, COUNT_KEY)?;
-    let doss_strd: StoredNftDossierForListing =
-        may_load::<StoredNftDossierForListing, _>(&deps.storage, EXAMPLE_KEY)?.unwrap_or(
-            StoredNftDossierForListing {
-                public_metadata: None,
-                royalty_info: None,
-                mint_run_info: None,
-            },
-        );
-    to_binary(&QueryAnswer::NftListingDisplay {
-        nft_info: doss_strd.into_humanized(&deps.api)?,
-        nft_contract_address: deps.api.human_address(&contr_strd.address)?,
-        mintable: counts.available > 0,
-    })
+/// * `viewer` - optional address and key making an authenticated query request
+/// * `permit` - optional permit with "owner" permission
+/// * `start_index` - first pool index to display
+/// * `end_index` - pool index after the last one to display
+fn query_tokens_in_range<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewer: Option<ViewerInfo>,
+    permit: Option<Permit>,
+    start_index: u32,
+    end_index: u32,
+) -> QueryResult {
+    // only allow admins to do this
+    authenticate_admin(deps, viewer, permit)?;
+    let counts = load_counts(&deps.storage)?;
+    let end = end_index.min(counts.available);
+    let head = load_pool_head(&deps.storage)?;
+    let id_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_IDS, &deps.storage);
+    let mut tokens: Vec<(u32, String)> = Vec::new();
+    for idx in start_index..end {
+        let (token_id, _): (String, u8) = may_load(&id_store, &(head + idx).to_le_bytes())?
+            .ok_or_else(|| StdError::from(ContractError::PoolCorrupt))?;
+        tokens.push((idx, token_id));
+    }
+    to_binary(&QueryAnswer::TokensInRange { tokens })
 }
 
-/// Returns QueryResult displaying the admin list
+/// Returns QueryResult displaying the number of NFTs a listing has dispensed
 ///
 /// # Arguments
 ///
 /// * `deps` - reference to Extern containing all the contract's external dependencies
 /// * `viewer` - optional address and key making an authenticated query request
 /// * `permit` - optional permit with "owner" permission
-fn query_admins<S: Storage, A: Api, Q: Querier>(
+/// * `listing` - the listing address to display
+fn query_listing_mint_count<S: Storage, A: Api, Q: Querier>(
     deps: &Extern<S, A, Q>,
     viewer: Option<ViewerInfo>,
     permit: Option<Permit>,
+    listing: HumanAddr,
 ) -> QueryResult {
     // only allow admins to do this
-    let (admins, _) = check_admin(deps, viewer, permit)?;
-    to_binary(&QueryAnswer::Admins {
-        admins: admins
-            .iter()
-            .map(|a| deps.api.human_address(a))
-            .collect::<StdResult<Vec<HumanAddr>>>()?,
+    authenticate_admin(deps, viewer, permit)?;
+    let listing_raw = deps.api.canonical_address(&listing)?;
+    let cnt_store = ReadonlyPrefixedStorage::new(PREFIX_LISTING_MINT_COUNT, &deps.storage);
+    let minted: u64 = may_load(&cnt_store, listing_raw.as_slice())?.unwrap_or(0);
+
+    to_binary(&QueryAnswer::ListingMintCount { listing, minted })
+}
+
+/// Returns QueryResult displaying the most recent mint events, newest first
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+/// * `viewer` - optional address and key making an authenticated query request
+/// * `permit` - optional permit with "owner" permission
+fn query_mint_log<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewer: Option<ViewerInfo>,
+    permit: Option<Permit>,
+) -> QueryResult {
+    // only allow admins to do this
+    authenticate_admin(deps, viewer, permit)?;
+    let head: u64 = may_load(&deps.storage, MINT_LOG_HEAD_KEY)?.unwrap_or(0);
+    let log_store = ReadonlyPrefixedStorage::new(PREFIX_MINT_LOG, &deps.storage);
+    let mut events: Vec<MintEventHumanized> = Vec::new();
+    for pos in (head.saturating_sub(MINT_LOG_SIZE)..head).rev() {
+        let event: MintEvent = may_load(&log_store, &(pos % MINT_LOG_SIZE).to_le_bytes())?
+            .ok_or_else(|| StdError::from(ContractError::MintLogCorrupt))?;
+        events.push(MintEventHumanized {
+            recipient: deps.api.human_address(&event.recipient)?,
+            token_id: event.token_id,
+            caller_type: event.caller_type,
+            block_height: event.block_height,
+        });
+    }
+    to_binary(&QueryAnswer::MintLog { events })
+}
+
+/// Returns QueryResult displaying the token ID stored at a pool index
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+/// * `viewer` - optional address and key making an authenticated query request
+/// * `permit` - optional permit with "owner" permission
+/// * `index` - pool index to display
+fn query_token_at_index<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewer: Option<ViewerInfo>,
+    permit: Option<Permit>,
+    index: u32,
+) -> QueryResult {
+    // only allow admins to do this
+    authenticate_admin(deps, viewer, permit)?;
+    let counts = load_counts(&deps.storage)?;
+    if index >= counts.available {
+        return Err(ContractError::IndexOutOfRange {
+            index,
+            available: counts.available,
+        }
+        .into());
+    }
+    let id_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_IDS, &deps.storage);
+    let head = load_pool_head(&deps.storage)?;
+    let token_id =
+        may_load::<(String, u8), _>(&id_store, &(head + index).to_le_bytes())?.map(|t| t.0);
+
+    to_binary(&QueryAnswer::TokenAtIndex { index, token_id })
+}
+
+/// Returns QueryResult displaying the cached info of a pool token
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+/// * `viewer` - optional address and key making an authenticated query request
+/// * `permit` - optional permit with "owner" permission
+/// * `token_id` - ID of the token
+fn query_token_metadata<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewer: Option<ViewerInfo>,
+    permit: Option<Permit>,
+    token_id: String,
+) -> QueryResult {
+    // only allow admins to do this
+    authenticate_admin(deps, viewer, permit)?;
+    let meta_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_METADATA, &deps.storage);
+    let nft_info = may_load::<StoredNftDossierForListing, _>(&meta_store, token_id.as_bytes())?
+        .map(|doss| doss.into_humanized(&deps.api))
+        .transpose()?;
+
+    to_binary(&QueryAnswer::TokenMetadata { token_id, nft_info })
+}
+
+/// Returns QueryResult displaying how often each value of a trait appears in the cached info
+/// of the pool tokens
+///
+/// # Arguments
+///
+/// * `deps` - reference to Extern containing all the contract's external dependencies
+/// * `viewer` - optional address and key making an authenticated query request
+/// * `permit` - optional permit with "owner" permission
+/// * `trait_type` - trait type to count
+fn query_trait_aggregates<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewer: Option<ViewerInfo>,
+    permit: Option<Permit>,
+    trait_type: String,
+) -> QueryResult {
+    // only allow admins to do this
+    authenticate_admin(deps, viewer, permit)?;
+    let counts = load_counts(&deps.storage)?;
+    let id_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_IDS, &deps.storage);
+    let meta_store = ReadonlyPrefixedStorage::new(PREFIX_TOKEN_METADATA, &deps.storage);
+    let mut value_counts: Vec<TraitCount> = Vec::new();
+    let mut tokens_with_trait = 0u32;
+    let mut total_tokens = 0u32;
+    // storage can not be iterated, so look up the cached info of each pool token
+    for idx in load_pool_head(&deps.storage)?..pool_end(&deps.storage, counts.available)? {
+        let (token_id, _): (String, u8) = may_load(&id_store, &idx.to_le_bytes())?
+            .ok_or_else(|| StdError::from(ContractError::PoolCorrupt))?;
+        let doss = if let Some(d) =
+            may_load::<StoredNftDossierForListing, _>(&meta_store, token_id.as_bytes())?
+        {
+            d
+        } else {
+            continue;
+        };
+        total_tokens += 1;
+        let mut has_trait = false;
+        let attributes = doss
+            .public_metadata
+            .and_then(|m| m.extension)
+            .and_then(|e| e.attributes)
+            .unwrap_or_default();
+        for attr in attributes
+            .into_iter()
+            .filter(|a| a.trait_type.as_deref() == Some(trait_type.as_str()))
+        {
+            has_trait = true;
+            if let Some(vc) = value_counts.iter_mut().find(|vc| vc.value == attr.value) {
+                vc.count += 1;
+            } else {
+                value_counts.push(TraitCount {
+                    value: attr.value,
+                    count: 1,
+                });
+            }
+        }
+        if has_trait {
+            tokens_with_trait += 1;
+        }
+    }
+    value_counts.sort_by(|a, b| a.value.cmp(&b.value));
+
+    to_binary(&QueryAnswer::TraitAggregates {
+        trait_type,
+        value_counts,
+        tokens_with_trait,
+        total_tokens,
     })
 }
 
-/// Returns StdResult<(CanonicalAddr, Option<CanonicalAddr>)> from determining the querying address
-/// (if possible) either from a Permit or a ViewerInfo.  Also returns this minter's address if
-/// a permit was supplied
+/// Returns StdResult<CanonicalAddr> from determining the querying address either from a Permit
+/// or a ViewerInfo.  If both are supplied, the permit is used and the viewer is ignored
 ///
 /// # Arguments
 ///
 /// * `deps` - a reference to Extern containing all the contract's external dependencies
 /// * `viewer` - optional address and key making an authenticated query request
 /// * `permit` - optional permit with "owner" permission
-fn get_querier<S: Storage, A: Api, Q: Querier>(
+fn authenticate_viewer<S: Storage, A: Api, Q: Querier>(
     deps: &Extern<S, A, Q>,
     viewer: Option<ViewerInfo>,
     permit: Option<Permit>,
-) -> StdResult<(CanonicalAddr, Option<CanonicalAddr>)> {
+) -> StdResult<CanonicalAddr> {
     if let Some(pmt) = permit {
         // Validate permit content
         let me_raw: CanonicalAddr = may_load(&deps.storage, MY_ADDRESS_KEY)?
-            .ok_or_else(|| StdError::generic_err("Minter contract address storage is corrupt"))?;
+            .ok_or_else(|| StdError::from(ContractError::AddressCorrupt))?;
         let my_address = deps.api.human_address(&me_raw)?;
         let querier = deps.api.canonical_address(&validate(
             deps,
@@ -866,46 +6228,47 @@ fn get_querier<S: Storage, A: Api, Q: Querier>(
             my_address,
         )?)?;
         if !pmt.check_permission(&secret_toolkit::permit::Permission::Owner) {
-            return Err(StdError::generic_err(format!(
-                "Owner permission is required for Stashh minter queries, got permissions {:?}",
+            return Err(ContractError::PermitOwnerRequired(format!(
+                "{:?}",
                 pmt.params.permissions
-            )));
+            ))
+            .into());
         }
-        return Ok((querier, Some(me_raw)));
+        return Ok(querier);
     }
     if let Some(vwr) = viewer {
         let raw = deps.api.canonical_address(&vwr.address)?;
         // load the address' key
         let key_store = ReadonlyPrefixedStorage::new(PREFIX_VIEW_KEY, &deps.storage);
         let load_key: [u8; VIEWING_KEY_SIZE] =
-            may_load(&key_store, raw.as_slice())?.unwrap_or_else(|| [0u8; VIEWING_KEY_SIZE]);
+            may_load(&key_store, raw.as_slice())?.unwrap_or([0u8; VIEWING_KEY_SIZE]);
         let input_key = ViewingKey(vwr.viewing_key);
         // if key matches
         if input_key.check_viewing_key(&load_key) {
-            return Ok((raw, None));
+            return Ok(raw);
         }
     }
     Err(StdError::unauthorized())
 }
 
-/// Returns StdResult<(Vec<CanonicalAddr>, Option<CanonicalAddr>)> which is the admin list
-/// and this contract's address if it has been retrieved, and checks if the querier is an admin
+/// Returns StdResult<Vec<CanonicalAddr>> which is the admin list after authenticating the
+/// querier and verifying that the querier is an admin
 ///
 /// # Arguments
 ///
 /// * `deps` - a reference to Extern containing all the contract's external dependencies
 /// * `viewer` - optional address and key making an authenticated query request
 /// * `permit` - optional permit with "owner" permission
-fn check_admin<S: Storage, A: Api, Q: Querier>(
+fn authenticate_admin<S: Storage, A: Api, Q: Querier>(
     deps: &Extern<S, A, Q>,
     viewer: Option<ViewerInfo>,
     permit: Option<Permit>,
-) -> StdResult<(Vec<CanonicalAddr>, Option<CanonicalAddr>)> {
-    let (admin, my_addr) = get_querier(deps, viewer, permit)?;
+) -> StdResult<Vec<CanonicalAddr>> {
+    let admin = authenticate_viewer(deps, viewer, permit)?;
     // only allow admins to do this
     let admins: Vec<CanonicalAddr> = load(&deps.storage, ADMINS_KEY)?;
     if !admins.contains(&admin) {
         return Err(StdError::unauthorized());
     }
-    Ok((admins, my_addr))
+    Ok(admins)
 }
@@ -0,0 +1,527 @@
+use std::fmt;
+
+use cosmwasm_std::{HumanAddr, StdError, Uint128};
+
+/// errors returned by the gumball.  Each error is returned as a generic StdError whose message
+/// starts with the error's code, so callers can match on the code instead of the wording
+#[derive(Debug, PartialEq)]
+pub enum ContractError {
+    /// the gumball is finalized or expired
+    Locked(String),
+    /// the gumball is paused
+    Paused,
+    /// the message may not be used with the gumball's collection
+    CollectionNotAllowed,
+    /// no randomness oracle has been set
+    NoOracle,
+    /// too many addresses in one whitelist update
+    WhitelistBatchTooLarge { max: u32, sent: u32 },
+    /// the minting round does not exist
+    RoundNotFound(u32),
+    /// RegisterListing was not called by an expected factory
+    UnexpectedFactory,
+    /// the factory has registered its maximum number of listings
+    FactoryListingLimit { max: u32 },
+    /// the listing registry is full
+    ListingRegistryFull,
+    /// the token ID pool is corrupt
+    PoolCorrupt,
+    /// the pool has reached the largest number of NFTs it can index
+    PoolIndexFull,
+    /// the listing is not in the registry
+    ListingNotFound,
+    /// (Batch)ReceiveNft was not called by the collection contract
+    NotCollection,
+    /// a deposit would exceed the gumball's maximum capacity
+    PoolFull { max: u32 },
+    /// a deposited token does not have the accepted prefix
+    TokenPrefixRejected { token_id: String, prefix: String },
+    /// a deposited token is already in the pool
+    DuplicateToken(String),
+    /// the pool does not hold enough NFTs to create a listing
+    MinFillNotMet { min_fill: u32 },
+    /// the listing creation fee was not paid
+    InsufficientListingFee,
+    /// a whitelisted address tried to mint more than it may
+    WhitelistQuantityExceeded { remaining: u32 },
+    /// only whitelisted addresses may mint
+    WhitelistOnly,
+    /// an admin minted before the cooldown expired
+    AdminCooldown { seconds: u64 },
+    /// a listing passed more buyers than its cap
+    BuyerCapExceeded { cap: u32 },
+    /// listing mints have not started
+    MintNotStarted,
+    /// listing mints have ended
+    MintWindowClosed,
+    /// a listing mint did not include enough buyer-supplied entropy
+    ListingEntropyRequired { min_length: u32 },
+    /// the round is only open to whitelisted addresses
+    RoundWhitelistOnly(u32),
+    /// the round has not opened
+    RoundNotOpen(u32),
+    /// the round has closed
+    RoundClosed(u32),
+    /// the pool does not hold enough NFTs
+    InsufficientSupply { requested: u32, available: u32 },
+    /// the mint would exceed the released cap
+    ReleasedCapExceeded { requested: u32, cap: u64 },
+    /// the mint would drop the pool below its reserve threshold
+    BelowReserve { threshold: u32 },
+    /// the pool does not hold enough NFTs with the listing's tag
+    InsufficientTagged {
+        requested: u32,
+        available: u32,
+        tag: String,
+    },
+    /// the pool does not hold enough NFTs that may be drawn
+    InsufficientEligible { requested: u32, eligible: u32 },
+    /// the recipient has received the maximum number of NFTs
+    AddressMintLimit(HumanAddr),
+    /// the recipient has received the maximum number of NFTs in the round
+    RoundBuyerLimit {
+        buyer: HumanAddr,
+        max: u32,
+        round: u32,
+    },
+    /// the last admin may not be removed
+    LastAdminRemoval,
+    /// the admin list would exceed its maximum size
+    TooManyAdmins { max: u32 },
+    /// the gumball does not have an example NFT
+    NoExample,
+    /// the contract expiry was already set
+    ExpiryAlreadySet,
+    /// the contract expiry is not in the future
+    ExpiryInPast,
+    /// the token is not in the pool
+    TokenNotInPool(String),
+    /// the listing preview count is out of range
+    InvalidPreviewCount { max: u8 },
+    /// the released cap may no longer be set
+    ReleasedCapLocked,
+    /// no more batches may be started
+    BatchLimit,
+    /// the batch has not been started
+    BatchNotStarted(u8),
+    /// a tier was given more than one weight
+    DuplicateTierWeight(u8),
+    /// the mint window does not start before it ends
+    InvalidMintWindow,
+    /// the pool is empty
+    PoolEmpty,
+    /// the reservation expiration is not in the future
+    ReservationInPast,
+    /// the recipient already has a reserved token
+    AlreadyReserved(HumanAddr),
+    /// the maximum number of admins is below the current number of admins
+    MaxAdminsBelowCount(u32),
+    /// no permit names were provided
+    NoPermitNames,
+    /// the code hash is blank
+    BlankCodeHash,
+    /// rounds must be added in order
+    RoundOutOfOrder { next: u32 },
+    /// the round does not open before it closes
+    InvalidRoundTimes,
+    /// there is no round to advance to
+    NoNextRound,
+    /// the image URI does not use a supported scheme
+    InvalidImageUri,
+    /// the transfer batch size is zero
+    InvalidTransferBatchSize,
+    /// the listing registry index is corrupt
+    ListingRegistryCorrupt,
+    /// the PRNG audit log is corrupt
+    AuditLogCorrupt,
+    /// the entropy is too short
+    InvalidEntropy { min_length: u8 },
+    /// the minter's address is missing from storage
+    AddressCorrupt,
+    /// a permit without owner permission was used
+    PermitOwnerRequired(String),
+    /// the mint log is corrupt
+    MintLogCorrupt,
+    /// the pool index is past the end of the pool
+    IndexOutOfRange { index: u32, available: u32 },
+    /// the caller did not deposit the token
+    NotDepositor(String),
+    /// the caller's whitelist entry has expired
+    WhitelistExpired,
+    /// the pool still holds tokens
+    PoolNotEmpty,
+    /// the listing price is below the minimum
+    PriceBelowMinimum(Uint128),
+    /// the listing price is above the maximum
+    PriceAboveMaximum(Uint128),
+    /// the minimum listing price is above the maximum
+    InvalidPriceBounds,
+    /// a listing fee was given without a denomination
+    MissingFeeDenom,
+    /// there is no pending proposal to clear the pool
+    NoPendingClear,
+    /// the proposal hash does not match the pending proposal
+    ClearProposalMismatch,
+    /// the pending proposal to clear the pool has expired
+    ClearProposalExpired,
+    /// a listing buyer could not be given an NFT, so the purchase must revert
+    ListingBuyerSkipped(HumanAddr),
+    /// more than the listing creation fee was sent
+    ListingFeeOverpaid,
+    /// tokens of the current collection are still reserved
+    ReservationsOutstanding(u32),
+    /// the gumball still holds pooled or reserved tokens
+    FinalizeWithTokens(u32),
+}
+
+impl ContractError {
+    /// Returns &'static str of the code callers can match on
+    pub fn code(&self) -> &'static str {
+        match self {
+            ContractError::Locked(_) => "E001",
+            ContractError::Paused => "E002",
+            ContractError::CollectionNotAllowed => "E003",
+            ContractError::NoOracle => "E004",
+            ContractError::WhitelistBatchTooLarge { .. } => "E005",
+            ContractError::RoundNotFound(_) => "E006",
+            ContractError::UnexpectedFactory => "E007",
+            ContractError::FactoryListingLimit { .. } => "E008",
+            ContractError::ListingRegistryFull => "E009",
+            ContractError::PoolCorrupt => "E010",
+            ContractError::PoolIndexFull => "E011",
+            ContractError::ListingNotFound => "E012",
+            ContractError::NotCollection => "E013",
+            ContractError::PoolFull { .. } => "E014",
+            ContractError::TokenPrefixRejected { .. } => "E015",
+            ContractError::DuplicateToken(_) => "E016",
+            ContractError::MinFillNotMet { .. } => "E017",
+            ContractError::InsufficientListingFee => "E018",
+            ContractError::WhitelistQuantityExceeded { .. } => "E019",
+            ContractError::WhitelistOnly => "E020",
+            ContractError::AdminCooldown { .. } => "E021",
+            ContractError::BuyerCapExceeded { .. } => "E022",
+            ContractError::MintNotStarted => "E023",
+            ContractError::MintWindowClosed => "E024",
+            ContractError::ListingEntropyRequired { .. } => "E025",
+            ContractError::RoundWhitelistOnly(_) => "E026",
+            ContractError::RoundNotOpen(_) => "E027",
+            ContractError::RoundClosed(_) => "E028",
+            ContractError::InsufficientSupply { .. } => "E029",
+            ContractError::ReleasedCapExceeded { .. } => "E030",
+            ContractError::BelowReserve { .. } => "E031",
+            ContractError::InsufficientTagged { .. } => "E032",
+            ContractError::InsufficientEligible { .. } => "E033",
+            ContractError::AddressMintLimit(_) => "E034",
+            ContractError::RoundBuyerLimit { .. } => "E035",
+            ContractError::LastAdminRemoval => "E036",
+            ContractError::TooManyAdmins { .. } => "E037",
+            ContractError::NoExample => "E038",
+            ContractError::ExpiryAlreadySet => "E039",
+            ContractError::ExpiryInPast => "E040",
+            ContractError::TokenNotInPool(_) => "E041",
+            ContractError::InvalidPreviewCount { .. } => "E042",
+            ContractError::ReleasedCapLocked => "E043",
+            ContractError::BatchLimit => "E044",
+            ContractError::BatchNotStarted(_) => "E045",
+            ContractError::DuplicateTierWeight(_) => "E046",
+            ContractError::InvalidMintWindow => "E047",
+            ContractError::PoolEmpty => "E048",
+            ContractError::ReservationInPast => "E049",
+            ContractError::AlreadyReserved(_) => "E050",
+            ContractError::MaxAdminsBelowCount(_) => "E051",
+            ContractError::NoPermitNames => "E052",
+            ContractError::BlankCodeHash => "E053",
+            ContractError::RoundOutOfOrder { .. } => "E054",
+            ContractError::InvalidRoundTimes => "E055",
+            ContractError::NoNextRound => "E056",
+            ContractError::InvalidImageUri => "E057",
+            ContractError::InvalidTransferBatchSize => "E058",
+            ContractError::ListingRegistryCorrupt => "E059",
+            ContractError::AuditLogCorrupt => "E060",
+            ContractError::InvalidEntropy { .. } => "E061",
+            ContractError::AddressCorrupt => "E062",
+            ContractError::PermitOwnerRequired(_) => "E063",
+            ContractError::MintLogCorrupt => "E064",
+            ContractError::IndexOutOfRange { .. } => "E065",
+            ContractError::NotDepositor(_) => "E066",
+            ContractError::WhitelistExpired => "E067",
+            ContractError::PoolNotEmpty => "E068",
+            ContractError::PriceBelowMinimum(_) => "E069",
+            ContractError::PriceAboveMaximum(_) => "E070",
+            ContractError::InvalidPriceBounds => "E071",
+            ContractError::MissingFeeDenom => "E072",
+            ContractError::NoPendingClear => "E073",
+            ContractError::ClearProposalMismatch => "E074",
+            ContractError::ClearProposalExpired => "E075",
+            ContractError::ListingBuyerSkipped(_) => "E076",
+            ContractError::ListingFeeOverpaid => "E077",
+            ContractError::ReservationsOutstanding(_) => "E078",
+            ContractError::FinalizeWithTokens(_) => "E079",
+        }
+    }
+}
+
+impl fmt::Display for ContractError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ContractError::Locked(reason) => write!(f, "{}", reason),
+            ContractError::Paused => write!(f, "Gumball is paused"),
+            ContractError::CollectionNotAllowed => write!(
+                f,
+                "This may not be called on the gumball contract's collection"
+            ),
+            ContractError::NoOracle => write!(f, "No randomness oracle has been set"),
+            ContractError::WhitelistBatchTooLarge { max, sent } => write!(
+                f,
+                "Batch whitelist update exceeds limit of {} addresses; sent {}",
+                max, sent
+            ),
+            ContractError::RoundNotFound(id) => write!(f, "Round {} does not exist", id),
+            ContractError::UnexpectedFactory => write!(
+                f,
+                "RegisterListing can only be called by an expected factory contract"
+            ),
+            ContractError::FactoryListingLimit { max } => write!(
+                f,
+                "This factory has already registered the maximum of {} listings",
+                max
+            ),
+            ContractError::ListingRegistryFull => {
+                write!(f, "Reached the maximum number of registered listings")
+            }
+            ContractError::PoolCorrupt => write!(f, "Token ID pool is corrupt"),
+            ContractError::PoolIndexFull => {
+                write!(f, "Gumball contract has reached its maximum number of NFTs")
+            }
+            ContractError::ListingNotFound => write!(f, "Listing not found in registry"),
+            ContractError::NotCollection => write!(
+                f,
+                "Only the collection contract specified on instantiation may call (Batch)ReceiveNft"
+            ),
+            ContractError::PoolFull { max } => write!(
+                f,
+                "Adding these tokens would exceed the gumball's maximum capacity of {}",
+                max
+            ),
+            ContractError::TokenPrefixRejected { token_id, prefix } => write!(
+                f,
+                "Token ID {} rejected: must start with '{}'",
+                token_id, prefix
+            ),
+            ContractError::DuplicateToken(id) => {
+                write!(f, "Token {} is already in the gumball pool", id)
+            }
+            ContractError::MinFillNotMet { min_fill } => write!(
+                f,
+                "Pool must contain at least {} tokens before creating a listing",
+                min_fill
+            ),
+            ContractError::InsufficientListingFee => write!(f, "Insufficient listing creation fee"),
+            ContractError::WhitelistQuantityExceeded { remaining } => write!(
+                f,
+                "Whitelisted address may only mint {} more tokens",
+                remaining
+            ),
+            ContractError::WhitelistOnly => write!(
+                f,
+                "The gumball is in whitelist-only mode; only whitelisted addresses may mint"
+            ),
+            ContractError::AdminCooldown { seconds } => write!(
+                f,
+                "Admin mints are limited to one every {} seconds",
+                seconds
+            ),
+            ContractError::BuyerCapExceeded { cap } => write!(
+                f,
+                "Exceeds per-transaction buyer cap of {} for this listing",
+                cap
+            ),
+            ContractError::MintNotStarted => write!(f, "Minting has not started yet"),
+            ContractError::MintWindowClosed => write!(f, "Minting window has closed"),
+            ContractError::ListingEntropyRequired { min_length } => write!(
+                f,
+                "Listing mints must include buyer-supplied entropy of at least {} characters",
+                min_length
+            ),
+            ContractError::RoundWhitelistOnly(id) => {
+                write!(f, "Round {} is only open to whitelisted addresses", id)
+            }
+            ContractError::RoundNotOpen(id) => write!(f, "Round {} has not opened yet", id),
+            ContractError::RoundClosed(id) => write!(f, "Round {} has closed", id),
+            ContractError::InsufficientSupply {
+                requested,
+                available,
+            } => write!(
+                f,
+                "Trying to mint {} tokens, but only {} are available",
+                requested, available
+            ),
+            ContractError::ReleasedCapExceeded { requested, cap } => write!(
+                f,
+                "Trying to mint {} tokens would exceed the cap of {} released tokens",
+                requested, cap
+            ),
+            ContractError::BelowReserve { threshold } => write!(
+                f,
+                "Minting paused: would drop below reserve threshold of {} tokens",
+                threshold
+            ),
+            ContractError::InsufficientTagged {
+                requested,
+                available,
+                tag,
+            } => write!(
+                f,
+                "Trying to mint {} tokens, but only {} tagged '{}' are available",
+                requested, available, tag
+            ),
+            ContractError::InsufficientEligible {
+                requested,
+                eligible,
+            } => write!(
+                f,
+                "Trying to mint {} tokens, but only {} are eligible to be drawn",
+                requested, eligible
+            ),
+            ContractError::AddressMintLimit(buyer) => {
+                write!(f, "Address mint limit reached for {}", buyer)
+            }
+            ContractError::RoundBuyerLimit { buyer, max, round } => write!(
+                f,
+                "{} may only receive {} NFTs in round {}",
+                buyer, max, round
+            ),
+            ContractError::LastAdminRemoval => write!(
+                f,
+                "Cannot remove all admins — at least one admin must remain"
+            ),
+            ContractError::TooManyAdmins { max } => {
+                write!(f, "Admin list would exceed maximum of {} admins", max)
+            }
+            ContractError::NoExample => write!(f, "The gumball does not have an example NFT"),
+            ContractError::ExpiryAlreadySet => {
+                write!(f, "The contract expiry has already been set")
+            }
+            ContractError::ExpiryInPast => write!(f, "The contract expiry must be in the future"),
+            ContractError::TokenNotInPool(id) => {
+                write!(f, "Token {} is not in the gumball pool", id)
+            }
+            ContractError::InvalidPreviewCount { max } => {
+                write!(f, "The listing preview count must be between 1 and {}", max)
+            }
+            ContractError::ReleasedCapLocked => write!(
+                f,
+                "The released cap can only be set before any NFTs have been released"
+            ),
+            ContractError::BatchLimit => write!(f, "Reached the maximum number of batches"),
+            ContractError::BatchNotStarted(id) => write!(f, "Batch {} has not been started", id),
+            ContractError::DuplicateTierWeight(tier) => {
+                write!(f, "Tier {} was given more than one weight", tier)
+            }
+            ContractError::InvalidMintWindow => {
+                write!(f, "Mint window start must be before its end")
+            }
+            ContractError::PoolEmpty => write!(f, "The gumball pool is empty"),
+            ContractError::ReservationInPast => {
+                write!(f, "A reservation's expiration must be in the future")
+            }
+            ContractError::AlreadyReserved(recipient) => {
+                write!(f, "{} already has a reserved token", recipient)
+            }
+            ContractError::MaxAdminsBelowCount(count) => {
+                write!(f, "There are already {} admins", count)
+            }
+            ContractError::NoPermitNames => write!(f, "No permit names were provided"),
+            ContractError::BlankCodeHash => write!(f, "Code hash may not be blank"),
+            ContractError::RoundOutOfOrder { next } => write!(
+                f,
+                "Rounds must be added in order.  The next round ID is {}",
+                next
+            ),
+            ContractError::InvalidRoundTimes => write!(f, "A round must open before it closes"),
+            ContractError::NoNextRound => write!(f, "There is no round to advance to"),
+            ContractError::InvalidImageUri => write!(
+                f,
+                "Image URI must start with http://, https://, ipfs://, or ar://"
+            ),
+            ContractError::InvalidTransferBatchSize => {
+                write!(f, "Transfer batch size must be at least 1")
+            }
+            ContractError::ListingRegistryCorrupt => write!(f, "Listing registry index is corrupt"),
+            ContractError::AuditLogCorrupt => write!(f, "PRNG audit log is corrupt"),
+            ContractError::InvalidEntropy { min_length } => {
+                write!(f, "Entropy must be at least {} characters", min_length)
+            }
+            ContractError::AddressCorrupt => {
+                write!(f, "Minter contract address storage is corrupt")
+            }
+            ContractError::PermitOwnerRequired(permissions) => write!(
+                f,
+                "Owner permission is required for Stashh minter queries, got permissions {}",
+                permissions
+            ),
+            ContractError::MintLogCorrupt => write!(f, "Mint log is corrupt"),
+            ContractError::IndexOutOfRange { index, available } => write!(
+                f,
+                "Index {} is out of range.  The pool holds {} tokens",
+                index, available
+            ),
+            ContractError::NotDepositor(id) => {
+                write!(f, "Token {} can only be recalled by its depositor", id)
+            }
+            ContractError::WhitelistExpired => write!(f, "Whitelist entry has expired"),
+            ContractError::PoolNotEmpty => write!(
+                f,
+                "Pool must be empty before updating the collection contract"
+            ),
+            ContractError::PriceBelowMinimum(min) => {
+                write!(f, "Listing price is below the minimum of {}", min)
+            }
+            ContractError::PriceAboveMaximum(max) => {
+                write!(f, "Listing price exceeds the maximum of {}", max)
+            }
+            ContractError::InvalidPriceBounds => write!(
+                f,
+                "The minimum listing price can not be greater than the maximum"
+            ),
+            ContractError::MissingFeeDenom => {
+                write!(f, "A listing fee requires a fee denomination")
+            }
+            ContractError::NoPendingClear => {
+                write!(f, "There is no pending proposal to clear the pool")
+            }
+            ContractError::ClearProposalMismatch => {
+                write!(f, "Proposal hash does not match the pending proposal")
+            }
+            ContractError::ClearProposalExpired => write!(
+                f,
+                "The proposal to clear the pool has expired and must be re-submitted"
+            ),
+            ContractError::ListingBuyerSkipped(buyer) => write!(
+                f,
+                "{} could not be given an NFT, so the listing purchase was reverted",
+                buyer
+            ),
+            ContractError::ListingFeeOverpaid => write!(
+                f,
+                "Only the exact listing creation fee may be sent, in the fee denomination"
+            ),
+            ContractError::ReservationsOutstanding(count) => write!(
+                f,
+                "{} reserved tokens must be claimed or cancelled before updating the collection",
+                count
+            ),
+            ContractError::FinalizeWithTokens(count) => write!(
+                f,
+                "{} pooled or reserved tokens must be removed before finalizing the gumball",
+                count
+            ),
+        }
+    }
+}
+
+impl From<ContractError> for StdError {
+    fn from(err: ContractError) -> Self {
+        StdError::generic_err(format!("{}: {}", err.code(), err))
+    }
+}
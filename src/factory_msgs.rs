@@ -1,5 +1,6 @@
 use crate::contract::BLOCK_SIZE;
 use crate::contract_info::ContractInfo;
+use crate::snip721::NftDossierForListing;
 use cosmwasm_std::{HumanAddr, Uint128};
 use secret_toolkit::utils::HandleCallback;
 use serde::Serialize;
@@ -40,6 +41,13 @@ pub enum FactoryHandleMsg {
         /// true if the minting contract implements RegisterListing to be notified of the listing address
         implements_register_listing: bool,
     },
+    /// updates the nft info displayed by a listing
+    UpdateListingNftInfo {
+        /// address of the listing to update
+        listing_address: HumanAddr,
+        /// the nft fields the listing displays
+        nft_info: NftDossierForListing,
+    },
 }
 
 impl HandleCallback for FactoryHandleMsg {
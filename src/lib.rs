@@ -1,13 +1,17 @@
 //#![allow(clippy::field_reassign_with_default)]
 pub mod contract;
 mod contract_info;
+mod error;
 mod factory_msgs;
 pub mod msg;
 mod rand;
 mod snip721;
 pub mod state;
 mod storage;
+#[cfg(test)]
+mod testing;
 mod utils;
+mod version;
 mod viewing_key;
 
 #[cfg(target_arch = "wasm32")]
@@ -1,7 +1,7 @@
 #![allow(clippy::large_enum_variant)]
 use crate::contract_info::ContractInfo;
 use crate::snip721::NftDossierForListing;
-use cosmwasm_std::{HumanAddr, Uint128};
+use cosmwasm_std::{Binary, HumanAddr, Uint128};
 use schemars::JsonSchema;
 use secret_toolkit::permit::Permit;
 use serde::{Deserialize, Serialize};
@@ -13,6 +13,26 @@ pub struct InitMsg {
     pub nft_contract: ContractInfo,
     /// entropy used for random viewing key generation
     pub entropy: String,
+    /// optional time listing mints open
+    pub mint_start: Option<u64>,
+    /// optional time listing mints close
+    pub mint_end: Option<u64>,
+    /// optional maximum number of NFTs the gumball may hold at once
+    pub max_capacity: Option<u32>,
+    /// optional number of NFTs the gumball must hold before a listing may be created
+    pub min_fill_before_listing: Option<u32>,
+    /// optionally only allow whitelisted addresses to mint.  Defaults to false
+    pub whitelist_only: Option<bool>,
+    /// optional minimum price of a listing
+    pub min_listing_price: Option<Uint128>,
+    /// optional maximum price of a listing
+    pub max_listing_price: Option<Uint128>,
+    /// optional fee that must be sent with CreateListing.  Exactly the fee must be sent
+    pub listing_fee: Option<Uint128>,
+    /// denomination of the listing fee.  Required if listing_fee is set
+    pub listing_fee_denom: Option<String>,
+    /// optional address that receives the listing fee.  Defaults to the instantiator
+    pub listing_fee_recipient: Option<HumanAddr>,
 }
 
 /// Handle messages
@@ -26,6 +46,8 @@ pub enum HandleMsg {
         from: HumanAddr,
         /// list of tokens sent
         token_ids: Vec<String>,
+        /// optional base64 encoded DepositMsg
+        msg: Option<Binary>,
     },
     /// ReceiveNft is only included to maintain CW721 compliance.  Hopefully everyone uses the
     /// superior BatchReceiveNft process.  ReceiveNft is called when the gumball is sent an NFT.
@@ -35,6 +57,8 @@ pub enum HandleMsg {
         sender: HumanAddr,
         /// the token sent
         token_id: String,
+        /// optional base64 encoded DepositMsg
+        msg: Option<Binary>,
     },
     /// Create a viewing key
     CreateViewingKey { entropy: String },
@@ -60,6 +84,13 @@ pub enum HandleMsg {
     AddToWhitelist {
         /// whitelisted addresses
         addresses: Vec<HumanAddr>,
+        /// optional number of free mints each address may receive.  Defaults to 1
+        quantity: Option<u32>,
+        /// optional round whose whitelist should be updated instead of the global whitelist
+        round_id: Option<u32>,
+        /// optional time after which the entries can no longer be used.  Only applies to the
+        /// global whitelist
+        expires_at: Option<u64>,
     },
     /// remove from whitelist.  This whitelist is for who is permitted to receive a free random NFT.
     /// Whitelisting for purchases must be done on the listing level, or a single non-whitelisted
@@ -67,6 +98,8 @@ pub enum HandleMsg {
     RemoveFromWhitelist {
         /// de-whitelisted addresses
         addresses: Vec<HumanAddr>,
+        /// optional round whose whitelist should be updated instead of the global whitelist
+        round_id: Option<u32>,
     },
     /// Mint an NFT for each buyer.  This can only be called by a listing contract this minter has created,
     /// an admin, or a whitelisted address
@@ -75,6 +108,8 @@ pub enum HandleMsg {
         buyers: Vec<HumanAddr>,
         /// string used for entropy
         entropy: String,
+        /// optional address that referred the buyers.  Only recorded for mints called by a listing
+        referrer: Option<HumanAddr>,
     },
     /// create a gumball listing
     CreateListing {
@@ -128,6 +163,259 @@ pub enum HandleMsg {
         /// ids of the tokens to transfer to the admin doing this tx
         token_ids: Vec<String>,
     },
+    /// only accept NFTs whose token IDs start with the specified prefix.  This can be used to
+    /// limit the gumball to a sub-collection (e.g. "gen1_").  A prefix of None disables the filter
+    SetAcceptedTokenPrefix {
+        /// optional prefix required of all token IDs sent to the gumball
+        prefix: Option<String>,
+    },
+    /// set how much minting statistics the gumball should record
+    SetStatsResolution {
+        /// level of statistics to record
+        level: StatsLevel,
+    },
+    /// manually register a listing address if the factory's RegisterListing callback was lost.  This
+    /// bypasses the factory authentication, so it can only be called by an admin and should only be
+    /// used as a recovery mechanism
+    ForwardListingRegistration {
+        listing_address: HumanAddr,
+        /// optional address of the factory whose RegisterListing was lost.  If provided, the
+        /// listing will use the label and closing time given when it was created
+        factory: Option<HumanAddr>,
+    },
+    /// have the gumball generate the viewing key it will use with its collection contract when querying
+    /// the dossier of the example NFT.  This allows the example display to include information only
+    /// visible to the token owner.  The key is never revealed, because anyone who knows it could view the
+    /// gumball's inventory
+    SetExampleQueryViewingKey {
+        /// entropy used to generate the viewing key
+        entropy: String,
+    },
+    /// set the contract whose NftDossier will be displayed by NftListingDisplay instead of the collection's.
+    /// This is used for blind-box drops whose real metadata is hidden until a reveal.  Set enabled to false
+    /// after the reveal to display the collection's metadata again
+    SetPreviewContract {
+        /// code hash and address of the preview contract
+        contract: ContractInfo,
+        /// true if the preview contract should be used
+        enabled: bool,
+    },
+    /// set the maximum number of addresses that can be added to or removed from the whitelist in a
+    /// single transaction.  Defaults to 100
+    SetMaxBatchWhitelistUpdate { max: u32 },
+    /// set the randomness oracle contract that is allowed to supply external randomness
+    SetRandomnessOracle {
+        /// code hash and address of the oracle contract
+        contract: ContractInfo,
+    },
+    /// supply randomness from the oracle that will be mixed into the entropy of the next Mint.
+    /// This can only be called by the registered randomness oracle
+    ReceiveOracleRandomness {
+        /// oracle round that produced the randomness
+        round: u64,
+        /// the random bytes
+        randomness: [u8; 32],
+    },
+    /// prevent a token in the pool from being minted.  The token remains in the pool
+    BlacklistToken { token_id: String },
+    /// allow a blacklisted token to be minted again
+    UnblacklistToken { token_id: String },
+    /// remove the message sender's revocation records for the specified permit names.  This
+    /// frees the storage used by permits that will never be used again
+    ClearMyRevokedPermits { permit_names: Vec<String> },
+    /// set the description used by CreateListing when one is not provided.  A description of
+    /// None removes the default
+    SetDefaultListingDescription { description: Option<String> },
+    /// set the maximum number of listings any single factory may register
+    SetMaxListingsPerFactory { max: u32 },
+    /// set the mint price shown in NftListingDisplay.  This is informational only and does not
+    /// affect the price charged by any listing
+    SetDisplayPrice { price: Uint128, denom: String },
+    /// permanently lock the gumball into a read-only state.  Queries and viewing key/permit
+    /// management still work, but no other handle messages will be accepted.  The pool must be
+    /// empty and no tokens may be reserved
+    FinalizeGumball {},
+    /// send the current example NFT info to the specified listings through their factory
+    PushExampleToListings {
+        /// addresses of the listings to update
+        listings: Vec<HumanAddr>,
+        /// code hash and address of the factory that will relay the update
+        factory_contract: ContractInfo,
+    },
+    /// set the time after which the contract becomes read-only.  This can only be set once.
+    /// Admins can still remove, recall, clear and retrieve tokens and cancel reservations after
+    /// the contract expires
+    SetContractExpiry {
+        /// timestamp in seconds since epoch 01/01/1970
+        expires_at: u64,
+    },
+    /// enable or disable recording hashes of the PRNG inputs of every mint.  Only the most recent
+    /// 100 entries are kept
+    SetPrngAuditEnabled { enabled: bool },
+    /// send minted NFTs to a bridge contract, along with their recipients, instead of
+    /// transferring them directly.  The bridge must be registered to receive the NFTs
+    SetBridgeMode {
+        enabled: bool,
+        /// code hash and address of the bridge contract
+        bridge_contract: ContractInfo,
+    },
+    /// require Mint calls from listings to include buyer-supplied entropy
+    SetListingEntropyMode {
+        /// true if listing mints must include entropy
+        required: bool,
+        /// minimum number of characters of entropy
+        min_length: u32,
+    },
+    /// set a memo that will be appended to the transfer memo of a token in the pool
+    SetTokenMemo { token_id: String, memo: String },
+    /// set the minimum number of seconds between Mint calls made directly by admins
+    SetAdminMintCooldown { seconds: u64 },
+    /// set the fee that must be sent with CreateListing
+    SetListingCreationFee {
+        /// fee amount
+        amount: Uint128,
+        /// fee denomination
+        denom: String,
+        /// address that receives the fee
+        recipient: HumanAddr,
+    },
+    /// tag a token in the pool
+    TagToken { token_id: String, tag: String },
+    /// restrict a listing to only draw tokens with the specified tag.  An empty tag removes the
+    /// restriction
+    SetRequiredTag {
+        listing_address: HumanAddr,
+        tag: String,
+    },
+    /// pause listing mints that would leave fewer than the threshold number of tokens in the
+    /// pool.  Admin and whitelist mints are not affected
+    SetMintPauseThreshold { threshold: u32 },
+    /// set how many NFTs are shown in NftListingDisplay, including the example.  Must be
+    /// between 1 and 5
+    SetListingPreviewCount { count: u8 },
+    /// set the maximum number of NFTs the gumball may ever release.  This can only be set before
+    /// any NFTs have been released
+    SetReleasedCap { cap: u64 },
+    /// start a new deposit batch.  NFTs received afterwards are tagged with the new batch ID
+    StartNewBatch {},
+    /// set the relative weight used when drawing tokens from a batch.  Batches without a weight
+    /// default to 1, and a weight of 0 prevents the batch's tokens from being drawn
+    SetBatchWeight { batch_id: u8, weight: u32 },
+    /// pause or unpause minting and deposits
+    SetPaused { paused: bool },
+    /// set the maximum number of NFTs any single address may receive across all mints
+    SetAddressMintLimit { limit: u32 },
+    /// set the selection weight of each rarity tier.  While tier weights are set, a draw picks a
+    /// tier by weight and then a token uniformly within that tier.  Tiers without a weight are not
+    /// drawn.  An empty list returns to uniform draws.  Tier weights are not used for draws that
+    /// are restricted by tag or batch weight
+    SetTierWeights { weights: Vec<TierWeight> },
+    /// remove a listing from the registry so it can no longer request minting
+    DeregisterListing { listing_address: HumanAddr },
+    /// set the window in which listings may mint.  Admins and whitelisted addresses may mint
+    /// outside of the window.  A None value removes that bound
+    SetMintWindow {
+        start: Option<u64>,
+        end: Option<u64>,
+    },
+    /// set whether a deposit that exceeds the maximum capacity should accept the tokens that fit
+    /// and return the rest, or be rejected entirely
+    SetCapacityMode { partial_ok: bool },
+    /// mix fresh entropy into the PRNG seed
+    ReseedPrng { entropy: String },
+    /// set the memo templates used when transferring minted NFTs.  Templates may contain the
+    /// placeholders {listing}, {contract}, {buyer}, and {token_id}.  A None template reverts to
+    /// the default memo for that type of mint
+    SetMemoTemplate {
+        listing_memo: Option<String>,
+        admin_memo: Option<String>,
+        whitelist_memo: Option<String>,
+    },
+    /// re-query the public info of the example NFT.  If no token ID is given, the token at the
+    /// front of the pool is used
+    RefreshExample { token_id: Option<String> },
+    /// set the order in which tokens are drawn from the pool
+    SetDrainMode { mode: DrainMode },
+    /// set the number of NFTs the gumball must hold before a listing may be created
+    SetMinFill { min_fill: u32 },
+    /// remove specific tokens from the pool and return them to the admin
+    RemoveTokens { token_ids: Vec<String> },
+    /// set the number of blocks a recipient must wait between mints
+    SetMintCooldown { blocks: u64 },
+    /// set the types of mint callers the cooldown applies to
+    SetCooldownMode { applies_to: CooldownScope },
+    /// hold a token out of the pool so it is the next token the recipient receives
+    ReserveToken {
+        token_id: String,
+        recipient: HumanAddr,
+        /// optional time after which the token returns to the pool
+        expires_at: Option<u64>,
+    },
+    /// set the maximum number of admins
+    SetMaxAdmins { max: u32 },
+    /// allows the primary admin to hand off primary admin status.  The new primary admin
+    /// replaces the old one in the admin list
+    TransferPrimaryAdmin { new_admin: HumanAddr },
+    /// set the pool size at or below which mints log a low pool alert
+    SetLowPoolThreshold { threshold: u32 },
+    /// disallow the use of several permits at once
+    RevokePermits { permit_names: Vec<String> },
+    /// update the code hash of the collection contract after it has been migrated.  The
+    /// collection address can only be changed with UpdateCollection
+    UpdateCollectionCodeHash { code_hash: String },
+    /// add a minting round.  Round IDs must be added in order
+    AddRound { round: Round },
+    /// make the next minting round active.  Admin mints are not restricted by rounds
+    AdvanceRound {},
+    /// set whether only whitelisted addresses may mint
+    SetWhitelistOnly { enabled: bool },
+    /// set the minimum number of bytes of entropy that must be provided
+    SetMinEntropyLength { length: u8 },
+    /// set the gumball's name, description, and image displayed by listing frontends
+    SetGumballInfo {
+        name: String,
+        description: Option<String>,
+        /// optional image URI.  Must use the http, https, ipfs, or ar scheme
+        image_uri: Option<String>,
+    },
+    /// set the maximum number of buyers a listing may pass in one Mint.  A cap of u32::MAX
+    /// means uncapped
+    SetListingBuyerCap {
+        listing_address: HumanAddr,
+        cap: u32,
+    },
+    /// allow a factory to register one listing without a CreateListing from this gumball
+    AddApprovedFactory { factory: HumanAddr },
+    /// cancel a factory's pending listing registration
+    RemoveApprovedFactory { factory: HumanAddr },
+    /// set the maximum number of tokens sent in one BatchTransferNft message
+    SetMaxTransferBatchSize { size: u32 },
+    /// mix entropy into the pool of user contributions used by future mints
+    ContributeEntropy { entropy: String },
+    /// remove tokens from the pool and return them to the admin who deposited them
+    RecallTokens { token_ids: Vec<String> },
+    /// remove all expired entries from the global whitelist
+    RemoveExpired {},
+    /// propose removing every token from the pool and sending them to the recipient.  The
+    /// proposal must be confirmed with ConfirmClearPool before it expires
+    ProposeClearPool { recipient: HumanAddr },
+    /// confirm a pending proposal to clear the pool
+    ConfirmClearPool { proposal_hash: String },
+    /// change the NFT collection contract.  The pool must be empty and no tokens may be reserved
+    UpdateCollection { new_nft_contract: ContractInfo },
+    /// set the bounds of listing prices.  A None value removes that bound
+    SetPriceBounds {
+        min: Option<Uint128>,
+        max: Option<Uint128>,
+    },
+    /// remove entries from the global whitelist, starting with the most recently added
+    ClearWhitelist {
+        /// optional maximum number of entries to remove.  Removes every entry if None
+        max_entries: Option<u32>,
+    },
+    /// return the tokens reserved for the recipients to the pool, whether or not their
+    /// reservations have expired
+    CancelReservations { recipients: Vec<HumanAddr> },
 }
 
 /// Responses from handle functions
@@ -149,6 +437,174 @@ pub enum HandleAnswer {
     RemoveFromWhitelist { status: String },
     /// response from retrieving nfts from the wrong collection
     RetrieveNft { status: String },
+    /// response from setting the accepted token ID prefix
+    SetAcceptedTokenPrefix { status: String },
+    /// response from setting the statistics resolution
+    SetStatsResolution { status: String },
+    /// response from setting the viewing key used to query the example NFT
+    SetExampleQueryViewingKey { status: String },
+    /// response from setting the preview contract
+    SetPreviewContract { status: String },
+    /// response from setting the whitelist update batch limit
+    SetMaxBatchWhitelistUpdate { status: String },
+    /// response from setting the randomness oracle
+    SetRandomnessOracle { status: String },
+    /// response from receiving oracle randomness
+    ReceiveOracleRandomness { status: String },
+    /// response from blacklisting a token
+    BlacklistToken { status: String },
+    /// response from removing a token from the blacklist
+    UnblacklistToken { status: String },
+    /// response from clearing revoked permits
+    ClearMyRevokedPermits {
+        /// number of revocation records that were removed
+        cleared: u32,
+    },
+    /// response from setting the default listing description
+    SetDefaultListingDescription { status: String },
+    /// response from setting the maximum number of listings per factory
+    SetMaxListingsPerFactory { status: String },
+    /// response from setting the display price
+    SetDisplayPrice { status: String },
+    /// response from finalizing the gumball
+    FinalizeGumball { status: String },
+    /// response from pushing the example NFT info to listings
+    PushExampleToListings { status: String },
+    /// response from setting the contract expiry
+    SetContractExpiry { status: String },
+    /// response from enabling or disabling the PRNG audit log
+    SetPrngAuditEnabled { status: String },
+    /// response from setting the bridge mode
+    SetBridgeMode { status: String },
+    /// response from setting the listing entropy requirement
+    SetListingEntropyMode { status: String },
+    /// response from setting a token's memo
+    SetTokenMemo { status: String },
+    /// response from setting the admin mint cooldown
+    SetAdminMintCooldown { status: String },
+    /// response from setting the listing creation fee
+    SetListingCreationFee { status: String },
+    /// response from tagging a token
+    TagToken { status: String },
+    /// response from setting a listing's required tag
+    SetRequiredTag { status: String },
+    /// response from setting the mint pause threshold
+    SetMintPauseThreshold { status: String },
+    /// response from setting the listing preview count
+    SetListingPreviewCount { status: String },
+    /// response from setting the released cap
+    SetReleasedCap { status: String },
+    /// response from starting a new batch
+    StartNewBatch {
+        /// ID of the new batch
+        batch_id: u8,
+    },
+    /// response from setting a batch weight
+    SetBatchWeight { status: String },
+    /// response from pausing or unpausing the gumball
+    SetPaused { status: String },
+    /// response from setting the address mint limit
+    SetAddressMintLimit { status: String },
+    /// response from setting the tier weights
+    SetTierWeights { status: String },
+    /// response from deregistering a listing
+    DeregisterListing { status: String },
+    /// response from setting the mint window
+    SetMintWindow { status: String },
+    /// response from setting the capacity mode
+    SetCapacityMode { status: String },
+    /// response from reseeding the PRNG
+    ReseedPrng { status: String },
+    /// response from setting the memo templates
+    SetMemoTemplate { status: String },
+    /// response from refreshing the example NFT
+    RefreshExample { status: String },
+    /// response from setting the drain mode
+    SetDrainMode { status: String },
+    /// response from setting the minimum pool fill
+    SetMinFill { status: String },
+    /// response from removing tokens from the pool
+    RemoveTokens {
+        /// tokens removed from the pool
+        removed: Vec<String>,
+        /// tokens that were not in the pool
+        not_found: Vec<String>,
+    },
+    /// response from setting the mint cooldown
+    SetMintCooldown { status: String },
+    /// response from setting the cooldown mode
+    SetCooldownMode { status: String },
+    /// response from reserving a token
+    ReserveToken { status: String },
+    /// response from setting the maximum number of admins
+    SetMaxAdmins { status: String },
+    /// response from setting the low pool threshold
+    SetLowPoolThreshold { status: String },
+    /// response from revoking several permits
+    RevokePermits {
+        /// number of permits revoked
+        revoked_count: u32,
+        /// permit names that could not be revoked
+        failed: Vec<String>,
+    },
+    /// response from updating the collection contract or its code hash
+    UpdateCollection { status: String },
+    /// response from adding a minting round
+    AddRound { status: String },
+    /// response from advancing to the next minting round
+    AdvanceRound { round_id: u32 },
+    /// response from setting whitelist-only mode
+    SetWhitelistOnly { status: String },
+    /// response from setting the minimum entropy length
+    SetMinEntropyLength { status: String },
+    /// response from setting the gumball branding info
+    SetGumballInfo { status: String },
+    /// response from setting a listing's buyer cap
+    SetListingBuyerCap { status: String },
+    /// response from approving a factory
+    AddApprovedFactory { status: String },
+    /// response from removing a factory's approval
+    RemoveApprovedFactory { status: String },
+    /// response from setting the maximum transfer batch size
+    SetMaxTransferBatchSize { status: String },
+    /// response from contributing entropy
+    ContributeEntropy { status: String },
+    /// response from recalling tokens
+    RecallTokens {
+        /// tokens returned to the depositor
+        recalled: Vec<String>,
+    },
+    /// response from removing expired whitelist entries
+    RemoveExpired {
+        /// number of entries removed
+        removed: u32,
+    },
+    /// response from proposing to clear the pool
+    ProposeClearPool {
+        /// hash that must be sent to confirm the proposal
+        proposal_hash: String,
+        /// last block height the proposal can be confirmed
+        expires_at_block: u64,
+    },
+    /// response from confirming a proposal to clear the pool
+    ConfirmClearPool {
+        /// number of tokens removed from the pool
+        cleared: u32,
+    },
+    /// response from setting the listing price bounds
+    SetPriceBounds { status: String },
+    /// response from clearing the global whitelist
+    ClearWhitelist {
+        /// number of entries removed
+        cleared_count: u32,
+    },
+    /// response from cancelling reservations
+    CancelReservations {
+        /// tokens returned to the pool
+        cancelled: Vec<String>,
+        /// recipients that did not have a reserved token
+        not_found: Vec<HumanAddr>,
+    },
 }
 
 /// Queries
@@ -171,6 +627,196 @@ pub enum QueryMsg {
     Counts {},
     /// display the address and code hash of the nft contract this gumball is used with
     NftContract {},
+    /// lists the listing contracts that have been registered with this gumball.  This can only
+    /// be viewed by an admin
+    ListingRegistry {
+        /// optional address and viewing key of an admin
+        viewer: Option<ViewerInfo>,
+        /// optional permit used to verify admin identity.  If both viewer and permit
+        /// are provided, the viewer will be ignored
+        permit: Option<Permit>,
+        /// page number to display
+        page: u32,
+        /// number of listings per page
+        page_size: u32,
+    },
+    /// display the number of tokens minted by listings on behalf of a referrer
+    ReferralCount { referrer: HumanAddr },
+    /// display entries from the PRNG audit log.  This can only be viewed by an admin
+    PrngAuditLog {
+        /// index of the first entry to display.  Entries older than the 100 most recent have
+        /// been overwritten and are skipped
+        start: u64,
+        /// maximum number of entries to display
+        limit: u8,
+        /// optional address and viewing key of an admin
+        viewer: Option<ViewerInfo>,
+        /// optional permit used to verify admin identity.  If both viewer and permit
+        /// are provided, the viewer will be ignored
+        permit: Option<Permit>,
+    },
+    /// display the number of NFTs released and how many of them were bridged
+    BridgeStats {},
+    /// display the number of tokens in the pool with the specified tag.  This can only be
+    /// viewed by an admin
+    TaggedTokenCount {
+        tag: String,
+        /// optional address and viewing key of an admin
+        viewer: Option<ViewerInfo>,
+        /// optional permit used to verify admin identity.  If both viewer and permit
+        /// are provided, the viewer will be ignored
+        permit: Option<Permit>,
+    },
+    /// display a page of the token IDs currently in the pool.  This can only be viewed by an
+    /// admin
+    PoolTokens {
+        /// page number to display
+        page: u32,
+        /// number of token IDs per page
+        page_size: u32,
+        /// optional address and viewing key of an admin
+        viewer: Option<ViewerInfo>,
+        /// optional permit used to verify admin identity.  If both viewer and permit
+        /// are provided, the viewer will be ignored
+        permit: Option<Permit>,
+    },
+    /// display whether the gumball is paused
+    IsPaused {},
+    /// display the number of NFTs an address has received.  This can only be viewed by an admin
+    AddressMintCount {
+        /// address to display
+        address: HumanAddr,
+        /// optional address and viewing key of an admin
+        viewer: Option<ViewerInfo>,
+        /// optional permit used to verify admin identity.  If both viewer and permit
+        /// are provided, the viewer will be ignored
+        permit: Option<Permit>,
+    },
+    /// lists the addresses of the registered listings.  This can only be viewed by an admin
+    RegisteredListings {
+        /// page number to display
+        page: u32,
+        /// number of listings per page
+        page_size: u32,
+        /// optional address and viewing key of an admin
+        viewer: Option<ViewerInfo>,
+        /// optional permit used to verify admin identity.  If both viewer and permit
+        /// are provided, the viewer will be ignored
+        permit: Option<Permit>,
+    },
+    /// display whether an address is whitelisted.  This can only be viewed by the address itself
+    /// or an admin
+    IsWhitelisted {
+        /// address to check
+        address: HumanAddr,
+        /// optional address and viewing key of the queried address or an admin
+        viewer: Option<ViewerInfo>,
+        /// optional permit used to verify identity.  If both viewer and permit
+        /// are provided, the viewer will be ignored
+        permit: Option<Permit>,
+    },
+    /// display a page of whitelisted addresses and their remaining mints.  This can only be
+    /// viewed by an admin
+    WhitelistSnapshot {
+        /// page number to display
+        page: u32,
+        /// number of addresses per page
+        page_size: u32,
+        /// optional address and viewing key of an admin
+        viewer: Option<ViewerInfo>,
+        /// optional permit used to verify admin identity.  If both viewer and permit
+        /// are provided, the viewer will be ignored
+        permit: Option<Permit>,
+    },
+    /// display the version of the gumball's storage layout
+    SchemaVersion {},
+    /// display the name and version of this contract
+    ContractInfo {},
+    /// display the active minting round
+    CurrentRound {},
+    /// display whether only whitelisted addresses may mint
+    WhitelistOnly {},
+    /// display how long it has been since the PRNG was seeded
+    PrngFreshness {
+        /// optional address and viewing key of an admin
+        viewer: Option<ViewerInfo>,
+        /// optional permit used to verify admin identity.  If both viewer and permit
+        /// are provided, the viewer will be ignored
+        permit: Option<Permit>,
+    },
+    /// verify the stored pool count matches the token index entries
+    PoolIntegrity {
+        /// optional address and viewing key of an admin
+        viewer: Option<ViewerInfo>,
+        /// optional permit used to verify admin identity.  If both viewer and permit
+        /// are provided, the viewer will be ignored
+        permit: Option<Permit>,
+    },
+    /// display the gumball's name, description, and image
+    GumballInfo {},
+    /// display the gumball's counts, collection, and operational flags in a single query
+    Status {},
+    /// display the token IDs in a range of pool indices.  This can only be viewed by an admin
+    TokensInRange {
+        /// first pool index to display
+        start_index: u32,
+        /// pool index after the last one to display
+        end_index: u32,
+        /// optional address and viewing key of an admin
+        viewer: Option<ViewerInfo>,
+        /// optional permit used to verify admin identity.  If both viewer and permit
+        /// are provided, the viewer will be ignored
+        permit: Option<Permit>,
+    },
+    /// display the number of NFTs a listing has dispensed.  This can only be viewed by an admin
+    ListingMintCount {
+        /// listing address to display
+        listing_address: HumanAddr,
+        /// optional address and viewing key of an admin
+        viewer: Option<ViewerInfo>,
+        /// optional permit used to verify admin identity.  If both viewer and permit
+        /// are provided, the viewer will be ignored
+        permit: Option<Permit>,
+    },
+    /// display the most recent mint events.  This can only be viewed by an admin
+    MintLog {
+        /// optional address and viewing key of an admin
+        viewer: Option<ViewerInfo>,
+        /// optional permit used to verify admin identity.  If both viewer and permit
+        /// are provided, the viewer will be ignored
+        permit: Option<Permit>,
+    },
+    /// display the token ID stored at a pool index.  This can only be viewed by an admin
+    TokenAtIndex {
+        /// pool index to display
+        index: u32,
+        /// optional address and viewing key of an admin
+        viewer: Option<ViewerInfo>,
+        /// optional permit used to verify admin identity.  If both viewer and permit
+        /// are provided, the viewer will be ignored
+        permit: Option<Permit>,
+    },
+    /// display the cached info of a pool token.  This can only be viewed by an admin
+    TokenMetadata {
+        /// ID of the token
+        token_id: String,
+        /// optional address and viewing key of an admin
+        viewer: Option<ViewerInfo>,
+        /// optional permit used to verify admin identity.  If both viewer and permit
+        /// are provided, the viewer will be ignored
+        permit: Option<Permit>,
+    },
+    /// display how often each value of a trait appears in the cached info of the pool tokens.
+    /// This can only be viewed by an admin
+    TraitAggregates {
+        /// trait type to count
+        trait_type: String,
+        /// optional address and viewing key of an admin
+        viewer: Option<ViewerInfo>,
+        /// optional permit used to verify admin identity.  If both viewer and permit
+        /// are provided, the viewer will be ignored
+        permit: Option<Permit>,
+    },
 }
 
 /// responses to queries
@@ -181,15 +827,25 @@ pub enum QueryAnswer {
     Admins {
         // current admins
         admins: Vec<HumanAddr>,
+        // maximum number of admins
+        max_admins: u32,
     },
     /// display the public info of an example NFT
     NftListingDisplay {
         /// the nft fields of interest
         nft_info: NftDossierForListing,
+        /// the nft fields of each stored example
+        nft_infos: Vec<NftDossierForListing>,
+        /// optional gumball name set by an admin
+        name: Option<String>,
         /// nft contract address that will contain this token,
         nft_contract_address: HumanAddr,
         /// true if this minting option can mint one more nft
         mintable: bool,
+        /// optional informational mint price and denomination set by an admin
+        price: Option<(Uint128, String)>,
+        /// cached info of other pool NFTs for carousel displays
+        additional_examples: Vec<NftDossierForListing>,
     },
     /// display the gumball counts
     Counts {
@@ -197,12 +853,175 @@ pub enum QueryAnswer {
         available: u32,
         /// number of NFTs released
         released: u64,
+        /// optional per-caller mint statistics if the gumball is recording full statistics
+        mint_stats: Option<MintStats>,
+        /// optional maximum number of NFTs the gumball may ever release
+        released_cap: Option<u64>,
+        /// block height of the last deposit
+        last_deposit_block: u64,
+        /// block height of the last mint
+        last_mint_block: u64,
     },
     /// display the address and code hash of the nft contract this gumball is used with
     NftContract {
         code_hash: String,
         address: HumanAddr,
     },
+    /// display a page of the registered listings
+    ListingRegistry {
+        /// registered listings on this page
+        listings: Vec<ListingRecord>,
+        /// total number of registered listings
+        total: u32,
+        /// page number displayed
+        page: u32,
+    },
+    /// display a referrer's mint count
+    ReferralCount { count: u32 },
+    /// display PRNG audit log entries
+    PrngAuditLog {
+        /// the audit log entries
+        entries: Vec<PrngAuditEntry>,
+        /// total number of entries ever written to the audit log
+        total: u64,
+    },
+    /// display the bridge statistics
+    BridgeStats {
+        /// number of NFTs released
+        minted: u64,
+        /// number of NFTs released through the bridge
+        bridged: u64,
+    },
+    /// display the number of tokens with a tag
+    TaggedTokenCount { count: u32 },
+    /// display a page of the pool's token IDs
+    PoolTokens {
+        /// token IDs on this page
+        token_ids: Vec<String>,
+        /// number of tokens in the pool
+        total: u32,
+    },
+    /// display whether the gumball is paused
+    IsPaused { paused: bool },
+    /// display the number of NFTs an address has received
+    AddressMintCount { count: u32 },
+    /// display a page of registered listing addresses
+    RegisteredListings {
+        /// registered listing addresses on this page
+        listings: Vec<HumanAddr>,
+        /// total number of registered listings
+        total: u32,
+    },
+    /// display an address' whitelist status
+    IsWhitelisted {
+        /// true if the address is whitelisted
+        whitelisted: bool,
+        /// number of whitelist mints the address has remaining
+        remaining_quantity: u32,
+        /// optional time after which the address can no longer use its whitelist mints
+        expires_at: Option<u64>,
+    },
+    /// display a page of the whitelist
+    WhitelistSnapshot {
+        /// whitelisted addresses on this page
+        entries: Vec<WhitelistEntry>,
+        /// total number of whitelisted addresses
+        total: u32,
+    },
+    /// display the version of the gumball's storage layout
+    SchemaVersion { version: u32 },
+    /// display the name and version of this contract
+    ContractInfo {
+        /// contract name
+        name: String,
+        /// contract version
+        version: String,
+        /// version of the contract's storage layout
+        schema_version: u32,
+    },
+    /// display the active minting round
+    CurrentRound { round: Option<Round> },
+    /// display whether only whitelisted addresses may mint
+    WhitelistOnly { enabled: bool },
+    /// display how long it has been since the PRNG was seeded
+    PrngFreshness {
+        /// block height the PRNG was last seeded
+        last_seeded_at_block: u64,
+        /// number of mints since the PRNG was last seeded
+        mints_since_reseed: u64,
+        /// number of NFTs released
+        total_mints: u64,
+    },
+    /// display the result of the pool integrity check
+    PoolIntegrity {
+        /// number of NFTs the pool count says are available
+        stored_count: u32,
+        /// number of token index entries actually present
+        actual_count: u32,
+        /// stored count minus actual count
+        discrepancy: i64,
+        /// true if the stored and actual counts match
+        is_consistent: bool,
+    },
+    /// display the gumball's name, description, and image
+    GumballInfo {
+        name: String,
+        description: Option<String>,
+        image_uri: Option<String>,
+    },
+    /// display the gumball's counts, collection, and operational flags
+    Status {
+        /// count of available NFTs
+        available: u32,
+        /// number of NFTs released
+        released: u64,
+        /// address of the nft contract
+        nft_contract_address: HumanAddr,
+        /// code hash of the nft contract
+        nft_contract_code_hash: String,
+        /// true if the gumball is paused
+        paused: bool,
+        /// true if the gumball has been finalized and no longer accepts deposits
+        pool_sealed: bool,
+        /// true if only whitelisted addresses may mint
+        whitelist_only: bool,
+        /// true if the gumball can mint one more nft
+        mintable: bool,
+    },
+    /// display the token IDs in a range of pool indices
+    TokensInRange {
+        /// pool index and ID of each token in the range
+        tokens: Vec<(u32, String)>,
+    },
+    /// display the number of NFTs a listing has dispensed
+    ListingMintCount { listing: HumanAddr, minted: u64 },
+    /// display the most recent mint events, newest first
+    MintLog { events: Vec<MintEventHumanized> },
+    /// display the token ID stored at a pool index
+    TokenAtIndex {
+        /// the pool index
+        index: u32,
+        /// the token ID, or None if nothing is stored at the index
+        token_id: Option<String>,
+    },
+    /// display the cached info of a pool token
+    TokenMetadata {
+        /// ID of the token
+        token_id: String,
+        /// the cached info, or None if the token's info was not cached
+        nft_info: Option<NftDossierForListing>,
+    },
+    /// display how often each value of a trait appears in the pool
+    TraitAggregates {
+        /// the trait type counted
+        trait_type: String,
+        /// number of occurrences of each value, sorted by value
+        value_counts: Vec<TraitCount>,
+        /// number of tokens with at least one attribute of the trait type
+        tokens_with_trait: u32,
+        /// number of pool tokens with cached info
+        total_tokens: u32,
+    },
 }
 
 /// the address and viewing key making an authenticated query request
@@ -213,3 +1032,143 @@ pub struct ViewerInfo {
     /// authentication key string
     pub viewing_key: String,
 }
+
+/// info about a registered listing
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingRecord {
+    /// address of the listing
+    pub address: HumanAddr,
+    /// optional String label of the listing.  This will be None if the listing was manually registered
+    pub label: Option<String>,
+    /// optional timestamp after which the operator may close the listing.  This will be None if the
+    /// listing was manually registered
+    pub closes_at: Option<u64>,
+    /// number of NFTs minted through the listing
+    pub minted_count: u64,
+}
+
+/// level of minting statistics the gumball records
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsLevel {
+    /// do not record any statistics beyond the total number of NFTs released.  Per-address mint
+    /// counts are only kept while an address mint limit is set, and the mint log, per-listing mint
+    /// counts and mints since the last reseed are not updated
+    None,
+    /// only record the total number of NFTs released
+    Basic,
+    /// record the total released as well as the number of NFTs minted by each type of caller
+    Full,
+}
+
+/// configuration of a minting round
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Round {
+    /// round ID.  Rounds are numbered in the order they are added, starting at 0
+    pub id: u32,
+    /// true if only whitelisted addresses may mint during the round
+    pub whitelist_only: bool,
+    /// time the round opens
+    pub opens_at: u64,
+    /// time the round closes
+    pub closes_at: u64,
+    /// maximum number of NFTs each address may receive during the round.  0 is unlimited
+    pub max_per_buyer: u32,
+}
+
+/// number of pool tokens with a trait value
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TraitCount {
+    /// trait value
+    pub value: String,
+    /// number of trait occurrences with the value
+    pub count: u32,
+}
+
+/// whitelisted address and its remaining mints
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WhitelistEntry {
+    /// whitelisted address
+    pub address: HumanAddr,
+    /// number of whitelist mints the address has remaining
+    pub remaining: u32,
+    /// optional time after which the address can no longer use its whitelist mints
+    pub expires_at: Option<u64>,
+}
+
+/// types of mint callers the per-address mint cooldown applies to
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CooldownScope {
+    /// only mints called by whitelisted addresses
+    Whitelist,
+    /// mints called by whitelisted addresses and listings.  A listing purchase for a recipient
+    /// still in cooldown is reverted
+    WhitelistAndListings,
+    /// every mint, including admin mints
+    All,
+}
+
+/// order in which tokens are drawn from the pool
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DrainMode {
+    /// draw tokens at random
+    Random,
+    /// draw tokens in the order they were deposited.  Tier weights are ignored
+    Sequential,
+}
+
+/// hashes of the PRNG inputs used by a mint
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PrngAuditEntry {
+    /// block height of the mint
+    pub block_height: u64,
+    /// block time of the mint in seconds since epoch 01/01/1970
+    pub block_time: u64,
+    /// sha256 hash of the entropy used by the mint
+    pub entropy_hash: [u8; 32],
+    /// sha256 hash of the PRNG seed used by the mint
+    pub seed_hash: [u8; 32],
+}
+
+/// displayable record of a distributed NFT
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MintEventHumanized {
+    /// address that received the NFT
+    pub recipient: HumanAddr,
+    /// ID of the NFT
+    pub token_id: String,
+    /// type of address that called Mint.  0 for a listing, 1 for an admin, and 2 for a
+    /// whitelisted address
+    pub caller_type: u8,
+    /// block height of the mint
+    pub block_height: u64,
+}
+
+/// message that can be included when sending NFTs to the gumball
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct DepositMsg {
+    /// optional rarity tier of the deposited tokens.  Defaults to tier 0
+    pub tier: Option<u8>,
+}
+
+/// selection weight of a rarity tier
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TierWeight {
+    /// the rarity tier
+    pub tier: u8,
+    /// relative selection weight of the tier
+    pub weight: u32,
+}
+
+/// number of NFTs minted by each type of caller
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+pub struct MintStats {
+    /// number of NFTs purchased through listings
+    pub listing: u64,
+    /// number of NFTs distributed by admins
+    pub admin: u64,
+    /// number of NFTs minted by whitelisted addresses
+    pub whitelist: u64,
+}
@@ -1,5 +1,6 @@
 use crate::contract::BLOCK_SIZE;
-use cosmwasm_std::{Api, CanonicalAddr, HumanAddr, StdResult};
+use crate::msg::ViewerInfo;
+use cosmwasm_std::{Api, Binary, CanonicalAddr, HumanAddr, StdResult};
 use schemars::JsonSchema;
 use secret_toolkit::utils::{HandleCallback, Query};
 use serde::{Deserialize, Serialize};
@@ -276,6 +277,11 @@ pub enum Snip721HandleMsg {
         /// list of transfers to perform
         transfers: Vec<Transfer>,
     },
+    /// send many tokens to contracts
+    BatchSendNft {
+        /// list of sends to perform
+        sends: Vec<Send>,
+    },
 }
 
 impl HandleCallback for Snip721HandleMsg {
@@ -293,12 +299,40 @@ pub struct Transfer {
     pub memo: String,
 }
 
+/// token send info used when doing a BatchSendNft
+#[derive(Serialize)]
+pub struct Send {
+    /// recipient contract of the sent tokens
+    pub contract: HumanAddr,
+    /// tokens being sent
+    pub token_ids: Vec<String>,
+    /// optional msg to send with the tokens
+    pub msg: Option<Binary>,
+    /// optional memo for the tx
+    pub memo: Option<String>,
+}
+
+/// msg included when sending tokens to a bridge contract
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BridgeMsg {
+    /// mint the sent tokens on another chain
+    BridgeMint {
+        /// recipient of the bridged tokens
+        recipient: HumanAddr,
+    },
+}
+
 /// snip721 query msgs
 #[derive(Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Snip721QueryMsg {
-    /// displays all the public information about a token
-    NftDossier { token_id: String },
+    /// displays all the information about a token that the viewer is permitted to see
+    NftDossier {
+        token_id: String,
+        /// optional address and viewing key of the querier
+        viewer: Option<ViewerInfo>,
+    },
 }
 
 impl Query for Snip721QueryMsg {
@@ -1,3 +1,6 @@
+use crate::contract_info::StoreContractInfo;
+use crate::snip721::StoredNftDossierForListing;
+use cosmwasm_std::{CanonicalAddr, Uint128};
 use serde::{Deserialize, Serialize};
 
 /// storage key for the token count
@@ -8,20 +11,200 @@ pub const ADMINS_KEY: &[u8] = b"admin";
 pub const COLLECTION_KEY: &[u8] = b"nftctt";
 /// storage key for this minter's address
 pub const MY_ADDRESS_KEY: &[u8] = b"myaddr";
-/// storage key for the example NFT
+/// storage key for the example NFTs
 pub const EXAMPLE_KEY: &[u8] = b"xamp";
 /// storage key for prng seed
 pub const PRNG_SEED_KEY: &[u8] = b"seed";
-/// storage key for the expected factory address that will call to register a listing
-pub const EXPECTED_KEY: &[u8] = b"expect";
-/// prefix for token id storage
+/// storage key for the number of registered listings
+pub const LIST_COUNT_KEY: &[u8] = b"listcnt";
+/// storage key for the prefix all accepted token IDs must start with
+pub const TOKEN_PREFIX_KEY: &[u8] = b"tknpfx";
+/// storage key for the level of minting statistics to record
+pub const STATS_LEVEL_KEY: &[u8] = b"statlvl";
+/// storage key for the per-caller mint statistics
+pub const MINT_STATS_KEY: &[u8] = b"mntstat";
+/// storage key for the viewing key used when querying the example NFT's dossier
+pub const EXAMPLE_QUERY_VK_KEY: &[u8] = b"xampvk";
+/// storage key for the blind-box preview contract
+pub const PREVIEW_CONTRACT_KEY: &[u8] = b"preview";
+/// storage key for the maximum number of addresses in a single whitelist update
+pub const BULK_WL_LIMIT_KEY: &[u8] = b"wllimit";
+/// storage key for the randomness oracle contract
+pub const RANDOMNESS_ORACLE_KEY: &[u8] = b"oracle";
+/// storage key for the randomness most recently supplied by the oracle
+pub const ORACLE_RANDOMNESS_KEY: &[u8] = b"orclrand";
+/// storage key for the default description used when creating listings
+pub const DEFAULT_LISTING_DESC_KEY: &[u8] = b"dfltdesc";
+/// storage key for the maximum number of listings a single factory may register
+pub const MAX_PER_FACTORY_KEY: &[u8] = b"maxfctry";
+/// storage key for the informational mint price shown in listing displays
+pub const DISPLAY_PRICE_KEY: &[u8] = b"dispprc";
+/// storage key for the flag indicating the gumball has been finalized
+pub const IS_FINALIZED_KEY: &[u8] = b"final";
+/// storage key for the time after which the contract becomes read-only
+pub const CONTRACT_EXPIRY_KEY: &[u8] = b"expiry";
+/// storage key for the flag enabling the PRNG audit log
+pub const PRNG_AUDIT_ENABLED_KEY: &[u8] = b"audit";
+/// storage key for the number of entries ever written to the PRNG audit log
+pub const PRNG_AUDIT_COUNT_KEY: &[u8] = b"auditcnt";
+/// storage key for the NFT bridge contract
+pub const BRIDGE_MODE_KEY: &[u8] = b"bridge";
+/// storage key for the number of NFTs released through the bridge
+pub const BRIDGE_RELEASED_KEY: &[u8] = b"bridged";
+/// storage key for the buyer entropy requirement of listing mints
+pub const LISTING_ENTROPY_KEY: &[u8] = b"lstent";
+/// storage key for the minimum number of seconds between admin mints
+pub const ADMIN_MINT_COOLDOWN_KEY: &[u8] = b"admcool";
+/// storage key for the time of the last admin mint
+pub const ADMIN_LAST_MINT_TIME_KEY: &[u8] = b"admlast";
+/// storage key for the listing creation fee
+pub const LISTING_FEE_KEY: &[u8] = b"listfee";
+/// storage key for the number of tokens reserved from listing mints
+pub const MINT_PAUSE_THRESHOLD_KEY: &[u8] = b"pausethr";
+/// storage key for the number of NFTs shown in listing displays
+pub const LISTING_PREVIEW_COUNT_KEY: &[u8] = b"prevcnt";
+/// storage key for the maximum number of NFTs the gumball may ever release
+pub const RELEASED_CAP_KEY: &[u8] = b"relcap";
+/// storage key for the ID of the batch currently being deposited
+pub const CURRENT_BATCH_ID_KEY: &[u8] = b"batchid";
+/// storage key for the flag indicating minting and deposits are paused
+pub const PAUSED_KEY: &[u8] = b"paused";
+/// storage key for the maximum number of NFTs any address may receive
+pub const ADDR_MINT_LIMIT_KEY: &[u8] = b"addrlim";
+/// storage key for the selection weight of each rarity tier
+pub const TIER_WEIGHTS_KEY: &[u8] = b"tierwgt";
+/// storage key for the time public minting opens
+pub const MINT_START_KEY: &[u8] = b"mintstrt";
+/// storage key for the time public minting closes
+pub const MINT_END_KEY: &[u8] = b"mintend";
+/// storage key for the minimum listing price
+pub const MIN_PRICE_KEY: &[u8] = b"minprice";
+/// storage key for the maximum listing price
+pub const MAX_PRICE_KEY: &[u8] = b"maxprice";
+/// storage key for the maximum number of NFTs the pool may hold
+pub const MAX_CAPACITY_KEY: &[u8] = b"maxcap";
+/// storage key for the flag allowing deposits to be partially accepted when the pool is full
+pub const CAPACITY_PARTIAL_KEY: &[u8] = b"cappart";
+/// storage key for the block height the PRNG was last seeded
+pub const PRNG_SEED_BLOCK_KEY: &[u8] = b"seedblk";
+/// storage key for the number of mints since the PRNG was last seeded
+pub const MINTS_SINCE_RESEED_KEY: &[u8] = b"mintsrs";
+/// storage key for the running hash of user-contributed entropy
+pub const ENTROPY_POOL_KEY: &[u8] = b"entpool";
+/// storage key for the memo template used for listing mints
+pub const LISTING_MEMO_KEY: &[u8] = b"lstmemo";
+/// storage key for the memo template used for admin mints
+pub const ADMIN_MEMO_KEY: &[u8] = b"admmemo";
+/// storage key for the memo template used for whitelist mints
+pub const WHITELIST_MEMO_KEY: &[u8] = b"wlmemo";
+/// storage key for the order in which tokens are drawn from the pool
+pub const DRAIN_MODE_KEY: &[u8] = b"drain";
+/// storage key for the number of tokens the pool must hold before a listing may be created
+pub const MIN_FILL_KEY: &[u8] = b"minfill";
+/// storage key for the number of blocks an address must wait between mints
+pub const MINT_COOLDOWN_KEY: &[u8] = b"cooldown";
+/// storage key for the types of mint callers the cooldown applies to
+pub const COOLDOWN_SCOPE_KEY: &[u8] = b"cdscope";
+/// storage key for the number of whitelisted addresses
+pub const WHITELIST_COUNT_KEY: &[u8] = b"wlcnt";
+/// storage key for the maximum number of admins
+pub const MAX_ADMINS_KEY: &[u8] = b"maxadm";
+/// storage key for the primary admin
+pub const PRIMARY_ADMIN_KEY: &[u8] = b"primary";
+/// storage key for the pool size at which a low pool alert is logged
+pub const LOW_POOL_THRESHOLD_KEY: &[u8] = b"lopthr";
+/// storage key for the flag indicating the low pool alert has been logged
+pub const LOW_POOL_NOTIFIED_KEY: &[u8] = b"lopntfy";
+/// storage key for the version of the persisted storage layout
+pub const SCHEMA_VERSION_KEY: &[u8] = b"schema_v";
+/// storage key for the ID of the active minting round
+pub const CURRENT_ROUND_KEY: &[u8] = b"round";
+/// storage key for the number of minting rounds
+pub const ROUND_COUNT_KEY: &[u8] = b"roundcnt";
+/// storage key for the whitelist-only mode flag
+pub const WHITELIST_ONLY_KEY: &[u8] = b"wlonly";
+/// storage key for the minimum entropy length
+pub const MIN_ENTROPY_LEN_KEY: &[u8] = b"minent";
+/// storage key for the gumball branding info
+pub const GUMBALL_INFO_KEY: &[u8] = b"brand";
+/// storage key for the maximum number of tokens sent in one BatchTransferNft message
+pub const MAX_TOKENS_PER_TRANSFER_MSG_KEY: &[u8] = b"maxtfr";
+/// storage key for the flag indicating an admin cleared the pool
+pub const POOL_CLEARED_KEY: &[u8] = b"cleared";
+/// storage key for the pending proposal to clear the pool
+pub const PENDING_CLEAR_KEY: &[u8] = b"pendclr";
+/// storage key for the block height after which the pending clear proposal expires
+pub const PENDING_CLEAR_EXPIRY_KEY: &[u8] = b"pendclrexp";
+/// storage key for the index the next mint event will be written to in the mint log
+pub const MINT_LOG_HEAD_KEY: &[u8] = b"loghead";
+/// storage key for the pool index of the first token in the pool
+pub const SEQ_HEAD_KEY: &[u8] = b"seqhead";
+/// storage key for the number of outstanding token reservations
+pub const RESERVED_COUNT_KEY: &[u8] = b"rsvcnt";
+/// prefix for token id storage.  Each entry holds the token ID and its rarity tier
 pub const PREFIX_TOKEN_IDS: &[u8] = b"ids";
 /// prefix for storage of viewing keys
 pub const PREFIX_VIEW_KEY: &[u8] = b"viewkey";
 /// prefix for storage of registered listings
 pub const PREFIX_LIST_REGISTRY: &[u8] = b"listing";
+/// prefix for the ordered index of registered listings
+pub const PREFIX_LIST_REGISTRY_INDEX: &[u8] = b"listidx";
+/// prefix for storage of blacklisted token IDs that may not be minted
+pub const PREFIX_TOKEN_BLACKLIST: &[u8] = b"blacklist";
 /// prefix for storage of whitelisted addresses allowed to receive a free random NFT
 pub const PREFIX_WHITELIST: &[u8] = b"white";
+/// prefix for the number of listings registered by each factory
+pub const PREFIX_FACTORY_LISTING_COUNT: &[u8] = b"fctrycnt";
+/// prefix for the number of tokens minted by listings on behalf of each referrer
+pub const PREFIX_REFERRAL_COUNTS: &[u8] = b"referral";
+/// prefix for storage of the PRNG audit log
+pub const PREFIX_PRNG_AUDIT: &[u8] = b"prngaudit";
+/// prefix for the reverse index mapping token IDs to their pool index
+pub const PREFIX_TOKEN_REVERSE: &[u8] = b"idsrev";
+/// prefix for storage of per-token transfer memos keyed by pool index
+pub const PREFIX_TOKEN_MEMO: &[u8] = b"tknmemo";
+/// prefix for storage of token tags keyed by pool index
+pub const PREFIX_TOKEN_TAG: &[u8] = b"tkntag";
+/// prefix for storage of the tag a listing is restricted to
+pub const PREFIX_LISTING_TAG: &[u8] = b"listtag";
+/// prefix for storage of cached token metadata keyed by token ID
+pub const PREFIX_TOKEN_METADATA: &[u8] = b"tknmeta";
+/// prefix for storage of the batch ID of each token keyed by pool index
+pub const PREFIX_BATCH_TAG: &[u8] = b"batchtag";
+/// prefix for storage of the address that deposited each token keyed by pool index
+pub const PREFIX_TOKEN_DEPOSITOR: &[u8] = b"depositor";
+/// prefix for storage of the selection weight of each batch
+pub const PREFIX_BATCH_WEIGHT: &[u8] = b"batchwgt";
+/// prefix for storage of the number of NFTs each address has received
+pub const PREFIX_ADDR_MINT_COUNT: &[u8] = b"addrcnt";
+/// prefix for storage of the pool indices occupied by each rarity tier keyed by tier and position
+pub const PREFIX_TIER_INDEX: &[u8] = b"tieridx";
+/// prefix for storage of the number of tokens in each rarity tier
+pub const PREFIX_TIER_COUNT: &[u8] = b"tiercnt";
+/// prefix for storage of each pool index' position in its rarity tier's index
+pub const PREFIX_TIER_POSITION: &[u8] = b"tierpos";
+/// prefix for storage of the block height of each address' last mint
+pub const PREFIX_LAST_MINT_BLOCK: &[u8] = b"lastmint";
+/// prefix for storage of tokens reserved for specific recipients
+pub const PREFIX_RESERVED: &[u8] = b"reserved";
+/// prefix for the ordered index of whitelisted addresses
+pub const PREFIX_WHITELIST_ORDER: &[u8] = b"wlorder";
+/// prefix for storage of each whitelisted address' position in the ordered index
+pub const PREFIX_WHITELIST_POSITION: &[u8] = b"wlpos";
+/// prefix for storage of minting rounds keyed by round ID
+pub const PREFIX_ROUNDS: &[u8] = b"rounds";
+/// prefix for storage of the number of NFTs each address received in a round
+pub const PREFIX_ROUND_MINT_COUNT: &[u8] = b"roundmnt";
+/// prefix for storage of per-round whitelists keyed by round ID and address
+pub const PREFIX_ROUND_WHITELIST: &[u8] = b"roundwl";
+/// prefix for storage of the number of NFTs each listing has dispensed
+pub const PREFIX_LISTING_MINT_COUNT: &[u8] = b"lmcnt";
+/// prefix for storage of each listing's maximum buyers per mint
+pub const PREFIX_BUYER_CAP: &[u8] = b"buyercap";
+/// prefix for storage of the factories that may call to register a listing
+pub const PREFIX_EXPECTED_FACTORIES: &[u8] = b"expfact";
+/// prefix for storage of the ring buffer of recent mint events
+pub const PREFIX_MINT_LOG: &[u8] = b"mintlog";
 /// prefix for the storage of revoked permits
 pub const PREFIX_REVOKED_PERMITS: &str = "revoke";
 
@@ -32,4 +215,151 @@ pub struct Counts {
     pub available: u32,
     // number of nfts distributed
     pub released: u64,
+    // block height of the last deposit
+    pub last_deposit_block: u64,
+    // block height of the last mint
+    pub last_mint_block: u64,
+}
+
+/// counts saved by earlier versions before block heights were recorded
+#[derive(Serialize, Deserialize)]
+pub struct LegacyCounts {
+    // number of nfts available
+    pub available: u32,
+    // number of nfts distributed
+    pub released: u64,
+}
+
+/// record of a distributed NFT kept in the mint log
+#[derive(Serialize, Deserialize)]
+pub struct MintEvent {
+    /// address that received the NFT
+    pub recipient: CanonicalAddr,
+    /// ID of the NFT
+    pub token_id: String,
+    /// type of address that called Mint.  0 for a listing, 1 for an admin, and 2 for a
+    /// whitelisted address
+    pub caller_type: u8,
+    /// block height of the mint
+    pub block_height: u64,
+}
+
+/// branding displayed by listing frontends
+#[derive(Serialize, Deserialize)]
+pub struct GumballInfo {
+    /// gumball name
+    pub name: String,
+    /// optional gumball description
+    pub description: Option<String>,
+    /// optional URI of the gumball's image
+    pub image_uri: Option<String>,
+}
+
+/// contract used to display preview metadata before a blind-box reveal
+#[derive(Serialize, Deserialize)]
+pub struct PreviewContract {
+    /// code hash and address of the preview contract
+    pub contract: StoreContractInfo,
+    /// true if listing displays should use the preview contract
+    pub enabled: bool,
+}
+
+/// bridge contract that receives minted tokens when bridge mode is enabled
+#[derive(Serialize, Deserialize)]
+pub struct BridgeMode {
+    /// code hash and address of the bridge contract
+    pub contract: StoreContractInfo,
+    /// true if mints should be sent through the bridge
+    pub enabled: bool,
+}
+
+/// buyer-supplied entropy requirement for mints called by listings
+#[derive(Serialize, Deserialize)]
+pub struct EntropyRequirement {
+    /// true if listing mints must include entropy
+    pub required: bool,
+    /// minimum number of characters of entropy
+    pub min_length: u32,
+}
+
+/// fee charged for creating a listing
+#[derive(Serialize, Deserialize)]
+pub struct ListingFee {
+    /// fee amount
+    pub amount: Uint128,
+    /// fee denomination
+    pub denom: String,
+    /// address that receives the fee
+    pub recipient: CanonicalAddr,
+}
+
+/// proposal to clear the pool awaiting confirmation
+#[derive(Serialize, Deserialize)]
+pub struct PendingClear {
+    /// address to receive the tokens
+    pub recipient: CanonicalAddr,
+    /// hash identifying the proposal
+    pub proposal_hash: String,
+}
+
+/// whitelist entry of an address
+#[derive(Serialize, Deserialize)]
+pub struct StoredWhitelistEntry {
+    /// number of free mints remaining
+    pub remaining: u32,
+    /// optional time after which the entry can no longer be used
+    pub expires_at: Option<u64>,
+}
+
+/// token held out of the pool for a specific recipient
+#[derive(Serialize, Deserialize)]
+pub struct ReservedToken {
+    /// ID of the reserved token
+    pub token_id: String,
+    /// rarity tier of the reserved token
+    pub tier: u8,
+    /// optional time after which the token returns to the pool
+    pub expires_at: Option<u64>,
+    /// optional memo added to the token's transfer
+    pub memo: Option<String>,
+    /// optional segment tag of the token
+    pub tag: Option<String>,
+    /// optional deposit batch of the token
+    pub batch: Option<u8>,
+    /// optional address that deposited the token
+    pub depositor: Option<CanonicalAddr>,
+    /// optional cached info of the token
+    pub metadata: Option<StoredNftDossierForListing>,
+}
+
+/// listing the gumball is waiting for a factory to register
+#[derive(Serialize, Deserialize)]
+pub struct ExpectedListing {
+    /// optional String label of the listing.  None if the factory was approved by an admin
+    pub label: Option<String>,
+    /// optional timestamp after which the operator may close the listing.  None if the factory
+    /// was approved by an admin
+    pub closes_at: Option<u64>,
+}
+
+/// registered listing info
+#[derive(Serialize, Deserialize)]
+pub struct StoredListing {
+    /// address of the listing
+    pub address: CanonicalAddr,
+    /// optional String label of the listing
+    pub label: Option<String>,
+    /// optional timestamp after which the operator may close the listing
+    pub closes_at: Option<u64>,
+    /// optional address of the factory that registered the listing
+    pub factory: Option<CanonicalAddr>,
+}
+
+/// randomness supplied by the randomness oracle
+#[derive(Serialize, Deserialize)]
+pub struct OracleRandomness {
+    /// oracle round that produced the randomness
+    pub round: u64,
+    /// the random bytes
+    pub randomness: [u8; 32],
 }
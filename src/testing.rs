@@ -0,0 +1,3951 @@
+use std::fmt::Debug;
+
+use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    coins, from_binary, to_binary, Api, BankMsg, Binary, CanonicalAddr, CosmosMsg, Env, Extern,
+    HandleResponse, HumanAddr, StdError, StdResult, Uint128, WasmMsg,
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use secret_toolkit::permit::{Permission, Permit, PermitParams, PermitSignature, PubKey};
+
+use crate::contract::{handle, init, query};
+use crate::contract_info::ContractInfo;
+use crate::msg::{
+    CooldownScope, DepositMsg, DrainMode, HandleAnswer, HandleMsg, InitMsg, QueryAnswer, QueryMsg,
+    Round, StatsLevel, TierWeight, TraitCount, ViewerInfo,
+};
+use crate::snip721::{BridgeMsg, Extension, Metadata, StoredNftDossierForListing, Trait};
+use crate::state::{
+    LegacyCounts, COUNT_KEY, ENTROPY_POOL_KEY, EXAMPLE_KEY, EXAMPLE_QUERY_VK_KEY,
+    MINTS_SINCE_RESEED_KEY, MINT_LOG_HEAD_KEY, POOL_CLEARED_KEY, PREFIX_ADDR_MINT_COUNT,
+    PREFIX_LISTING_MINT_COUNT, PREFIX_LIST_REGISTRY_INDEX, PREFIX_TOKEN_MEMO,
+    PREFIX_TOKEN_METADATA, PREFIX_TOKEN_REVERSE, PREFIX_TOKEN_TAG, PRIMARY_ADMIN_KEY,
+    PRNG_SEED_KEY, SEQ_HEAD_KEY,
+};
+use crate::storage::{load, may_load, remove, save};
+
+/// Returns an InitMsg for a gumball used with the specified nft contract
+///
+/// # Arguments
+///
+/// * `nft_addr` - address of the nft contract
+/// * `nft_hash` - code hash of the nft contract
+pub fn mock_init_msg(nft_addr: &str, nft_hash: &str) -> InitMsg {
+    InitMsg {
+        nft_contract: ContractInfo {
+            code_hash: nft_hash.to_string(),
+            address: HumanAddr(nft_addr.to_string()),
+        },
+        entropy: "We're going to need a bigger boat".to_string(),
+        mint_start: None,
+        mint_end: None,
+        max_capacity: None,
+        min_fill_before_listing: None,
+        whitelist_only: None,
+        min_listing_price: None,
+        max_listing_price: None,
+        listing_fee: None,
+        listing_fee_denom: None,
+        listing_fee_recipient: None,
+    }
+}
+
+/// Returns an Env with the specified message sender
+///
+/// # Arguments
+///
+/// * `addr` - address of the message sender
+pub fn mock_env_sender(addr: &str) -> Env {
+    mock_env(addr, &[])
+}
+
+/// Returns mock dependencies
+pub fn mock_deps() -> Extern<MockStorage, MockApi, MockQuerier> {
+    mock_dependencies(20, &[])
+}
+
+// Helper functions
+
+fn init_helper() -> Extern<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_deps();
+    init(
+        &mut deps,
+        mock_env_sender("admin"),
+        mock_init_msg("nftcontract", "nfthash"),
+    )
+    .unwrap();
+    deps
+}
+
+fn receive_helper(
+    deps: &mut Extern<MockStorage, MockApi, MockQuerier>,
+    from: &str,
+    token_ids: &[&str],
+) -> StdResult<HandleResponse> {
+    handle(
+        deps,
+        mock_env_sender("nftcontract"),
+        HandleMsg::BatchReceiveNft {
+            from: HumanAddr(from.to_string()),
+            token_ids: token_ids.iter().map(|id| id.to_string()).collect(),
+            msg: None,
+        },
+    )
+}
+
+fn mint_helper(
+    deps: &mut Extern<MockStorage, MockApi, MockQuerier>,
+    sender: &str,
+    buyers: &[&str],
+) -> StdResult<HandleResponse> {
+    handle(
+        deps,
+        mock_env_sender(sender),
+        HandleMsg::Mint {
+            buyers: buyers.iter().map(|b| HumanAddr(b.to_string())).collect(),
+            entropy: "entropy".to_string(),
+            referrer: None,
+        },
+    )
+}
+
+fn counts_helper(deps: &Extern<MockStorage, MockApi, MockQuerier>) -> (u32, u64) {
+    let query_answer: QueryAnswer =
+        from_binary(&query(deps, QueryMsg::Counts {}).unwrap()).unwrap();
+    match query_answer {
+        QueryAnswer::Counts {
+            available,
+            released,
+            ..
+        } => (available, released),
+        _ => panic!("unexpected"),
+    }
+}
+
+fn distributed_helper(resp: &HandleResponse) -> Vec<String> {
+    let dist = resp
+        .log
+        .iter()
+        .find(|l| l.key.trim_end() == "distributed")
+        .expect("no distributed log")
+        .value
+        .clone();
+    dist.trim_end()
+        .trim_matches(|c| c == '[' || c == ']')
+        .split(", ")
+        .filter(|s| !s.is_empty())
+        .map(|s| s.trim_matches('"').to_string())
+        .collect()
+}
+
+fn extract_error_msg<T: Debug>(error: StdResult<T>) -> String {
+    match error {
+        Ok(response) => panic!("Expected error, but had Ok response: {:#?}", response),
+        Err(err) => match err {
+            StdError::GenericErr { msg, .. } => msg,
+            _ => panic!("Unexpected error result {:#?}", err),
+        },
+    }
+}
+
+fn assert_unauthorized<T: Debug>(result: StdResult<T>) {
+    match result {
+        Err(StdError::Unauthorized { .. }) => {}
+        other => panic!("Expected unauthorized error, got {:#?}", other),
+    }
+}
+
+// Init and receive tests
+
+#[test]
+fn test_init_and_first_receive() {
+    let mut deps = init_helper();
+    assert_eq!(counts_helper(&deps), (0, 0));
+    let query_answer: QueryAnswer =
+        from_binary(&query(&deps, QueryMsg::NftContract {}).unwrap()).unwrap();
+    match query_answer {
+        QueryAnswer::NftContract { code_hash, address } => {
+            assert_eq!(code_hash, "nfthash".to_string());
+            assert_eq!(address, HumanAddr("nftcontract".to_string()));
+        }
+        _ => panic!("unexpected"),
+    }
+
+    // test someone other than the collection trying to add tokens
+    let handle_result = handle(
+        &mut deps,
+        mock_env_sender("spoofer"),
+        HandleMsg::BatchReceiveNft {
+            from: HumanAddr("admin".to_string()),
+            token_ids: vec!["NFT1".to_string()],
+            msg: None,
+        },
+    );
+    let error = extract_error_msg(handle_result);
+    assert!(error.contains("Only the collection contract specified on instantiation"));
+
+    // test a non-admin sending tokens
+    assert_unauthorized(receive_helper(&mut deps, "alice", &["NFT1"]));
+    assert_eq!(counts_helper(&deps), (0, 0));
+
+    // test the admin sending tokens
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3"]).unwrap();
+    assert_eq!(counts_helper(&deps), (3, 0));
+    receive_helper(&mut deps, "admin", &["NFT4"]).unwrap();
+    assert_eq!(counts_helper(&deps), (4, 0));
+    let query_answer: QueryAnswer =
+        from_binary(&query(&deps, QueryMsg::NftListingDisplay {}).unwrap()).unwrap();
+    match query_answer {
+        QueryAnswer::NftListingDisplay {
+            nft_infos,
+            nft_contract_address,
+            mintable,
+            ..
+        } => {
+            // the first token of each deposit is kept as an example
+            assert_eq!(nft_infos.len(), 2);
+            assert_eq!(nft_contract_address, HumanAddr("nftcontract".to_string()));
+            assert!(mintable);
+        }
+        _ => panic!("unexpected"),
+    }
+}
+
+// Listing tests
+
+#[test]
+fn test_mint_from_listing() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3", "NFT4"]).unwrap();
+    let create_msg = || HandleMsg::CreateListing {
+        label: "gumball".to_string(),
+        payment_address: None,
+        factory_contract: ContractInfo {
+            code_hash: "factoryhash".to_string(),
+            address: HumanAddr("factory".to_string()),
+        },
+        buy_contract: ContractInfo {
+            code_hash: "sscrthash".to_string(),
+            address: HumanAddr("sscrt".to_string()),
+        },
+        batch_send: true,
+        price: Uint128(1000000),
+        closes_at: 2000000000,
+        description: None,
+        entropy: "listing entropy".to_string(),
+    };
+
+    // test a non-admin creating a listing
+    assert_unauthorized(handle(&mut deps, mock_env_sender("alice"), create_msg()));
+
+    // test registering a listing without a CreateListing
+    let handle_result = handle(
+        &mut deps,
+        mock_env_sender("factory"),
+        HandleMsg::RegisterListing {
+            listing_address: HumanAddr("listing".to_string()),
+        },
+    );
+    let error = extract_error_msg(handle_result);
+    assert!(error.contains("RegisterListing can only be called by an expected factory"));
+
+    let handle_result = handle(&mut deps, mock_env_sender("admin"), create_msg());
+    assert_eq!(handle_result.unwrap().messages.len(), 1);
+
+    // test someone other than the factory registering the listing
+    let handle_result = handle(
+        &mut deps,
+        mock_env_sender("notfactory"),
+        HandleMsg::RegisterListing {
+            listing_address: HumanAddr("listing".to_string()),
+        },
+    );
+    let error = extract_error_msg(handle_result);
+    assert!(error.contains("RegisterListing can only be called by an expected factory"));
+
+    // test an unregistered listing minting
+    assert_unauthorized(mint_helper(&mut deps, "listing", &["alice"]));
+
+    handle(
+        &mut deps,
+        mock_env_sender("factory"),
+        HandleMsg::RegisterListing {
+            listing_address: HumanAddr("listing".to_string()),
+        },
+    )
+    .unwrap();
+
+    // test minting more than are available
+    let error = extract_error_msg(mint_helper(
+        &mut deps,
+        "listing",
+        &["alice", "bob", "charlie", "dave", "edmund"],
+    ));
+    assert!(error.contains("Trying to mint 5 tokens, but only 4 are available"));
+
+    // test a listing exceeding its buyer cap
+    let set_cap = |deps: &mut Extern<MockStorage, MockApi, MockQuerier>, cap: u32| {
+        handle(
+            deps,
+            mock_env_sender("admin"),
+            HandleMsg::SetListingBuyerCap {
+                listing_address: HumanAddr("listing".to_string()),
+                cap,
+            },
+        )
+        .unwrap();
+    };
+    set_cap(&mut deps, 1);
+    let error = extract_error_msg(mint_helper(&mut deps, "listing", &["alice", "bob"]));
+    assert!(error.contains("Exceeds per-transaction buyer cap of 1 for this listing"));
+    set_cap(&mut deps, u32::MAX);
+
+    let resp = mint_helper(&mut deps, "listing", &["alice", "bob"]).unwrap();
+    assert_eq!(resp.messages.len(), 1);
+    let distributed = distributed_helper(&resp);
+    assert_eq!(distributed.len(), 2);
+    assert_ne!(distributed[0], distributed[1]);
+    assert_eq!(counts_helper(&deps), (2, 2));
+    let lst_cnt = resp
+        .log
+        .iter()
+        .find(|l| l.key.trim_end() == "listing_mint_count")
+        .expect("no listing_mint_count log");
+    assert_eq!(lst_cnt.value.trim_end(), "2");
+
+    // a buyer in cooldown reverts the whole listing purchase instead of being skipped
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetMintCooldown { blocks: 10 },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetCooldownMode {
+            applies_to: CooldownScope::All,
+        },
+    )
+    .unwrap();
+    mint_helper(&mut deps, "listing", &["alice"]).unwrap();
+    receive_helper(&mut deps, "admin", &["NFT5"]).unwrap();
+    let error = extract_error_msg(mint_helper(&mut deps, "listing", &["alice", "charlie"]));
+    assert!(error.contains("E076: alice"));
+    let resp = mint_helper(&mut deps, "listing", &["charlie"]).unwrap();
+    let lst_cnt = resp
+        .log
+        .iter()
+        .find(|l| l.key.trim_end() == "listing_mint_count")
+        .expect("no listing_mint_count log");
+    assert_eq!(lst_cnt.value.trim_end(), "4");
+}
+
+// Whitelist tests
+
+#[test]
+fn test_whitelist_batch_cap() {
+    let mut deps = init_helper();
+    let add_msg = |count: usize| HandleMsg::AddToWhitelist {
+        addresses: (0..count)
+            .map(|i| HumanAddr(format!("buyer{}", i)))
+            .collect(),
+        quantity: None,
+        round_id: None,
+        expires_at: None,
+    };
+
+    // test exceeding the default batch cap
+    let error = extract_error_msg(handle(&mut deps, mock_env_sender("admin"), add_msg(101)));
+    assert!(error.contains("E005: "));
+    handle(&mut deps, mock_env_sender("admin"), add_msg(100)).unwrap();
+}
+
+#[test]
+fn test_whitelist_single_use() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3"]).unwrap();
+
+    // test a non-admin updating the whitelist
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::AddToWhitelist {
+            addresses: vec![HumanAddr("alice".to_string())],
+            quantity: None,
+            round_id: None,
+            expires_at: None,
+        },
+    ));
+    assert_unauthorized(mint_helper(&mut deps, "alice", &["alice"]));
+
+    let handle_result = handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddToWhitelist {
+            addresses: vec![HumanAddr("alice".to_string()), HumanAddr("bob".to_string())],
+            quantity: None,
+            round_id: None,
+            expires_at: None,
+        },
+    );
+    let handle_answer: HandleAnswer = from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+    match handle_answer {
+        HandleAnswer::AddToWhitelist { status } => assert_eq!(status, "success".to_string()),
+        _ => panic!("unexpected"),
+    }
+
+    // test a whitelisted address minting more than its quantity
+    let error = extract_error_msg(mint_helper(&mut deps, "bob", &["bob", "charlie"]));
+    assert!(error.contains("Whitelisted address may only mint 1 more tokens"));
+
+    let resp = mint_helper(&mut deps, "alice", &["alice"]).unwrap();
+    assert_eq!(distributed_helper(&resp).len(), 1);
+    assert_eq!(counts_helper(&deps), (2, 1));
+
+    // test that the whitelist can only be used once
+    assert_unauthorized(mint_helper(&mut deps, "alice", &["alice"]));
+
+    // test removing from the whitelist
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddToWhitelist {
+            addresses: vec![HumanAddr("charlie".to_string())],
+            quantity: None,
+            round_id: None,
+            expires_at: None,
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::RemoveFromWhitelist {
+            addresses: vec![HumanAddr("charlie".to_string())],
+            round_id: None,
+        },
+    )
+    .unwrap();
+    assert_unauthorized(mint_helper(&mut deps, "charlie", &["charlie"]));
+}
+
+// Admin tests
+
+#[test]
+fn test_admin_only_guards() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2"]).unwrap();
+
+    // test non-admins calling admin-only handles
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::AddAdmins {
+            admins: vec![HumanAddr("alice".to_string())],
+        },
+    ));
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::RemoveAdmins {
+            admins: vec![HumanAddr("admin".to_string())],
+        },
+    ));
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::SetAcceptedTokenPrefix {
+            prefix: Some("gen1_".to_string()),
+        },
+    ));
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::ForwardListingRegistration {
+            listing_address: HumanAddr("alice".to_string()),
+            factory: None,
+        },
+    ));
+    assert_unauthorized(mint_helper(&mut deps, "alice", &["alice"]));
+
+    // test an admin adding another admin
+    let handle_result = handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddAdmins {
+            admins: vec![HumanAddr("alice".to_string())],
+        },
+    );
+    let handle_answer: HandleAnswer = from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+    match handle_answer {
+        HandleAnswer::AdminsList { admins } => assert_eq!(
+            admins,
+            vec![
+                HumanAddr("admin".to_string()),
+                HumanAddr("alice".to_string())
+            ]
+        ),
+        _ => panic!("unexpected"),
+    }
+    let resp = mint_helper(&mut deps, "alice", &["bob"]).unwrap();
+    assert_eq!(distributed_helper(&resp).len(), 1);
+
+    // test an admin removing an admin
+    handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::RemoveAdmins {
+            admins: vec![HumanAddr("admin".to_string())],
+        },
+    )
+    .unwrap();
+    assert_unauthorized(mint_helper(&mut deps, "admin", &["bob"]));
+
+    // test the last admin removing themselves along with a non-admin
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::RemoveAdmins {
+            admins: vec![HumanAddr("bob".to_string()), HumanAddr("alice".to_string())],
+        },
+    ));
+    assert!(error.contains("at least one admin must remain"));
+}
+
+// Pool tests
+
+#[test]
+fn test_pool_drain_and_counts() {
+    let mut deps = init_helper();
+    let ids = ["NFT1", "NFT2", "NFT3", "NFT4", "NFT5"];
+    receive_helper(&mut deps, "admin", &ids).unwrap();
+    let mut distributed: Vec<String> = Vec::new();
+    for i in 0..ids.len() {
+        let resp = mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+        distributed.extend(distributed_helper(&resp));
+        assert_eq!(
+            counts_helper(&deps),
+            ((ids.len() - i - 1) as u32, (i + 1) as u64)
+        );
+    }
+    // every token should have been distributed exactly once
+    distributed.sort();
+    assert_eq!(
+        distributed,
+        ids.iter().map(|i| i.to_string()).collect::<Vec<String>>()
+    );
+
+    // test minting from an empty pool
+    let error = extract_error_msg(mint_helper(&mut deps, "admin", &["alice"]));
+    assert!(error.contains("Trying to mint 1 tokens, but only 0 are available"));
+    let query_answer: QueryAnswer =
+        from_binary(&query(&deps, QueryMsg::NftListingDisplay {}).unwrap()).unwrap();
+    match query_answer {
+        QueryAnswer::NftListingDisplay { mintable, .. } => assert!(!mintable),
+        _ => panic!("unexpected"),
+    }
+}
+
+// Query authentication tests
+
+#[test]
+fn test_query_authentication() {
+    let mut deps = init_helper();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetViewingKey {
+            key: "akey".to_string(),
+            padding: None,
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::SetViewingKey {
+            key: "alicekey".to_string(),
+            padding: None,
+        },
+    )
+    .unwrap();
+
+    // test wrong viewing key
+    assert_unauthorized(query(
+        &deps,
+        QueryMsg::Admins {
+            viewer: Some(ViewerInfo {
+                address: HumanAddr("admin".to_string()),
+                viewing_key: "wrong".to_string(),
+            }),
+            permit: None,
+        },
+    ));
+    // test a non-admin with a valid key
+    assert_unauthorized(query(
+        &deps,
+        QueryMsg::Admins {
+            viewer: Some(ViewerInfo {
+                address: HumanAddr("alice".to_string()),
+                viewing_key: "alicekey".to_string(),
+            }),
+            permit: None,
+        },
+    ));
+    // test no credentials
+    assert_unauthorized(query(
+        &deps,
+        QueryMsg::Admins {
+            viewer: None,
+            permit: None,
+        },
+    ));
+
+    // test a permit for a different contract
+    let permit = Permit {
+        params: PermitParams {
+            allowed_tokens: vec![HumanAddr("othercontract".to_string())],
+            permit_name: "gumball".to_string(),
+            chain_id: "pulsar-1".to_string(),
+            permissions: vec![Permission::Owner],
+        },
+        signature: PermitSignature {
+            pub_key: PubKey {
+                r#type: "tendermint/PubKeySecp256k1".to_string(),
+                value: Binary::from(vec![2u8; 33]),
+            },
+            signature: Binary::from(vec![0u8; 64]),
+        },
+    };
+    let error = extract_error_msg(query(
+        &deps,
+        QueryMsg::Admins {
+            viewer: Some(ViewerInfo {
+                address: HumanAddr("admin".to_string()),
+                viewing_key: "akey".to_string(),
+            }),
+            permit: Some(permit),
+        },
+    ));
+    assert!(error.contains("Permit doesn't apply to token"));
+
+    // test a valid admin viewing key
+    let query_answer: QueryAnswer = from_binary(
+        &query(
+            &deps,
+            QueryMsg::Admins {
+                viewer: Some(ViewerInfo {
+                    address: HumanAddr("admin".to_string()),
+                    viewing_key: "akey".to_string(),
+                }),
+                permit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    match query_answer {
+        QueryAnswer::Admins { admins, max_admins } => {
+            assert_eq!(admins, vec![HumanAddr("admin".to_string())]);
+            assert_eq!(max_admins, 20);
+        }
+        _ => panic!("unexpected"),
+    }
+}
+
+// Permit tests
+
+#[test]
+fn test_clear_revoked_permits() {
+    let mut deps = init_helper();
+    handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::RevokePermit {
+            permit_name: "first".to_string(),
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::RevokePermit {
+            permit_name: "second".to_string(),
+        },
+    )
+    .unwrap();
+    let clear_msg = || HandleMsg::ClearMyRevokedPermits {
+        permit_names: vec![
+            "first".to_string(),
+            "second".to_string(),
+            "never".to_string(),
+        ],
+    };
+
+    // test someone else trying to clear alice's revocations
+    let handle_result = handle(&mut deps, mock_env_sender("bob"), clear_msg());
+    let handle_answer: HandleAnswer = from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+    match handle_answer {
+        HandleAnswer::ClearMyRevokedPermits { cleared } => assert_eq!(cleared, 0),
+        _ => panic!("unexpected"),
+    }
+
+    let handle_result = handle(&mut deps, mock_env_sender("alice"), clear_msg());
+    let handle_answer: HandleAnswer = from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+    match handle_answer {
+        HandleAnswer::ClearMyRevokedPermits { cleared } => assert_eq!(cleared, 2),
+        _ => panic!("unexpected"),
+    }
+
+    // test that the records are gone
+    let handle_result = handle(&mut deps, mock_env_sender("alice"), clear_msg());
+    let handle_answer: HandleAnswer = from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+    match handle_answer {
+        HandleAnswer::ClearMyRevokedPermits { cleared } => assert_eq!(cleared, 0),
+        _ => panic!("unexpected"),
+    }
+
+    // test revoking several permits at once
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::RevokePermits {
+            permit_names: vec![],
+        },
+    ));
+    assert!(error.contains("No permit names were provided"));
+    let handle_result = handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::RevokePermits {
+            permit_names: vec!["first".to_string(), " ".to_string(), "second".to_string()],
+        },
+    );
+    let handle_answer: HandleAnswer = from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+    match handle_answer {
+        HandleAnswer::RevokePermits {
+            revoked_count,
+            failed,
+        } => {
+            assert_eq!(revoked_count, 2);
+            assert_eq!(failed, vec![" ".to_string()]);
+        }
+        _ => panic!("unexpected"),
+    }
+    let handle_result = handle(&mut deps, mock_env_sender("alice"), clear_msg());
+    let handle_answer: HandleAnswer = from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+    match handle_answer {
+        HandleAnswer::ClearMyRevokedPermits { cleared } => assert_eq!(cleared, 2),
+        _ => panic!("unexpected"),
+    }
+}
+
+// Finalization tests
+
+#[test]
+fn test_finalize_gumball() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2"]).unwrap();
+
+    // test a non-admin finalizing
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::FinalizeGumball {},
+    ));
+    // test finalizing while the gumball still holds tokens
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::FinalizeGumball {},
+    ));
+    assert!(error.contains("E079: 2 pooled or reserved tokens"));
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::ReserveToken {
+            token_id: "NFT1".to_string(),
+            recipient: HumanAddr("bob".to_string()),
+            expires_at: None,
+        },
+    )
+    .unwrap();
+    mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::FinalizeGumball {},
+    ));
+    assert!(error.contains("E079: 1 pooled or reserved tokens"));
+    mint_helper(&mut deps, "admin", &["bob"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::FinalizeGumball {},
+    )
+    .unwrap();
+
+    // test that state changing messages are rejected
+    let error = extract_error_msg(receive_helper(&mut deps, "admin", &["NFT3"]));
+    assert!(error.contains("Gumball is finalized"));
+    let error = extract_error_msg(mint_helper(&mut deps, "admin", &["alice"]));
+    assert!(error.contains("Gumball is finalized"));
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddAdmins {
+            admins: vec![HumanAddr("alice".to_string())],
+        },
+    ));
+    assert!(error.contains("Gumball is finalized"));
+
+    // test that viewing keys and queries still work
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetViewingKey {
+            key: "akey".to_string(),
+            padding: None,
+        },
+    )
+    .unwrap();
+    assert!(query(
+        &deps,
+        QueryMsg::Admins {
+            viewer: Some(ViewerInfo {
+                address: HumanAddr("admin".to_string()),
+                viewing_key: "akey".to_string(),
+            }),
+            permit: None,
+        },
+    )
+    .is_ok());
+    assert_eq!(counts_helper(&deps), (0, 2));
+}
+
+#[test]
+fn test_contract_expiry() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3"]).unwrap();
+    let env = mock_env_sender("admin");
+    let expires_at = env.block.time + 10;
+    handle(
+        &mut deps,
+        env.clone(),
+        HandleMsg::SetContractExpiry { expires_at },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        env.clone(),
+        HandleMsg::ReserveToken {
+            token_id: "NFT1".to_string(),
+            recipient: HumanAddr("bob".to_string()),
+            expires_at: None,
+        },
+    )
+    .unwrap();
+    let mut later = env;
+    later.block.time = expires_at;
+
+    // test that state changing messages are rejected
+    let error = extract_error_msg(handle(
+        &mut deps,
+        later.clone(),
+        HandleMsg::Mint {
+            buyers: vec![HumanAddr("bob".to_string())],
+            entropy: "entropy".to_string(),
+            referrer: None,
+        },
+    ));
+    assert!(error.contains("Contract has expired"));
+    let error = extract_error_msg(handle(
+        &mut deps,
+        later.clone(),
+        HandleMsg::AddAdmins {
+            admins: vec![HumanAddr("alice".to_string())],
+        },
+    ));
+    assert!(error.contains("Contract has expired"));
+
+    // test that admins can still get the tokens out
+    handle(
+        &mut deps,
+        later.clone(),
+        HandleMsg::CancelReservations {
+            recipients: vec![HumanAddr("bob".to_string())],
+        },
+    )
+    .unwrap();
+    assert_eq!(counts_helper(&deps).0, 3);
+    handle(
+        &mut deps,
+        later.clone(),
+        HandleMsg::RemoveTokens {
+            token_ids: vec!["NFT1".to_string()],
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        later,
+        HandleMsg::RecallTokens {
+            token_ids: vec!["NFT2".to_string(), "NFT3".to_string()],
+        },
+    )
+    .unwrap();
+    assert_eq!(counts_helper(&deps).0, 0);
+}
+
+// Token memo tests
+
+#[test]
+fn test_token_memo_follows_pool() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3"]).unwrap();
+    let memo_msg = |id: &str| HandleMsg::SetTokenMemo {
+        token_id: id.to_string(),
+        memo: "First edition".to_string(),
+    };
+
+    // test a non-admin setting a memo
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        memo_msg("NFT1"),
+    ));
+    // test a token that is not in the pool
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        memo_msg("NFT9"),
+    ));
+    assert!(error.contains("Token NFT9 is not in the gumball pool"));
+
+    // every token should stay reachable through the reverse index as the pool shrinks
+    let resp = mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+    let minted = distributed_helper(&resp);
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        memo_msg(&minted[0]),
+    ));
+    assert!(error.contains("is not in the gumball pool"));
+    for id in ["NFT1", "NFT2", "NFT3"]
+        .iter()
+        .filter(|id| **id != minted[0])
+    {
+        handle(&mut deps, mock_env_sender("admin"), memo_msg(id)).unwrap();
+    }
+}
+
+// Tag tests
+
+#[test]
+fn test_tagged_listing_draws() {
+    let mut deps = init_helper();
+    receive_helper(
+        &mut deps,
+        "admin",
+        &["NFT1", "NFT2", "NFT3", "NFT4", "NFT5"],
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::ForwardListingRegistration {
+            listing_address: HumanAddr("listing".to_string()),
+            factory: None,
+        },
+    )
+    .unwrap();
+    for id in ["NFT2", "NFT4"].iter() {
+        handle(
+            &mut deps,
+            mock_env_sender("admin"),
+            HandleMsg::TagToken {
+                token_id: id.to_string(),
+                tag: "legendary".to_string(),
+            },
+        )
+        .unwrap();
+    }
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetRequiredTag {
+            listing_address: HumanAddr("listing".to_string()),
+            tag: "legendary".to_string(),
+        },
+    )
+    .unwrap();
+
+    // test minting more than the tagged tokens
+    let error = extract_error_msg(mint_helper(
+        &mut deps,
+        "listing",
+        &["alice", "bob", "charlie"],
+    ));
+    assert!(error.contains("but only 2 tagged 'legendary' are available"));
+
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetViewingKey {
+            key: "akey".to_string(),
+            padding: None,
+        },
+    )
+    .unwrap();
+    let tagged_count = |deps: &Extern<MockStorage, MockApi, MockQuerier>| {
+        let query_answer: QueryAnswer = from_binary(
+            &query(
+                deps,
+                QueryMsg::TaggedTokenCount {
+                    tag: "legendary".to_string(),
+                    viewer: Some(ViewerInfo {
+                        address: HumanAddr("admin".to_string()),
+                        viewing_key: "akey".to_string(),
+                    }),
+                    permit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        match query_answer {
+            QueryAnswer::TaggedTokenCount { count } => count,
+            _ => panic!("unexpected"),
+        }
+    };
+    assert_eq!(tagged_count(&deps), 2);
+
+    // the listing should only receive tagged tokens even as the pool is reshuffled
+    let mut minted: Vec<String> = Vec::new();
+    for expected_left in [1u32, 0].iter() {
+        let resp = mint_helper(&mut deps, "listing", &["alice"]).unwrap();
+        minted.extend(distributed_helper(&resp));
+        assert_eq!(tagged_count(&deps), *expected_left);
+    }
+    minted.sort();
+    assert_eq!(minted, vec!["NFT2".to_string(), "NFT4".to_string()]);
+    let error = extract_error_msg(mint_helper(&mut deps, "listing", &["alice"]));
+    assert!(error.contains("but only 0 tagged 'legendary' are available"));
+    assert_eq!(counts_helper(&deps), (3, 2));
+}
+
+// Batch weight tests
+
+#[test]
+fn test_batch_weights() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["OLD1", "OLD2", "OLD3"]).unwrap();
+    let handle_result = handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::StartNewBatch {},
+    );
+    let handle_answer: HandleAnswer = from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+    match handle_answer {
+        HandleAnswer::StartNewBatch { batch_id } => assert_eq!(batch_id, 1),
+        _ => panic!("unexpected"),
+    }
+    receive_helper(&mut deps, "admin", &["NEW1", "NEW2"]).unwrap();
+
+    // test weighting a batch that has not started
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetBatchWeight {
+            batch_id: 2,
+            weight: 5,
+        },
+    ));
+    assert!(error.contains("Batch 2 has not been started"));
+
+    // exclude the first batch from selection
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetBatchWeight {
+            batch_id: 0,
+            weight: 0,
+        },
+    )
+    .unwrap();
+    let error = extract_error_msg(mint_helper(
+        &mut deps,
+        "admin",
+        &["alice", "bob", "charlie"],
+    ));
+    assert!(error.contains("but only 2 are eligible to be drawn"));
+    let resp = mint_helper(&mut deps, "admin", &["alice", "bob"]).unwrap();
+    let mut minted = distributed_helper(&resp);
+    minted.sort();
+    assert_eq!(minted, vec!["NEW1".to_string(), "NEW2".to_string()]);
+    assert_eq!(counts_helper(&deps), (3, 2));
+}
+
+// Tier tests
+
+#[test]
+fn test_tier_weights() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["RARE1", "RARE2"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("nftcontract"),
+        HandleMsg::BatchReceiveNft {
+            from: HumanAddr("admin".to_string()),
+            token_ids: vec!["LEG1".to_string(), "LEG2".to_string()],
+            msg: Some(to_binary(&DepositMsg { tier: Some(3) }).unwrap()),
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("nftcontract"),
+        HandleMsg::ReceiveNft {
+            sender: HumanAddr("admin".to_string()),
+            token_id: "RARE3".to_string(),
+            msg: None,
+        },
+    )
+    .unwrap();
+
+    // test duplicate tiers
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetTierWeights {
+            weights: vec![
+                TierWeight { tier: 3, weight: 1 },
+                TierWeight { tier: 3, weight: 2 },
+            ],
+        },
+    ));
+    assert!(error.contains("Tier 3 was given more than one weight"));
+
+    // only draw from tier 3
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetTierWeights {
+            weights: vec![
+                TierWeight { tier: 0, weight: 0 },
+                TierWeight { tier: 3, weight: 1 },
+            ],
+        },
+    )
+    .unwrap();
+    let mut minted: Vec<String> = Vec::new();
+    for _ in 0..2 {
+        let resp = mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+        minted.extend(distributed_helper(&resp));
+    }
+    minted.sort();
+    assert_eq!(minted, vec!["LEG1".to_string(), "LEG2".to_string()]);
+    let error = extract_error_msg(mint_helper(&mut deps, "admin", &["alice"]));
+    assert!(error.contains("but only 0 are eligible to be drawn"));
+
+    // the tier 0 index should still be accurate after the pool was reshuffled
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetTierWeights {
+            weights: vec![TierWeight { tier: 0, weight: 1 }],
+        },
+    )
+    .unwrap();
+    let resp = mint_helper(&mut deps, "admin", &["alice", "bob", "charlie"]).unwrap();
+    let mut minted = distributed_helper(&resp);
+    minted.sort();
+    assert_eq!(
+        minted,
+        vec![
+            "RARE1".to_string(),
+            "RARE2".to_string(),
+            "RARE3".to_string()
+        ]
+    );
+    assert_eq!(counts_helper(&deps), (0, 5));
+}
+
+// Listing registry tests
+
+#[test]
+fn test_deregister_listing() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetViewingKey {
+            key: "akey".to_string(),
+            padding: None,
+        },
+    )
+    .unwrap();
+    for listing in ["listing1", "listing2", "listing3"].iter() {
+        handle(
+            &mut deps,
+            mock_env_sender("admin"),
+            HandleMsg::ForwardListingRegistration {
+                listing_address: HumanAddr(listing.to_string()),
+                factory: None,
+            },
+        )
+        .unwrap();
+    }
+    let registered = |deps: &Extern<MockStorage, MockApi, MockQuerier>| {
+        let query_answer: QueryAnswer = from_binary(
+            &query(
+                deps,
+                QueryMsg::RegisteredListings {
+                    page: 0,
+                    page_size: 10,
+                    viewer: Some(ViewerInfo {
+                        address: HumanAddr("admin".to_string()),
+                        viewing_key: "akey".to_string(),
+                    }),
+                    permit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        match query_answer {
+            QueryAnswer::RegisteredListings { listings, total } => (listings, total),
+            _ => panic!("unexpected"),
+        }
+    };
+    let (listings, total) = registered(&deps);
+    assert_eq!(total, 3);
+    assert_eq!(listings.len(), 3);
+
+    // test a non-admin deregistering
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::DeregisterListing {
+            listing_address: HumanAddr("listing1".to_string()),
+        },
+    ));
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::DeregisterListing {
+            listing_address: HumanAddr("listing1".to_string()),
+        },
+    )
+    .unwrap();
+    let (listings, total) = registered(&deps);
+    assert_eq!(total, 2);
+    assert_eq!(
+        listings,
+        vec![
+            HumanAddr("listing3".to_string()),
+            HumanAddr("listing2".to_string())
+        ]
+    );
+
+    // test deregistering a listing that is not registered
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::DeregisterListing {
+            listing_address: HumanAddr("listing1".to_string()),
+        },
+    ));
+    assert!(error.contains("Listing not found in registry"));
+    assert_unauthorized(mint_helper(&mut deps, "listing1", &["alice"]));
+    mint_helper(&mut deps, "listing3", &["alice"]).unwrap();
+
+    // the registry shows each listing's mint count and skips missing index entries
+    let registry = |deps: &Extern<MockStorage, MockApi, MockQuerier>| {
+        let query_answer: QueryAnswer = from_binary(
+            &query(
+                deps,
+                QueryMsg::ListingRegistry {
+                    page: 0,
+                    page_size: 10,
+                    viewer: Some(ViewerInfo {
+                        address: HumanAddr("admin".to_string()),
+                        viewing_key: "akey".to_string(),
+                    }),
+                    permit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        match query_answer {
+            QueryAnswer::ListingRegistry {
+                listings, total, ..
+            } => (listings, total),
+            _ => panic!("unexpected"),
+        }
+    };
+    let (listings, total) = registry(&deps);
+    assert_eq!(total, 2);
+    assert_eq!(listings[0].address, HumanAddr("listing3".to_string()));
+    assert_eq!(listings[0].minted_count, 1);
+    assert_eq!(listings[1].address, HumanAddr("listing2".to_string()));
+    assert_eq!(listings[1].minted_count, 0);
+    remove(
+        &mut PrefixedStorage::new(PREFIX_LIST_REGISTRY_INDEX, &mut deps.storage),
+        &0u32.to_le_bytes(),
+    );
+    let (listings, total) = registry(&deps);
+    assert_eq!(total, 2);
+    assert_eq!(listings.len(), 1);
+    assert_eq!(listings[0].address, HumanAddr("listing2".to_string()));
+}
+
+#[test]
+fn test_whitelist_quantity() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3", "NFT4"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddToWhitelist {
+            addresses: vec![HumanAddr("dave".to_string())],
+            quantity: Some(3),
+            round_id: None,
+            expires_at: None,
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("dave"),
+        HandleMsg::SetViewingKey {
+            key: "dkey".to_string(),
+            padding: None,
+        },
+    )
+    .unwrap();
+    let remaining = |deps: &Extern<MockStorage, MockApi, MockQuerier>| {
+        let query_answer: QueryAnswer = from_binary(
+            &query(
+                deps,
+                QueryMsg::IsWhitelisted {
+                    address: HumanAddr("dave".to_string()),
+                    viewer: Some(ViewerInfo {
+                        address: HumanAddr("dave".to_string()),
+                        viewing_key: "dkey".to_string(),
+                    }),
+                    permit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        match query_answer {
+            QueryAnswer::IsWhitelisted {
+                whitelisted,
+                remaining_quantity,
+                expires_at,
+            } => {
+                assert_eq!(whitelisted, remaining_quantity > 0);
+                assert!(expires_at.is_none());
+                remaining_quantity
+            }
+            _ => panic!("unexpected"),
+        }
+    };
+    assert_eq!(remaining(&deps), 3);
+
+    // test someone else checking dave's status
+    assert_unauthorized(query(
+        &deps,
+        QueryMsg::IsWhitelisted {
+            address: HumanAddr("dave".to_string()),
+            viewer: Some(ViewerInfo {
+                address: HumanAddr("alice".to_string()),
+                viewing_key: "dkey".to_string(),
+            }),
+            permit: None,
+        },
+    ));
+
+    mint_helper(&mut deps, "dave", &["dave", "edmund"]).unwrap();
+    assert_eq!(remaining(&deps), 1);
+    let error = extract_error_msg(mint_helper(&mut deps, "dave", &["dave", "edmund"]));
+    assert!(error.contains("Whitelisted address may only mint 1 more tokens"));
+    mint_helper(&mut deps, "dave", &["dave"]).unwrap();
+    assert_eq!(remaining(&deps), 0);
+    assert_unauthorized(mint_helper(&mut deps, "dave", &["dave"]));
+}
+
+#[test]
+fn test_max_capacity() {
+    let mut deps = mock_deps();
+    let mut init_msg = mock_init_msg("nftcontract", "nfthash");
+    init_msg.max_capacity = Some(3);
+    init(&mut deps, mock_env_sender("admin"), init_msg).unwrap();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2"]).unwrap();
+
+    // test all-or-nothing rejecting a deposit that does not fit
+    let error = extract_error_msg(receive_helper(&mut deps, "admin", &["NFT3", "NFT4"]));
+    assert!(error.contains("would exceed the gumball's maximum capacity of 3"));
+    assert_eq!(counts_helper(&deps).0, 2);
+
+    // test a non-admin setting the capacity mode
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::SetCapacityMode { partial_ok: true },
+    ));
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetCapacityMode { partial_ok: true },
+    )
+    .unwrap();
+
+    // test partially accepting a deposit and returning the rest
+    let resp = receive_helper(&mut deps, "admin", &["NFT3", "NFT4", "NFT5"]).unwrap();
+    assert_eq!(resp.messages.len(), 1);
+    assert_eq!(counts_helper(&deps).0, 3);
+    let resp = receive_helper(&mut deps, "admin", &["NFT6"]).unwrap();
+    assert_eq!(resp.messages.len(), 1);
+    assert_eq!(counts_helper(&deps).0, 3);
+}
+
+#[test]
+fn test_duplicate_token_rejected() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2"]).unwrap();
+
+    // test re-sending a token already in the pool
+    let error = extract_error_msg(receive_helper(&mut deps, "admin", &["NFT3", "NFT1"]));
+    assert!(error.starts_with("E016: "));
+    assert!(error.contains("Token NFT1 is already in the gumball pool"));
+
+    // test sending the same token twice in one batch
+    let error = extract_error_msg(receive_helper(&mut deps, "admin", &["NFT3", "NFT3"]));
+    assert!(error.contains("Token NFT3 is already in the gumball pool"));
+    assert_eq!(counts_helper(&deps).0, 2);
+
+    // a token may be returned to the gumball once it has been distributed
+    let resp = mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+    let minted = distributed_helper(&resp);
+    let minted: Vec<&str> = minted.iter().map(|s| s.as_str()).collect();
+    receive_helper(&mut deps, "admin", &minted).unwrap();
+    assert_eq!(counts_helper(&deps).0, 2);
+    let error = extract_error_msg(receive_helper(&mut deps, "admin", &["NFT1", "NFT2"]));
+    assert!(error.contains("is already in the gumball pool"));
+}
+
+#[test]
+fn test_sequential_drain() {
+    let mut deps = init_helper();
+    receive_helper(
+        &mut deps,
+        "admin",
+        &["NFT1", "NFT2", "NFT3", "NFT4", "NFT5"],
+    )
+    .unwrap();
+
+    // test a non-admin setting the drain mode
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::SetDrainMode {
+            mode: DrainMode::Sequential,
+        },
+    ));
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetDrainMode {
+            mode: DrainMode::Sequential,
+        },
+    )
+    .unwrap();
+
+    let resp = mint_helper(&mut deps, "admin", &["alice", "bob"]).unwrap();
+    assert_eq!(distributed_helper(&resp), vec!["NFT1", "NFT2"]);
+    // drawing from the front only advances the head
+    assert_eq!(
+        may_load::<u32, _>(&deps.storage, SEQ_HEAD_KEY).unwrap(),
+        Some(2)
+    );
+    // refreshing the example from the head of the pool reaches the collection query
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::RefreshExample { token_id: None },
+    ));
+    assert!(!error.contains("The gumball pool is empty"));
+    assert!(error.contains("Querier system error"));
+    receive_helper(&mut deps, "admin", &["NFT6"]).unwrap();
+    let resp = mint_helper(&mut deps, "admin", &["alice", "bob", "charlie"]).unwrap();
+    assert_eq!(distributed_helper(&resp), vec!["NFT3", "NFT4", "NFT5"]);
+    let resp = mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+    assert_eq!(distributed_helper(&resp), vec!["NFT6"]);
+    assert_eq!(counts_helper(&deps).0, 0);
+    // an emptied pool starts back at index 0
+    assert!(may_load::<u32, _>(&deps.storage, SEQ_HEAD_KEY)
+        .unwrap()
+        .is_none());
+
+    // removing a token behind the head keeps the deposit order
+    receive_helper(&mut deps, "admin", &["NFT7", "NFT8", "NFT9", "NFT10"]).unwrap();
+    let resp = mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+    assert_eq!(distributed_helper(&resp), vec!["NFT7"]);
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::RemoveTokens {
+            token_ids: vec!["NFT9".to_string()],
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        may_load::<u32, _>(&deps.storage, SEQ_HEAD_KEY).unwrap(),
+        Some(2)
+    );
+    let resp = mint_helper(&mut deps, "admin", &["alice", "bob"]).unwrap();
+    assert_eq!(distributed_helper(&resp), vec!["NFT8", "NFT10"]);
+}
+
+#[test]
+fn test_remove_tokens() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3", "NFT4"]).unwrap();
+    let remove_msg = || HandleMsg::RemoveTokens {
+        token_ids: vec!["NFT2".to_string(), "NFT9".to_string(), "NFT2".to_string()],
+    };
+
+    // test a non-admin removing tokens
+    assert_unauthorized(handle(&mut deps, mock_env_sender("alice"), remove_msg()));
+
+    let resp = handle(&mut deps, mock_env_sender("admin"), remove_msg()).unwrap();
+    assert_eq!(resp.messages.len(), 1);
+    let handle_answer: HandleAnswer = from_binary(&resp.data.unwrap()).unwrap();
+    match handle_answer {
+        HandleAnswer::RemoveTokens { removed, not_found } => {
+            assert_eq!(removed, vec!["NFT2".to_string()]);
+            assert_eq!(not_found, vec!["NFT9".to_string(), "NFT2".to_string()]);
+        }
+        _ => panic!("unexpected"),
+    }
+    assert_eq!(counts_helper(&deps).0, 3);
+
+    // the removed token is never drawn
+    let resp = mint_helper(&mut deps, "admin", &["alice", "bob", "charlie"]).unwrap();
+    let mut minted = distributed_helper(&resp);
+    minted.sort();
+    assert_eq!(minted, vec!["NFT1", "NFT3", "NFT4"]);
+
+    // removals honor the transfer batch size and emptying the pool drops the examples
+    receive_helper(&mut deps, "admin", &["NFT5", "NFT6", "NFT7"]).unwrap();
+    assert!(
+        may_load::<Vec<StoredNftDossierForListing>, _>(&deps.storage, EXAMPLE_KEY)
+            .unwrap()
+            .is_some()
+    );
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetMaxTransferBatchSize { size: 2 },
+    )
+    .unwrap();
+    let resp = handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::RemoveTokens {
+            token_ids: vec!["NFT5".to_string(), "NFT6".to_string(), "NFT7".to_string()],
+        },
+    )
+    .unwrap();
+    assert_eq!(resp.messages.len(), 2);
+    assert_eq!(counts_helper(&deps).0, 0);
+    assert!(
+        may_load::<Vec<StoredNftDossierForListing>, _>(&deps.storage, EXAMPLE_KEY)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn test_mint_cooldown() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3", "NFT4"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetMintCooldown { blocks: 10 },
+    )
+    .unwrap();
+
+    // test admin mints bypassing the cooldown by default
+    let resp = mint_helper(&mut deps, "admin", &["alice", "alice"]).unwrap();
+    assert_eq!(distributed_helper(&resp).len(), 2);
+
+    // test a non-admin changing the cooldown mode
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::SetCooldownMode {
+            applies_to: CooldownScope::All,
+        },
+    ));
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetCooldownMode {
+            applies_to: CooldownScope::All,
+        },
+    )
+    .unwrap();
+
+    // alice is skipped because she received a token in this block
+    let resp = mint_helper(&mut deps, "admin", &["alice", "bob"]).unwrap();
+    assert_eq!(distributed_helper(&resp).len(), 1);
+    assert!(resp
+        .log
+        .iter()
+        .any(|l| l.key.trim_end() == "skipped" && l.value.contains("alice")));
+    assert_eq!(counts_helper(&deps).0, 1);
+}
+
+#[test]
+fn test_reserve_token() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3", "NFT4"]).unwrap();
+    let reserve_msg =
+        |token_id: &str, recipient: &str, expires_at: Option<u64>| HandleMsg::ReserveToken {
+            token_id: token_id.to_string(),
+            recipient: HumanAddr(recipient.to_string()),
+            expires_at,
+        };
+
+    // test a non-admin reserving a token
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        reserve_msg("NFT1", "bob", None),
+    ));
+
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        reserve_msg("NFT1", "bob", None),
+    )
+    .unwrap();
+    assert_eq!(counts_helper(&deps).0, 3);
+
+    // test reserving a token that is not in the pool
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        reserve_msg("NFT1", "charlie", None),
+    ));
+    assert!(error.contains("Token NFT1 is not in the gumball pool"));
+
+    // test reserving a second token for the same recipient
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        reserve_msg("NFT2", "bob", None),
+    ));
+    assert!(error.contains("bob already has a reserved token"));
+
+    // the reserved token does not count against the pool
+    let resp = mint_helper(&mut deps, "admin", &["bob"]).unwrap();
+    assert_eq!(distributed_helper(&resp), vec!["NFT1"]);
+    assert_eq!(counts_helper(&deps).0, 3);
+
+    // test an expired reservation returning to the pool
+    let env = mock_env_sender("admin");
+    let expires_at = env.block.time + 10;
+    handle(
+        &mut deps,
+        env.clone(),
+        reserve_msg("NFT2", "charlie", Some(expires_at)),
+    )
+    .unwrap();
+    assert_eq!(counts_helper(&deps).0, 2);
+    let mut later = env;
+    later.block.time = expires_at;
+    handle(
+        &mut deps,
+        later,
+        HandleMsg::Mint {
+            buyers: vec![HumanAddr("charlie".to_string())],
+            entropy: "entropy".to_string(),
+            referrer: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(counts_helper(&deps).0, 2);
+
+    // test cancelling a reservation that has not expired
+    receive_helper(&mut deps, "admin", &["NFT5"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        reserve_msg("NFT5", "dave", None),
+    )
+    .unwrap();
+    assert_eq!(counts_helper(&deps).0, 2);
+    let cancel_msg = || HandleMsg::CancelReservations {
+        recipients: vec![HumanAddr("dave".to_string()), HumanAddr("erin".to_string())],
+    };
+    // test a non-admin cancelling a reservation
+    assert_unauthorized(handle(&mut deps, mock_env_sender("alice"), cancel_msg()));
+    let resp = handle(&mut deps, mock_env_sender("admin"), cancel_msg()).unwrap();
+    let handle_answer: HandleAnswer = from_binary(&resp.data.unwrap()).unwrap();
+    match handle_answer {
+        HandleAnswer::CancelReservations {
+            cancelled,
+            not_found,
+        } => {
+            assert_eq!(cancelled, vec!["NFT5".to_string()]);
+            assert_eq!(not_found, vec![HumanAddr("erin".to_string())]);
+        }
+        _ => panic!("unexpected"),
+    }
+    assert_eq!(counts_helper(&deps).0, 3);
+    // the cancelled token is back in the pool
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        reserve_msg("NFT5", "erin", None),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_reservation_keeps_token_info() {
+    let mut deps = init_helper();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddAdmins {
+            admins: vec![HumanAddr("alice".to_string())],
+        },
+    )
+    .unwrap();
+    receive_helper(&mut deps, "alice", &["NFT1"]).unwrap();
+    receive_helper(&mut deps, "admin", &["NFT2", "NFT3"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetDrainMode {
+            mode: DrainMode::Sequential,
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetTokenMemo {
+            token_id: "NFT1".to_string(),
+            memo: "First edition".to_string(),
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::TagToken {
+            token_id: "NFT1".to_string(),
+            tag: "gold".to_string(),
+        },
+    )
+    .unwrap();
+    // the mock querier can not answer dossier queries, so cache the info directly
+    let mut meta_store = PrefixedStorage::new(PREFIX_TOKEN_METADATA, &mut deps.storage);
+    save(
+        &mut meta_store,
+        b"NFT1",
+        &StoredNftDossierForListing {
+            public_metadata: None,
+            royalty_info: None,
+            mint_run_info: None,
+        },
+    )
+    .unwrap();
+
+    let env = mock_env_sender("admin");
+    let expires_at = env.block.time + 10;
+    handle(
+        &mut deps,
+        env.clone(),
+        HandleMsg::ReserveToken {
+            token_id: "NFT1".to_string(),
+            recipient: HumanAddr("bob".to_string()),
+            expires_at: Some(expires_at),
+        },
+    )
+    .unwrap();
+    // the expired reservation returns to the end of the queue, so bob draws from the front
+    let mut later = env;
+    later.block.time = expires_at;
+    let resp = handle(
+        &mut deps,
+        later,
+        HandleMsg::Mint {
+            buyers: vec![HumanAddr("bob".to_string())],
+            entropy: "entropy".to_string(),
+            referrer: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(distributed_helper(&resp), vec!["NFT2"]);
+
+    // the returned token keeps its memo, tag and cached info
+    let idx: u32 = load(
+        &ReadonlyPrefixedStorage::new(PREFIX_TOKEN_REVERSE, &deps.storage),
+        b"NFT1",
+    )
+    .unwrap();
+    let memo: Option<String> = may_load(
+        &ReadonlyPrefixedStorage::new(PREFIX_TOKEN_MEMO, &deps.storage),
+        &idx.to_le_bytes(),
+    )
+    .unwrap();
+    assert_eq!(memo, Some("First edition".to_string()));
+    let tag: Option<String> = may_load(
+        &ReadonlyPrefixedStorage::new(PREFIX_TOKEN_TAG, &deps.storage),
+        &idx.to_le_bytes(),
+    )
+    .unwrap();
+    assert_eq!(tag, Some("gold".to_string()));
+    assert!(may_load::<StoredNftDossierForListing, _>(
+        &ReadonlyPrefixedStorage::new(PREFIX_TOKEN_METADATA, &deps.storage),
+        b"NFT1",
+    )
+    .unwrap()
+    .is_some());
+
+    // and its depositor can still recall it
+    let resp = handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::RecallTokens {
+            token_ids: vec!["NFT1".to_string()],
+        },
+    )
+    .unwrap();
+    let handle_answer: HandleAnswer = from_binary(&resp.data.unwrap()).unwrap();
+    match handle_answer {
+        HandleAnswer::RecallTokens { recalled } => {
+            assert_eq!(recalled, vec!["NFT1".to_string()]);
+        }
+        _ => panic!("unexpected"),
+    }
+    assert_eq!(counts_helper(&deps).0, 1);
+}
+
+#[test]
+fn test_whitelist_snapshot() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetViewingKey {
+            key: "akey".to_string(),
+            padding: None,
+        },
+    )
+    .unwrap();
+    let addrs = |names: &[&str]| -> Vec<HumanAddr> {
+        names.iter().map(|n| HumanAddr(n.to_string())).collect()
+    };
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddToWhitelist {
+            addresses: addrs(&["alice", "bob", "charlie"]),
+            quantity: Some(2),
+            round_id: None,
+            expires_at: None,
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::RemoveFromWhitelist {
+            addresses: addrs(&["alice"]),
+            round_id: None,
+        },
+    )
+    .unwrap();
+    mint_helper(&mut deps, "bob", &["bob"]).unwrap();
+    mint_helper(&mut deps, "charlie", &["charlie"]).unwrap();
+    let snapshot = |deps: &Extern<MockStorage, MockApi, MockQuerier>, viewer: &str| {
+        query(
+            deps,
+            QueryMsg::WhitelistSnapshot {
+                page: 0,
+                page_size: 10,
+                viewer: Some(ViewerInfo {
+                    address: HumanAddr(viewer.to_string()),
+                    viewing_key: "akey".to_string(),
+                }),
+                permit: None,
+            },
+        )
+    };
+
+    // test a non-admin viewing the whitelist
+    assert_unauthorized(snapshot(&deps, "bob"));
+
+    let query_answer: QueryAnswer = from_binary(&snapshot(&deps, "admin").unwrap()).unwrap();
+    match query_answer {
+        QueryAnswer::WhitelistSnapshot { entries, total } => {
+            assert_eq!(total, 2);
+            let mut listed: Vec<(String, u32)> = entries
+                .into_iter()
+                .map(|e| (e.address.to_string(), e.remaining))
+                .collect();
+            listed.sort();
+            assert_eq!(
+                listed,
+                vec![("bob".to_string(), 1), ("charlie".to_string(), 1)]
+            );
+        }
+        _ => panic!("unexpected"),
+    }
+
+    // an address leaves the snapshot once its mints are used
+    receive_helper(&mut deps, "admin", &["NFT3"]).unwrap();
+    mint_helper(&mut deps, "bob", &["bob"]).unwrap();
+    let query_answer: QueryAnswer = from_binary(&snapshot(&deps, "admin").unwrap()).unwrap();
+    match query_answer {
+        QueryAnswer::WhitelistSnapshot { entries, total } => {
+            assert_eq!(total, 1);
+            assert_eq!(entries[0].address, HumanAddr("charlie".to_string()));
+        }
+        _ => panic!("unexpected"),
+    }
+}
+
+#[test]
+fn test_transfer_primary_admin() {
+    let mut deps = init_helper();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddAdmins {
+            admins: vec![HumanAddr("alice".to_string())],
+        },
+    )
+    .unwrap();
+    let transfer_msg = |new_admin: &str| HandleMsg::TransferPrimaryAdmin {
+        new_admin: HumanAddr(new_admin.to_string()),
+    };
+
+    // test a secondary admin handing off primary status
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        transfer_msg("alice"),
+    ));
+
+    let resp = handle(&mut deps, mock_env_sender("admin"), transfer_msg("bob")).unwrap();
+    let handle_answer: HandleAnswer = from_binary(&resp.data.unwrap()).unwrap();
+    match handle_answer {
+        HandleAnswer::AdminsList { admins } => assert_eq!(
+            admins,
+            vec![HumanAddr("alice".to_string()), HumanAddr("bob".to_string())]
+        ),
+        _ => panic!("unexpected"),
+    }
+
+    // the old primary is no longer an admin
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        transfer_msg("admin"),
+    ));
+    handle(&mut deps, mock_env_sender("bob"), transfer_msg("alice")).unwrap();
+
+    // test removing the primary admin hands primary status to the remover
+    handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::AddAdmins {
+            admins: vec![HumanAddr("bob".to_string())],
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("bob"),
+        HandleMsg::RemoveAdmins {
+            admins: vec![HumanAddr("alice".to_string())],
+        },
+    )
+    .unwrap();
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        transfer_msg("alice"),
+    ));
+    let bob_raw = deps
+        .api
+        .canonical_address(&HumanAddr("bob".to_string()))
+        .unwrap();
+    assert_eq!(
+        load::<CanonicalAddr, _>(&deps.storage, PRIMARY_ADMIN_KEY).unwrap(),
+        bob_raw
+    );
+
+    // a stale primary that is no longer an admin can not reinstate itself
+    let alice_raw = deps
+        .api
+        .canonical_address(&HumanAddr("alice".to_string()))
+        .unwrap();
+    save(&mut deps.storage, PRIMARY_ADMIN_KEY, &alice_raw).unwrap();
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        transfer_msg("alice"),
+    ));
+}
+
+#[test]
+fn test_low_pool_alert() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3", "NFT4"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetLowPoolThreshold { threshold: 2 },
+    )
+    .unwrap();
+    let alerted = |resp: &HandleResponse| {
+        resp.log
+            .iter()
+            .any(|l| l.key.trim_end() == "low_pool_alert")
+    };
+
+    let resp = mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+    assert!(!alerted(&resp));
+    let resp = mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+    assert!(alerted(&resp));
+    let resp = mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+    assert!(!alerted(&resp));
+
+    // refilling above the threshold re-arms the alert
+    receive_helper(&mut deps, "admin", &["NFT5", "NFT6"]).unwrap();
+    let resp = mint_helper(&mut deps, "admin", &["alice", "bob"]).unwrap();
+    assert!(alerted(&resp));
+}
+
+#[test]
+fn test_mint_rounds() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3", "NFT4"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddToWhitelist {
+            addresses: vec![HumanAddr("alice".to_string())],
+            quantity: Some(3),
+            round_id: None,
+            expires_at: None,
+        },
+    )
+    .unwrap();
+    let now = mock_env_sender("admin").block.time;
+    let round = |id: u32, opens_at: u64| Round {
+        id,
+        whitelist_only: true,
+        opens_at,
+        closes_at: now + 100,
+        max_per_buyer: 1,
+    };
+
+    // test a non-admin adding a round
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::AddRound {
+            round: round(0, now),
+        },
+    ));
+
+    // test adding rounds out of order
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddRound {
+            round: round(1, now),
+        },
+    ));
+    assert!(error.contains("The next round ID is 0"));
+
+    // test advancing without any rounds
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AdvanceRound {},
+    ));
+    assert!(error.contains("There is no round to advance to"));
+
+    for &(id, opens_at) in [(0, now), (1, now + 50)].iter() {
+        handle(
+            &mut deps,
+            mock_env_sender("admin"),
+            HandleMsg::AddRound {
+                round: round(id, opens_at),
+            },
+        )
+        .unwrap();
+        // whitelist-only rounds ignore the global whitelist
+        handle(
+            &mut deps,
+            mock_env_sender("admin"),
+            HandleMsg::AddToWhitelist {
+                addresses: vec![HumanAddr("alice".to_string())],
+                quantity: Some(3),
+                round_id: Some(id),
+                expires_at: None,
+            },
+        )
+        .unwrap();
+    }
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AdvanceRound {},
+    )
+    .unwrap();
+    mint_helper(&mut deps, "alice", &["alice"]).unwrap();
+    let error = extract_error_msg(mint_helper(&mut deps, "alice", &["alice"]));
+    assert!(error.contains("alice may only receive 1 NFTs in round 0"));
+
+    // admins are not restricted by rounds
+    mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AdvanceRound {},
+    )
+    .unwrap();
+    let error = extract_error_msg(mint_helper(&mut deps, "alice", &["bob"]));
+    assert!(error.contains("Round 1 has not opened yet"));
+    let query_answer: QueryAnswer =
+        from_binary(&query(&deps, QueryMsg::CurrentRound {}).unwrap()).unwrap();
+    match query_answer {
+        QueryAnswer::CurrentRound { round: current } => {
+            assert_eq!(current, Some(round(1, now + 50)))
+        }
+        _ => panic!("unexpected"),
+    }
+}
+
+#[test]
+fn test_round_whitelist() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3", "NFT4"]).unwrap();
+    let now = mock_env_sender("admin").block.time;
+    let add_round =
+        |deps: &mut Extern<MockStorage, MockApi, MockQuerier>, id: u32, whitelist_only: bool| {
+            handle(
+                deps,
+                mock_env_sender("admin"),
+                HandleMsg::AddRound {
+                    round: Round {
+                        id,
+                        whitelist_only,
+                        opens_at: now,
+                        closes_at: now + 100,
+                        max_per_buyer: 0,
+                    },
+                },
+            )
+            .unwrap();
+        };
+    let whitelist = |deps: &mut Extern<MockStorage, MockApi, MockQuerier>,
+                     addr: &str,
+                     round_id: Option<u32>| {
+        handle(
+            deps,
+            mock_env_sender("admin"),
+            HandleMsg::AddToWhitelist {
+                addresses: vec![HumanAddr(addr.to_string())],
+                quantity: Some(1),
+                round_id,
+                expires_at: None,
+            },
+        )
+    };
+
+    // test whitelisting for a round that does not exist
+    let error = extract_error_msg(whitelist(&mut deps, "alice", Some(0)));
+    assert!(error.contains("Round 0 does not exist"));
+
+    add_round(&mut deps, 0, true);
+    add_round(&mut deps, 1, false);
+    whitelist(&mut deps, "alice", Some(0)).unwrap();
+    whitelist(&mut deps, "bob", None).unwrap();
+    whitelist(&mut deps, "charlie", Some(1)).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AdvanceRound {},
+    )
+    .unwrap();
+
+    // a whitelist-only round does not fall back to the global whitelist
+    assert_unauthorized(mint_helper(&mut deps, "bob", &["bob"]));
+    // addresses whitelisted for a later round may not mint yet
+    assert_unauthorized(mint_helper(&mut deps, "charlie", &["charlie"]));
+    mint_helper(&mut deps, "alice", &["alice"]).unwrap();
+    // the round whitelist entry is used up
+    assert_unauthorized(mint_helper(&mut deps, "alice", &["alice"]));
+
+    // the next round falls back to the global whitelist
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AdvanceRound {},
+    )
+    .unwrap();
+    mint_helper(&mut deps, "charlie", &["charlie"]).unwrap();
+    mint_helper(&mut deps, "bob", &["bob"]).unwrap();
+
+    // test removing a round whitelist entry
+    whitelist(&mut deps, "alice", Some(1)).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::RemoveFromWhitelist {
+            addresses: vec![HumanAddr("alice".to_string())],
+            round_id: Some(1),
+        },
+    )
+    .unwrap();
+    assert_unauthorized(mint_helper(&mut deps, "alice", &["alice"]));
+}
+
+#[test]
+fn test_whitelist_only() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddToWhitelist {
+            addresses: vec![HumanAddr("alice".to_string())],
+            quantity: None,
+            round_id: None,
+            expires_at: None,
+        },
+    )
+    .unwrap();
+
+    // test a non-admin setting the mode
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::SetWhitelistOnly { enabled: true },
+    ));
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetWhitelistOnly { enabled: true },
+    )
+    .unwrap();
+    let query_answer: QueryAnswer =
+        from_binary(&query(&deps, QueryMsg::WhitelistOnly {}).unwrap()).unwrap();
+    match query_answer {
+        QueryAnswer::WhitelistOnly { enabled } => assert!(enabled),
+        _ => panic!("unexpected"),
+    }
+
+    // admins may not mint in whitelist-only mode
+    let error = extract_error_msg(mint_helper(&mut deps, "admin", &["bob"]));
+    assert!(error.contains("only whitelisted addresses may mint"));
+    mint_helper(&mut deps, "alice", &["alice"]).unwrap();
+
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetWhitelistOnly { enabled: false },
+    )
+    .unwrap();
+    mint_helper(&mut deps, "admin", &["bob"]).unwrap();
+}
+
+#[test]
+fn test_min_entropy_length() {
+    // test init with short entropy
+    let mut deps = mock_deps();
+    let mut init_msg = mock_init_msg("nftcontract", "nfthash");
+    init_msg.entropy = "1".to_string();
+    let error = extract_error_msg(init(&mut deps, mock_env_sender("admin"), init_msg));
+    assert!(error.contains("Entropy must be at least 16 characters"));
+
+    let mut deps = init_helper();
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::CreateViewingKey {
+            entropy: "short".to_string(),
+        },
+    ));
+    assert!(error.contains("Entropy must be at least 16 characters"));
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::ReseedPrng {
+            entropy: "short".to_string(),
+        },
+    ));
+    assert!(error.contains("Entropy must be at least 16 characters"));
+
+    // test a non-admin setting the length
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::SetMinEntropyLength { length: 4 },
+    ));
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetMinEntropyLength { length: 4 },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::CreateViewingKey {
+            entropy: "short".to_string(),
+        },
+    )
+    .unwrap();
+    // length is measured in bytes, not characters
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetMinEntropyLength { length: 6 },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::CreateViewingKey {
+            entropy: "ééé".to_string(),
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_prng_freshness() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetViewingKey {
+            key: "key".to_string(),
+            padding: None,
+        },
+    )
+    .unwrap();
+    let freshness = |deps: &Extern<MockStorage, MockApi, MockQuerier>| {
+        let query_answer: QueryAnswer = from_binary(
+            &query(
+                deps,
+                QueryMsg::PrngFreshness {
+                    viewer: Some(ViewerInfo {
+                        address: HumanAddr("admin".to_string()),
+                        viewing_key: "key".to_string(),
+                    }),
+                    permit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        match query_answer {
+            QueryAnswer::PrngFreshness {
+                last_seeded_at_block,
+                mints_since_reseed,
+                ..
+            } => (last_seeded_at_block, mints_since_reseed),
+            _ => panic!("unexpected"),
+        }
+    };
+    let init_height = mock_env_sender("admin").block.height;
+    assert_eq!(freshness(&deps), (init_height, 0));
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetMintCooldown { blocks: 10 },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetCooldownMode {
+            applies_to: CooldownScope::All,
+        },
+    )
+    .unwrap();
+    mint_helper(&mut deps, "admin", &["alice", "bob"]).unwrap();
+    assert_eq!(freshness(&deps), (init_height, 2));
+    // skipped buyers are not counted
+    let resp = mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+    assert!(distributed_helper(&resp).is_empty());
+    assert_eq!(freshness(&deps), (init_height, 2));
+
+    let mut later = mock_env_sender("admin");
+    later.block.height += 100;
+    handle(
+        &mut deps,
+        later,
+        HandleMsg::ReseedPrng {
+            entropy: "some fresh entropy for the prng".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(freshness(&deps), (init_height + 100, 0));
+}
+
+#[test]
+fn test_pool_integrity() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetViewingKey {
+            key: "key".to_string(),
+            padding: None,
+        },
+    )
+    .unwrap();
+    mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+    let query_answer: QueryAnswer = from_binary(
+        &query(
+            &deps,
+            QueryMsg::PoolIntegrity {
+                viewer: Some(ViewerInfo {
+                    address: HumanAddr("admin".to_string()),
+                    viewing_key: "key".to_string(),
+                }),
+                permit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    match query_answer {
+        QueryAnswer::PoolIntegrity {
+            stored_count,
+            actual_count,
+            discrepancy,
+            is_consistent,
+        } => {
+            assert_eq!(stored_count, 2);
+            assert_eq!(actual_count, 2);
+            assert_eq!(discrepancy, 0);
+            assert!(is_consistent);
+        }
+        _ => panic!("unexpected"),
+    }
+}
+
+#[test]
+fn test_counts_block_heights() {
+    let mut deps = init_helper();
+    let block_heights = |deps: &Extern<MockStorage, MockApi, MockQuerier>| {
+        let query_answer: QueryAnswer =
+            from_binary(&query(deps, QueryMsg::Counts {}).unwrap()).unwrap();
+        match query_answer {
+            QueryAnswer::Counts {
+                available,
+                last_deposit_block,
+                last_mint_block,
+                ..
+            } => (available, last_deposit_block, last_mint_block),
+            _ => panic!("unexpected"),
+        }
+    };
+
+    // counts saved by earlier versions report block heights of 0
+    save(
+        &mut deps.storage,
+        COUNT_KEY,
+        &LegacyCounts {
+            available: 0,
+            released: 0,
+        },
+    )
+    .unwrap();
+    assert_eq!(block_heights(&deps), (0, 0, 0));
+
+    let height = mock_env_sender("admin").block.height;
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2"]).unwrap();
+    assert_eq!(block_heights(&deps), (2, height, 0));
+    let mut later = mock_env_sender("admin");
+    later.block.height += 10;
+    handle(
+        &mut deps,
+        later,
+        HandleMsg::Mint {
+            buyers: vec![HumanAddr("alice".to_string())],
+            entropy: "entropy".to_string(),
+            referrer: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(block_heights(&deps), (1, height, height + 10));
+}
+
+#[test]
+fn test_legacy_example() {
+    let mut deps = init_helper();
+    // an example saved by earlier versions is displayed as the only example
+    save(
+        &mut deps.storage,
+        EXAMPLE_KEY,
+        &StoredNftDossierForListing {
+            public_metadata: None,
+            royalty_info: None,
+            mint_run_info: None,
+        },
+    )
+    .unwrap();
+    let query_answer: QueryAnswer =
+        from_binary(&query(&deps, QueryMsg::NftListingDisplay {}).unwrap()).unwrap();
+    match query_answer {
+        QueryAnswer::NftListingDisplay { nft_infos, .. } => assert_eq!(nft_infos.len(), 1),
+        _ => panic!("unexpected"),
+    }
+}
+
+#[test]
+fn test_gumball_info() {
+    let mut deps = init_helper();
+    let set_info =
+        |deps: &mut Extern<MockStorage, MockApi, MockQuerier>, sender: &str, image_uri: &str| {
+            handle(
+                deps,
+                mock_env_sender(sender),
+                HandleMsg::SetGumballInfo {
+                    name: "Gumball".to_string(),
+                    description: Some("A gumball".to_string()),
+                    image_uri: Some(image_uri.to_string()),
+                },
+            )
+        };
+
+    // test a non-admin setting the info
+    assert_unauthorized(set_info(&mut deps, "alice", "ipfs://image"));
+    // test an unsupported scheme
+    let error = extract_error_msg(set_info(&mut deps, "admin", "ftp://image"));
+    assert!(error.contains("Image URI must start with"));
+    set_info(&mut deps, "admin", "ipfs://image").unwrap();
+
+    let query_answer: QueryAnswer =
+        from_binary(&query(&deps, QueryMsg::GumballInfo {}).unwrap()).unwrap();
+    match query_answer {
+        QueryAnswer::GumballInfo {
+            name,
+            description,
+            image_uri,
+        } => {
+            assert_eq!(name, "Gumball".to_string());
+            assert_eq!(description, Some("A gumball".to_string()));
+            assert_eq!(image_uri, Some("ipfs://image".to_string()));
+        }
+        _ => panic!("unexpected"),
+    }
+    let query_answer: QueryAnswer =
+        from_binary(&query(&deps, QueryMsg::NftListingDisplay {}).unwrap()).unwrap();
+    match query_answer {
+        QueryAnswer::NftListingDisplay { name, .. } => {
+            assert_eq!(name, Some("Gumball".to_string()))
+        }
+        _ => panic!("unexpected"),
+    }
+}
+
+#[test]
+fn test_status() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2"]).unwrap();
+    mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+    let query_answer: QueryAnswer =
+        from_binary(&query(&deps, QueryMsg::Status {}).unwrap()).unwrap();
+    match query_answer {
+        QueryAnswer::Status {
+            available,
+            nft_contract_address,
+            paused,
+            pool_sealed,
+            whitelist_only,
+            mintable,
+            ..
+        } => {
+            assert_eq!(available, 1);
+            assert_eq!(nft_contract_address, HumanAddr("nftcontract".to_string()));
+            assert!(!paused && !pool_sealed && !whitelist_only);
+            assert!(mintable);
+        }
+        _ => panic!("unexpected"),
+    }
+}
+
+#[test]
+fn test_tokens_in_range() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetViewingKey {
+            key: "key".to_string(),
+            padding: None,
+        },
+    )
+    .unwrap();
+    // the end index is clamped to the pool size
+    let query_answer: QueryAnswer = from_binary(
+        &query(
+            &deps,
+            QueryMsg::TokensInRange {
+                start_index: 1,
+                end_index: 10,
+                viewer: Some(ViewerInfo {
+                    address: HumanAddr("admin".to_string()),
+                    viewing_key: "key".to_string(),
+                }),
+                permit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    match query_answer {
+        QueryAnswer::TokensInRange { tokens } => assert_eq!(
+            tokens,
+            vec![(1, "NFT2".to_string()), (2, "NFT3".to_string())]
+        ),
+        _ => panic!("unexpected"),
+    }
+}
+
+#[test]
+fn test_multiple_factories() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2"]).unwrap();
+    let create_msg = |factory: &str| HandleMsg::CreateListing {
+        label: format!("{} listing", factory),
+        payment_address: None,
+        factory_contract: ContractInfo {
+            code_hash: "factoryhash".to_string(),
+            address: HumanAddr(factory.to_string()),
+        },
+        buy_contract: ContractInfo {
+            code_hash: "sscrthash".to_string(),
+            address: HumanAddr("sscrt".to_string()),
+        },
+        batch_send: true,
+        price: Uint128(1000000),
+        closes_at: 2000000000,
+        description: None,
+        entropy: "listing entropy".to_string(),
+    };
+    let register =
+        |deps: &mut Extern<MockStorage, MockApi, MockQuerier>, factory: &str, listing: &str| {
+            handle(
+                deps,
+                mock_env_sender(factory),
+                HandleMsg::RegisterListing {
+                    listing_address: HumanAddr(listing.to_string()),
+                },
+            )
+        };
+
+    // a second CreateListing does not overwrite the first factory's pending registration
+    handle(&mut deps, mock_env_sender("admin"), create_msg("factory1")).unwrap();
+    handle(&mut deps, mock_env_sender("admin"), create_msg("factory2")).unwrap();
+    register(&mut deps, "factory2", "listing2").unwrap();
+    register(&mut deps, "factory1", "listing1").unwrap();
+    // each pending registration may only be used once
+    let error = extract_error_msg(register(&mut deps, "factory1", "listing3"));
+    assert!(error.contains("RegisterListing can only be called by an expected factory"));
+    mint_helper(&mut deps, "listing1", &["alice"]).unwrap();
+    mint_helper(&mut deps, "listing2", &["bob"]).unwrap();
+
+    // test a non-admin approving a factory
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::AddApprovedFactory {
+            factory: HumanAddr("factory3".to_string()),
+        },
+    ));
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddApprovedFactory {
+            factory: HumanAddr("factory3".to_string()),
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::RemoveApprovedFactory {
+            factory: HumanAddr("factory3".to_string()),
+        },
+    )
+    .unwrap();
+    let error = extract_error_msg(register(&mut deps, "factory3", "listing3"));
+    assert!(error.contains("RegisterListing can only be called by an expected factory"));
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddApprovedFactory {
+            factory: HumanAddr("factory3".to_string()),
+        },
+    )
+    .unwrap();
+    register(&mut deps, "factory3", "listing3").unwrap();
+}
+
+#[test]
+fn test_split_transfer_messages() {
+    let mut deps = init_helper();
+    receive_helper(
+        &mut deps,
+        "admin",
+        &["NFT1", "NFT2", "NFT3", "NFT4", "NFT5"],
+    )
+    .unwrap();
+
+    // test a non-admin setting the batch size
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::SetMaxTransferBatchSize { size: 2 },
+    ));
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetMaxTransferBatchSize { size: 0 },
+    ));
+    assert!(error.contains("Transfer batch size must be at least 1"));
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetMaxTransferBatchSize { size: 2 },
+    )
+    .unwrap();
+
+    let resp = mint_helper(
+        &mut deps,
+        "admin",
+        &["alice", "bob", "charlie", "dave", "edmund"],
+    )
+    .unwrap();
+    assert_eq!(resp.messages.len(), 3);
+    assert_eq!(distributed_helper(&resp).len(), 5);
+}
+
+#[test]
+fn test_bridge_mode() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetBridgeMode {
+            enabled: true,
+            bridge_contract: ContractInfo {
+                code_hash: "bridgehash".to_string(),
+                address: HumanAddr("bridge".to_string()),
+            },
+        },
+    )
+    .unwrap();
+
+    // the collection sends the token to the bridge along with its recipient
+    let resp = mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+    assert_eq!(resp.messages.len(), 1);
+    let bridge_msg = to_binary(&BridgeMsg::BridgeMint {
+        recipient: HumanAddr("alice".to_string()),
+    })
+    .unwrap();
+    match &resp.messages[0] {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }) => {
+            assert_eq!(contract_addr, &HumanAddr("nftcontract".to_string()));
+            let sent = String::from_utf8(msg.0.clone()).unwrap();
+            assert!(sent.contains("\"batch_send_nft\""));
+            assert!(sent.contains("\"contract\":\"bridge\""));
+            assert!(sent.contains("\"token_ids\":[\"NFT1\"]"));
+            assert!(sent.contains(&bridge_msg.to_base64()));
+        }
+        _ => panic!("unexpected"),
+    }
+}
+
+#[test]
+fn test_mint_log() {
+    let mut deps = init_helper();
+    let token_ids: Vec<String> = (0..22).map(|i| format!("NFT{}", i)).collect();
+    let buyers: Vec<String> = (0..22).map(|i| format!("buyer{}", i)).collect();
+    receive_helper(
+        &mut deps,
+        "admin",
+        &token_ids.iter().map(|t| t.as_str()).collect::<Vec<&str>>(),
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetViewingKey {
+            key: "key".to_string(),
+            padding: None,
+        },
+    )
+    .unwrap();
+    let buyer_refs: Vec<&str> = buyers.iter().map(|b| b.as_str()).collect();
+    mint_helper(&mut deps, "admin", &buyer_refs[..2]).unwrap();
+    mint_helper(&mut deps, "admin", &buyer_refs[2..]).unwrap();
+
+    let query_answer: QueryAnswer = from_binary(
+        &query(
+            &deps,
+            QueryMsg::MintLog {
+                viewer: Some(ViewerInfo {
+                    address: HumanAddr("admin".to_string()),
+                    viewing_key: "key".to_string(),
+                }),
+                permit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    match query_answer {
+        QueryAnswer::MintLog { events } => {
+            // only the 20 most recent mints are kept, newest first
+            assert_eq!(events.len(), 20);
+            assert_eq!(events[0].recipient, HumanAddr("buyer21".to_string()));
+            assert_eq!(events[19].recipient, HumanAddr("buyer2".to_string()));
+            assert_eq!(events[0].caller_type, 1);
+        }
+        _ => panic!("unexpected"),
+    }
+}
+
+#[test]
+fn test_token_at_index() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetViewingKey {
+            key: "key".to_string(),
+            padding: None,
+        },
+    )
+    .unwrap();
+    let token_at = |deps: &Extern<MockStorage, MockApi, MockQuerier>, index: u32| {
+        query(
+            deps,
+            QueryMsg::TokenAtIndex {
+                index,
+                viewer: Some(ViewerInfo {
+                    address: HumanAddr("admin".to_string()),
+                    viewing_key: "key".to_string(),
+                }),
+                permit: None,
+            },
+        )
+    };
+    let query_answer: QueryAnswer = from_binary(&token_at(&deps, 1).unwrap()).unwrap();
+    match query_answer {
+        QueryAnswer::TokenAtIndex { index, token_id } => {
+            assert_eq!(index, 1);
+            assert_eq!(token_id, Some("NFT2".to_string()));
+        }
+        _ => panic!("unexpected"),
+    }
+    // test an index past the end of the pool
+    let error = extract_error_msg(token_at(&deps, 2));
+    assert!(error.contains("Index 2 is out of range"));
+}
+
+#[test]
+fn test_contribute_entropy() {
+    let mut deps = init_helper();
+    let initial: Vec<u8> = load(&deps.storage, ENTROPY_POOL_KEY).unwrap();
+    let seed: Vec<u8> = load(&deps.storage, PRNG_SEED_KEY).unwrap();
+    assert_eq!(initial, seed);
+    // any caller can contribute
+    let handle_result = handle(
+        &mut deps,
+        mock_env_sender("anyone"),
+        HandleMsg::ContributeEntropy {
+            entropy: "noise".to_string(),
+        },
+    );
+    assert!(handle_result.is_ok());
+    let pool: Vec<u8> = load(&deps.storage, ENTROPY_POOL_KEY).unwrap();
+    assert_ne!(pool, initial);
+    assert_eq!(
+        load::<Vec<u8>, _>(&deps.storage, PRNG_SEED_KEY).unwrap(),
+        seed
+    );
+}
+
+#[test]
+fn test_recall_tokens() {
+    let mut deps = init_helper();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddAdmins {
+            admins: vec![HumanAddr("alice".to_string())],
+        },
+    )
+    .unwrap();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2"]).unwrap();
+    receive_helper(&mut deps, "alice", &["NFT3"]).unwrap();
+    let recall_msg = || HandleMsg::RecallTokens {
+        token_ids: vec!["NFT1".to_string(), "NFT3".to_string()],
+    };
+
+    // test a non-admin recalling tokens
+    assert_unauthorized(handle(&mut deps, mock_env_sender("bob"), recall_msg()));
+    // test recalling a token deposited by someone else
+    let error = extract_error_msg(handle(&mut deps, mock_env_sender("admin"), recall_msg()));
+    assert!(error.contains("Token NFT3 can only be recalled by its depositor"));
+    assert_eq!(counts_helper(&deps).0, 3);
+
+    // NFT1 is swapped out, so NFT3 moves into its index and keeps its depositor
+    let resp = handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::RecallTokens {
+            token_ids: vec!["NFT1".to_string()],
+        },
+    )
+    .unwrap();
+    assert_eq!(resp.messages.len(), 1);
+    let resp = handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::RecallTokens {
+            token_ids: vec!["NFT3".to_string()],
+        },
+    )
+    .unwrap();
+    let handle_answer: HandleAnswer = from_binary(&resp.data.unwrap()).unwrap();
+    match handle_answer {
+        HandleAnswer::RecallTokens { recalled } => {
+            assert_eq!(recalled, vec!["NFT3".to_string()]);
+        }
+        _ => panic!("unexpected"),
+    }
+    assert_eq!(counts_helper(&deps).0, 1);
+
+    // recalls honor the transfer batch size and emptying the pool drops the examples
+    receive_helper(&mut deps, "admin", &["NFT4", "NFT5"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetMaxTransferBatchSize { size: 2 },
+    )
+    .unwrap();
+    let resp = handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::RecallTokens {
+            token_ids: vec!["NFT2".to_string(), "NFT4".to_string(), "NFT5".to_string()],
+        },
+    )
+    .unwrap();
+    assert_eq!(resp.messages.len(), 2);
+    assert_eq!(counts_helper(&deps).0, 0);
+    assert!(
+        may_load::<Vec<StoredNftDossierForListing>, _>(&deps.storage, EXAMPLE_KEY)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn test_whitelist_expiry() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3", "NFT4"]).unwrap();
+    let now = mock_env_sender("admin").block.time;
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddToWhitelist {
+            addresses: vec![HumanAddr("alice".to_string())],
+            quantity: Some(2),
+            round_id: None,
+            expires_at: Some(now + 10),
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddToWhitelist {
+            addresses: vec![HumanAddr("bob".to_string())],
+            quantity: None,
+            round_id: None,
+            expires_at: None,
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetViewingKey {
+            key: "akey".to_string(),
+            padding: None,
+        },
+    )
+    .unwrap();
+
+    // queries report the expiry since they can not see the block time
+    let query_answer: QueryAnswer = from_binary(
+        &query(
+            &deps,
+            QueryMsg::IsWhitelisted {
+                address: HumanAddr("alice".to_string()),
+                viewer: Some(ViewerInfo {
+                    address: HumanAddr("admin".to_string()),
+                    viewing_key: "akey".to_string(),
+                }),
+                permit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    match query_answer {
+        QueryAnswer::IsWhitelisted {
+            whitelisted,
+            remaining_quantity,
+            expires_at,
+        } => {
+            assert!(whitelisted);
+            assert_eq!(remaining_quantity, 2);
+            assert_eq!(expires_at, Some(now + 10));
+        }
+        _ => panic!("unexpected"),
+    }
+    let query_answer: QueryAnswer = from_binary(
+        &query(
+            &deps,
+            QueryMsg::WhitelistSnapshot {
+                page: 0,
+                page_size: 10,
+                viewer: Some(ViewerInfo {
+                    address: HumanAddr("admin".to_string()),
+                    viewing_key: "akey".to_string(),
+                }),
+                permit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    match query_answer {
+        QueryAnswer::WhitelistSnapshot { entries, total } => {
+            assert_eq!(total, 2);
+            let mut listed: Vec<(String, Option<u64>)> = entries
+                .into_iter()
+                .map(|e| (e.address.to_string(), e.expires_at))
+                .collect();
+            listed.sort();
+            assert_eq!(
+                listed,
+                vec![
+                    ("alice".to_string(), Some(now + 10)),
+                    ("bob".to_string(), None)
+                ]
+            );
+        }
+        _ => panic!("unexpected"),
+    }
+
+    // the entry is usable before it expires and keeps its expiry
+    let resp = mint_helper(&mut deps, "alice", &["alice"]).unwrap();
+    assert_eq!(distributed_helper(&resp).len(), 1);
+    let mut later = mock_env_sender("alice");
+    later.block.time = now + 10;
+    let error = extract_error_msg(handle(
+        &mut deps,
+        later,
+        HandleMsg::Mint {
+            buyers: vec![HumanAddr("alice".to_string())],
+            entropy: "entropy".to_string(),
+            referrer: None,
+        },
+    ));
+    assert!(error.contains("Whitelist entry has expired"));
+
+    // test a non-admin pruning the whitelist
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::RemoveExpired {},
+    ));
+    let mut later = mock_env_sender("admin");
+    later.block.time = now + 10;
+    let resp = handle(&mut deps, later, HandleMsg::RemoveExpired {}).unwrap();
+    let handle_answer: HandleAnswer = from_binary(&resp.data.unwrap()).unwrap();
+    match handle_answer {
+        HandleAnswer::RemoveExpired { removed } => assert_eq!(removed, 1),
+        _ => panic!("unexpected"),
+    }
+    assert_unauthorized(mint_helper(&mut deps, "alice", &["alice"]));
+    let resp = mint_helper(&mut deps, "bob", &["bob"]).unwrap();
+    assert_eq!(distributed_helper(&resp).len(), 1);
+}
+
+#[test]
+fn test_clear_pool() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddAdmins {
+            admins: vec![HumanAddr("alice".to_string())],
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetMaxTransferBatchSize { size: 2 },
+    )
+    .unwrap();
+    let propose = |deps: &mut Extern<MockStorage, MockApi, MockQuerier>, env: Env| {
+        let resp = handle(
+            deps,
+            env,
+            HandleMsg::ProposeClearPool {
+                recipient: HumanAddr("vault".to_string()),
+            },
+        )?;
+        match from_binary(&resp.data.unwrap())? {
+            HandleAnswer::ProposeClearPool { proposal_hash, .. } => Ok(proposal_hash),
+            _ => panic!("unexpected"),
+        }
+    };
+    let confirm_msg = |proposal_hash: &str| HandleMsg::ConfirmClearPool {
+        proposal_hash: proposal_hash.to_string(),
+    };
+
+    // test a non-admin proposing to clear the pool
+    assert_unauthorized(propose(&mut deps, mock_env_sender("bob")));
+    // test confirming without a proposal
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        confirm_msg("hash"),
+    ));
+    assert!(error.contains("There is no pending proposal to clear the pool"));
+
+    let proposal_hash = propose(&mut deps, mock_env_sender("admin")).unwrap();
+    // test a non-admin confirming
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("bob"),
+        confirm_msg(&proposal_hash),
+    ));
+    // test the wrong hash
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        confirm_msg("hash"),
+    ));
+    assert!(error.contains("Proposal hash does not match the pending proposal"));
+    // test confirming after the proposal expires
+    let mut later = mock_env_sender("alice");
+    later.block.height += 51;
+    let error = extract_error_msg(handle(&mut deps, later, confirm_msg(&proposal_hash)));
+    assert!(error.contains("has expired and must be re-submitted"));
+    assert_eq!(counts_helper(&deps).0, 3);
+
+    // a different admin may confirm a fresh proposal
+    let mut later = mock_env_sender("admin");
+    later.block.height += 51;
+    let proposal_hash = propose(&mut deps, later.clone()).unwrap();
+    later.message.sender = HumanAddr("alice".to_string());
+    let resp = handle(&mut deps, later, confirm_msg(&proposal_hash)).unwrap();
+    // the tokens are split between transfer messages
+    assert_eq!(resp.messages.len(), 2);
+    let handle_answer: HandleAnswer = from_binary(&resp.data.unwrap()).unwrap();
+    match handle_answer {
+        HandleAnswer::ConfirmClearPool { cleared } => assert_eq!(cleared, 3),
+        _ => panic!("unexpected"),
+    }
+    assert_eq!(counts_helper(&deps).0, 0);
+    let cleared: bool = load(&deps.storage, POOL_CLEARED_KEY).unwrap();
+    assert!(cleared);
+    // the proposal can not be confirmed twice
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        confirm_msg(&proposal_hash),
+    ));
+    assert!(error.contains("E073: "));
+
+    // the cleared tokens can be deposited again
+    receive_helper(&mut deps, "admin", &["NFT1"]).unwrap();
+    assert_eq!(counts_helper(&deps).0, 1);
+}
+
+#[test]
+fn test_update_collection() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1"]).unwrap();
+    let update_msg = || HandleMsg::UpdateCollection {
+        new_nft_contract: ContractInfo {
+            code_hash: "newhash".to_string(),
+            address: HumanAddr("newcontract".to_string()),
+        },
+    };
+
+    // test a non-admin changing the collection
+    assert_unauthorized(handle(&mut deps, mock_env_sender("alice"), update_msg()));
+    // test changing the collection while the pool holds tokens
+    let error = extract_error_msg(handle(&mut deps, mock_env_sender("admin"), update_msg()));
+    assert!(error.contains("Pool must be empty before updating the collection contract"));
+
+    // test changing the collection while a token is reserved
+    let env = mock_env_sender("admin");
+    let expires_at = env.block.time + 10;
+    handle(
+        &mut deps,
+        env.clone(),
+        HandleMsg::ReserveToken {
+            token_id: "NFT1".to_string(),
+            recipient: HumanAddr("alice".to_string()),
+            expires_at: Some(expires_at),
+        },
+    )
+    .unwrap();
+    let error = extract_error_msg(handle(&mut deps, mock_env_sender("admin"), update_msg()));
+    assert!(error.contains("E078: 1 reserved tokens"));
+    // an expired reservation still holds a token of the current collection until it is cancelled
+    let mut later = env;
+    later.block.time = expires_at;
+    let error = extract_error_msg(handle(&mut deps, later.clone(), update_msg()));
+    assert!(error.contains("E078: 1 reserved tokens"));
+    handle(
+        &mut deps,
+        later.clone(),
+        HandleMsg::CancelReservations {
+            recipients: vec![HumanAddr("alice".to_string())],
+        },
+    )
+    .unwrap();
+    let error = extract_error_msg(handle(&mut deps, later.clone(), update_msg()));
+    assert!(error.contains("Pool must be empty before updating the collection contract"));
+    handle(
+        &mut deps,
+        later,
+        HandleMsg::RemoveTokens {
+            token_ids: vec!["NFT1".to_string()],
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetExampleQueryViewingKey {
+            entropy: "example key entropy".to_string(),
+        },
+    )
+    .unwrap();
+    assert!(
+        may_load::<Vec<StoredNftDossierForListing>, _>(&deps.storage, EXAMPLE_KEY)
+            .unwrap()
+            .is_some()
+    );
+    let resp = handle(&mut deps, mock_env_sender("admin"), update_msg()).unwrap();
+    // registers to receive the new collection's tokens and sets the example query key with it
+    assert_eq!(resp.messages.len(), 2);
+    for msg in resp.messages.iter() {
+        match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => {
+                assert_eq!(contract_addr, &HumanAddr("newcontract".to_string()));
+            }
+            _ => panic!("unexpected"),
+        }
+    }
+    assert_eq!(resp.log[0].value.trim(), "newcontract");
+    // the old collection's examples are gone
+    assert!(
+        may_load::<Vec<StoredNftDossierForListing>, _>(&deps.storage, EXAMPLE_KEY)
+            .unwrap()
+            .is_none()
+    );
+
+    // only the new collection can deposit
+    let error = extract_error_msg(receive_helper(&mut deps, "admin", &["NFT2"]));
+    assert!(error.contains("E013: "));
+    let resp = handle(
+        &mut deps,
+        mock_env_sender("newcontract"),
+        HandleMsg::BatchReceiveNft {
+            from: HumanAddr("admin".to_string()),
+            token_ids: vec!["NFT2".to_string()],
+            msg: None,
+        },
+    );
+    assert!(resp.is_ok());
+    assert_eq!(counts_helper(&deps).0, 1);
+}
+
+#[test]
+fn test_price_bounds() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1"]).unwrap();
+    let create_msg = |price: u128| HandleMsg::CreateListing {
+        label: "gumball".to_string(),
+        payment_address: None,
+        factory_contract: ContractInfo {
+            code_hash: "factoryhash".to_string(),
+            address: HumanAddr("factory".to_string()),
+        },
+        buy_contract: ContractInfo {
+            code_hash: "sscrthash".to_string(),
+            address: HumanAddr("sscrt".to_string()),
+        },
+        batch_send: true,
+        price: Uint128(price),
+        closes_at: 2000000000,
+        description: None,
+        entropy: "listing entropy".to_string(),
+    };
+    let bounds_msg = |min: Option<u128>, max: Option<u128>| HandleMsg::SetPriceBounds {
+        min: min.map(Uint128),
+        max: max.map(Uint128),
+    };
+
+    // test a non-admin setting the bounds
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        bounds_msg(Some(10), None),
+    ));
+    // test a minimum above the maximum
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        bounds_msg(Some(100), Some(10)),
+    ));
+    assert!(error.contains("E071: "));
+
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        bounds_msg(Some(10), Some(100)),
+    )
+    .unwrap();
+    let error = extract_error_msg(handle(&mut deps, mock_env_sender("admin"), create_msg(0)));
+    assert!(error.contains("Listing price is below the minimum of 10"));
+    let error = extract_error_msg(handle(&mut deps, mock_env_sender("admin"), create_msg(101)));
+    assert!(error.contains("Listing price exceeds the maximum of 100"));
+    assert!(handle(&mut deps, mock_env_sender("admin"), create_msg(100)).is_ok());
+
+    // removing the bounds allows any price
+    handle(&mut deps, mock_env_sender("admin"), bounds_msg(None, None)).unwrap();
+    assert!(handle(&mut deps, mock_env_sender("admin"), create_msg(0)).is_ok());
+}
+
+#[test]
+fn test_init_listing_fee() {
+    // test a fee without a denomination
+    let mut deps = mock_deps();
+    let mut init_msg = mock_init_msg("nftcontract", "nfthash");
+    init_msg.listing_fee = Some(Uint128(100));
+    let init_result = init(&mut deps, mock_env_sender("admin"), init_msg);
+    let error = match init_result {
+        Err(StdError::GenericErr { msg, .. }) => msg,
+        _ => panic!("unexpected"),
+    };
+    assert!(error.contains("E072: "));
+
+    let mut deps = mock_deps();
+    let mut init_msg = mock_init_msg("nftcontract", "nfthash");
+    init_msg.listing_fee = Some(Uint128(100));
+    init_msg.listing_fee_denom = Some("uscrt".to_string());
+    init(&mut deps, mock_env_sender("admin"), init_msg).unwrap();
+    receive_helper(&mut deps, "admin", &["NFT1"]).unwrap();
+    let create_msg = || HandleMsg::CreateListing {
+        label: "gumball".to_string(),
+        payment_address: None,
+        factory_contract: ContractInfo {
+            code_hash: "factoryhash".to_string(),
+            address: HumanAddr("factory".to_string()),
+        },
+        buy_contract: ContractInfo {
+            code_hash: "sscrthash".to_string(),
+            address: HumanAddr("sscrt".to_string()),
+        },
+        batch_send: true,
+        price: Uint128(1000000),
+        closes_at: 2000000000,
+        description: None,
+        entropy: "listing entropy".to_string(),
+    };
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env("admin", &coins(99, "uscrt")),
+        create_msg(),
+    ));
+    assert!(error.contains("Insufficient listing creation fee"));
+    // test overpaying the fee
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env("admin", &coins(101, "uscrt")),
+        create_msg(),
+    ));
+    assert!(error.contains("E077: "));
+    let mut funds = coins(100, "uscrt");
+    funds.extend(coins(5, "uatom"));
+    let error = extract_error_msg(handle(&mut deps, mock_env("admin", &funds), create_msg()));
+    assert!(error.contains("E077: "));
+
+    // the fee is sent to the instantiator by default
+    let resp = handle(
+        &mut deps,
+        mock_env("admin", &coins(100, "uscrt")),
+        create_msg(),
+    )
+    .unwrap();
+    assert_eq!(resp.messages.len(), 2);
+    match &resp.messages[0] {
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address, amount, ..
+        }) => {
+            assert_eq!(to_address, &HumanAddr("admin".to_string()));
+            assert_eq!(amount, &coins(100, "uscrt"));
+        }
+        _ => panic!("unexpected"),
+    }
+}
+
+#[test]
+fn test_token_metadata() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2"]).unwrap();
+    // the mock querier can not answer dossier queries, so cache the info directly
+    let mut meta_store = PrefixedStorage::new(PREFIX_TOKEN_METADATA, &mut deps.storage);
+    for id in ["NFT1", "NFT2"].iter() {
+        save(
+            &mut meta_store,
+            id.as_bytes(),
+            &StoredNftDossierForListing {
+                public_metadata: None,
+                royalty_info: None,
+                mint_run_info: None,
+            },
+        )
+        .unwrap();
+    }
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetViewingKey {
+            key: "key".to_string(),
+            padding: None,
+        },
+    )
+    .unwrap();
+    let cached = |deps: &Extern<MockStorage, MockApi, MockQuerier>, token_id: &str| {
+        let query_answer: QueryAnswer = from_binary(
+            &query(
+                deps,
+                QueryMsg::TokenMetadata {
+                    token_id: token_id.to_string(),
+                    viewer: Some(ViewerInfo {
+                        address: HumanAddr("admin".to_string()),
+                        viewing_key: "key".to_string(),
+                    }),
+                    permit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        match query_answer {
+            QueryAnswer::TokenMetadata { nft_info, .. } => nft_info.is_some(),
+            _ => panic!("unexpected"),
+        }
+    };
+    assert!(cached(&deps, "NFT1"));
+
+    // removing a token deletes its cached info
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::RemoveTokens {
+            token_ids: vec!["NFT1".to_string()],
+        },
+    )
+    .unwrap();
+    assert!(!cached(&deps, "NFT1"));
+    assert!(cached(&deps, "NFT2"));
+}
+
+#[test]
+fn test_trait_aggregates() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3", "NFT4"]).unwrap();
+    let trait_of = |trait_type: &str, value: &str| Trait {
+        trait_type: Some(trait_type.to_string()),
+        value: value.to_string(),
+        ..Trait::default()
+    };
+    // NFT4 has no cached info
+    let attributes = vec![
+        (
+            "NFT1",
+            vec![trait_of("Eyes", "Red"), trait_of("Hat", "Cap")],
+        ),
+        ("NFT2", vec![trait_of("Eyes", "Blue")]),
+        ("NFT3", vec![trait_of("Eyes", "Red")]),
+    ];
+    let mut meta_store = PrefixedStorage::new(PREFIX_TOKEN_METADATA, &mut deps.storage);
+    for (id, attrs) in attributes.into_iter() {
+        save(
+            &mut meta_store,
+            id.as_bytes(),
+            &StoredNftDossierForListing {
+                public_metadata: Some(Metadata {
+                    token_uri: None,
+                    extension: Some(Extension {
+                        attributes: Some(attrs),
+                        ..Extension::default()
+                    }),
+                }),
+                royalty_info: None,
+                mint_run_info: None,
+            },
+        )
+        .unwrap();
+    }
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetViewingKey {
+            key: "key".to_string(),
+            padding: None,
+        },
+    )
+    .unwrap();
+    let aggregates = |deps: &Extern<MockStorage, MockApi, MockQuerier>, trait_type: &str| {
+        let query_answer: QueryAnswer = from_binary(
+            &query(
+                deps,
+                QueryMsg::TraitAggregates {
+                    trait_type: trait_type.to_string(),
+                    viewer: Some(ViewerInfo {
+                        address: HumanAddr("admin".to_string()),
+                        viewing_key: "key".to_string(),
+                    }),
+                    permit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        match query_answer {
+            QueryAnswer::TraitAggregates {
+                value_counts,
+                tokens_with_trait,
+                total_tokens,
+                ..
+            } => (value_counts, tokens_with_trait, total_tokens),
+            _ => panic!("unexpected"),
+        }
+    };
+    let (value_counts, tokens_with_trait, total_tokens) = aggregates(&deps, "Eyes");
+    assert_eq!(
+        value_counts,
+        vec![
+            TraitCount {
+                value: "Blue".to_string(),
+                count: 1,
+            },
+            TraitCount {
+                value: "Red".to_string(),
+                count: 2,
+            },
+        ]
+    );
+    assert_eq!(tokens_with_trait, 3);
+    assert_eq!(total_tokens, 3);
+    let (value_counts, tokens_with_trait, _) = aggregates(&deps, "Hat");
+    assert_eq!(value_counts.len(), 1);
+    assert_eq!(tokens_with_trait, 1);
+}
+
+#[test]
+fn test_clear_whitelist() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddToWhitelist {
+            addresses: vec![
+                HumanAddr("alice".to_string()),
+                HumanAddr("bob".to_string()),
+                HumanAddr("charlie".to_string()),
+            ],
+            quantity: None,
+            round_id: None,
+            expires_at: None,
+        },
+    )
+    .unwrap();
+    let cleared = |deps: &mut Extern<MockStorage, MockApi, MockQuerier>, max: Option<u32>| {
+        let resp = handle(
+            deps,
+            mock_env_sender("admin"),
+            HandleMsg::ClearWhitelist { max_entries: max },
+        )
+        .unwrap();
+        match from_binary(&resp.data.unwrap()).unwrap() {
+            HandleAnswer::ClearWhitelist { cleared_count } => cleared_count,
+            _ => panic!("unexpected"),
+        }
+    };
+
+    // test a non-admin clearing the whitelist
+    assert_unauthorized(handle(
+        &mut deps,
+        mock_env_sender("alice"),
+        HandleMsg::ClearWhitelist { max_entries: None },
+    ));
+
+    // clearing in batches removes the most recent entries first
+    assert_eq!(cleared(&mut deps, Some(2)), 2);
+    assert_unauthorized(mint_helper(&mut deps, "charlie", &["charlie"]));
+    let resp = mint_helper(&mut deps, "alice", &["alice"]).unwrap();
+    assert_eq!(distributed_helper(&resp).len(), 1);
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddToWhitelist {
+            addresses: vec![HumanAddr("bob".to_string())],
+            quantity: None,
+            round_id: None,
+            expires_at: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(cleared(&mut deps, None), 1);
+    assert_eq!(cleared(&mut deps, None), 0);
+    assert_unauthorized(mint_helper(&mut deps, "bob", &["bob"]));
+}
+
+#[test]
+fn test_example_query_viewing_key() {
+    let mut deps = init_helper();
+    let key_msg = || HandleMsg::SetExampleQueryViewingKey {
+        entropy: "example key entropy".to_string(),
+    };
+
+    // test a non-admin setting the key
+    assert_unauthorized(handle(&mut deps, mock_env_sender("alice"), key_msg()));
+
+    let resp = handle(&mut deps, mock_env_sender("admin"), key_msg()).unwrap();
+    // the generated key is registered with the collection but never returned
+    let key: String = load(&deps.storage, EXAMPLE_QUERY_VK_KEY).unwrap();
+    assert_eq!(resp.messages.len(), 1);
+    let handle_answer: HandleAnswer = from_binary(&resp.data.unwrap()).unwrap();
+    match handle_answer {
+        HandleAnswer::SetExampleQueryViewingKey { status } => assert_eq!(status, "success"),
+        _ => panic!("unexpected"),
+    }
+    assert!(resp.log.iter().all(|l| !l.value.contains(&key)));
+}
+
+#[test]
+fn test_draw_failed_refund() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddToWhitelist {
+            addresses: vec![HumanAddr("alice".to_string())],
+            quantity: None,
+            round_id: None,
+            expires_at: None,
+        },
+    )
+    .unwrap();
+    for id in ["NFT1", "NFT2"].iter() {
+        handle(
+            &mut deps,
+            mock_env_sender("admin"),
+            HandleMsg::BlacklistToken {
+                token_id: id.to_string(),
+            },
+        )
+        .unwrap();
+    }
+
+    // every draw hits a blacklisted token, so the buyer is skipped
+    let resp = mint_helper(&mut deps, "alice", &["alice"]).unwrap();
+    assert!(resp.messages.is_empty());
+    assert!(distributed_helper(&resp).is_empty());
+    let skipped = resp
+        .log
+        .iter()
+        .find(|l| l.key.trim_end() == "skipped")
+        .unwrap();
+    assert!(skipped.value.contains("alice"));
+    assert_eq!(counts_helper(&deps).0, 2);
+
+    // a listing buyer already paid, so the purchase reverts instead
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::ForwardListingRegistration {
+            listing_address: HumanAddr("listing".to_string()),
+            factory: None,
+        },
+    )
+    .unwrap();
+    let error = extract_error_msg(mint_helper(&mut deps, "listing", &["bob"]));
+    assert!(error.contains("E076: bob"));
+
+    // the whitelist mint was refunded
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::UnblacklistToken {
+            token_id: "NFT1".to_string(),
+        },
+    )
+    .unwrap();
+    let resp = mint_helper(&mut deps, "alice", &["alice"]).unwrap();
+    assert_eq!(distributed_helper(&resp), vec!["NFT1"]);
+}
+
+#[test]
+fn test_prng_audit_ring_buffer() {
+    let mut deps = init_helper();
+    let ids: Vec<String> = (0..102).map(|i| format!("NFT{}", i)).collect();
+    handle(
+        &mut deps,
+        mock_env_sender("nftcontract"),
+        HandleMsg::BatchReceiveNft {
+            from: HumanAddr("admin".to_string()),
+            token_ids: ids,
+            msg: None,
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetViewingKey {
+            key: "akey".to_string(),
+            padding: None,
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetPrngAuditEnabled { enabled: true },
+    )
+    .unwrap();
+    for _ in 0..102 {
+        mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+    }
+    let audit = |deps: &Extern<MockStorage, MockApi, MockQuerier>, start: u64| {
+        let query_answer: QueryAnswer = from_binary(
+            &query(
+                deps,
+                QueryMsg::PrngAuditLog {
+                    start,
+                    limit: 10,
+                    viewer: Some(ViewerInfo {
+                        address: HumanAddr("admin".to_string()),
+                        viewing_key: "akey".to_string(),
+                    }),
+                    permit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        match query_answer {
+            QueryAnswer::PrngAuditLog { entries, total } => (entries.len(), total),
+            _ => panic!("unexpected"),
+        }
+    };
+
+    // the two oldest entries were overwritten, so they are skipped
+    assert_eq!(audit(&deps, 0), (10, 102));
+    assert_eq!(audit(&deps, 95), (7, 102));
+}
+
+#[test]
+fn test_stats_level() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2", "NFT3", "NFT4"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::AddApprovedFactory {
+            factory: HumanAddr("factory".to_string()),
+        },
+    )
+    .unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("factory"),
+        HandleMsg::RegisterListing {
+            listing_address: HumanAddr("listing".to_string()),
+        },
+    )
+    .unwrap();
+    let stats_helper = |deps: &Extern<MockStorage, MockApi, MockQuerier>| {
+        let query_answer: QueryAnswer =
+            from_binary(&query(deps, QueryMsg::Counts {}).unwrap()).unwrap();
+        match query_answer {
+            QueryAnswer::Counts { mint_stats, .. } => mint_stats,
+            _ => panic!("unexpected"),
+        }
+    };
+    let addr_count = |deps: &Extern<MockStorage, MockApi, MockQuerier>, addr: &str| {
+        let raw = deps
+            .api
+            .canonical_address(&HumanAddr(addr.to_string()))
+            .unwrap();
+        may_load::<u32, _>(
+            &ReadonlyPrefixedStorage::new(PREFIX_ADDR_MINT_COUNT, &deps.storage),
+            raw.as_slice(),
+        )
+        .unwrap()
+    };
+    let listing_count = |deps: &Extern<MockStorage, MockApi, MockQuerier>| {
+        let raw = deps
+            .api
+            .canonical_address(&HumanAddr("listing".to_string()))
+            .unwrap();
+        may_load::<u64, _>(
+            &ReadonlyPrefixedStorage::new(PREFIX_LISTING_MINT_COUNT, &deps.storage),
+            raw.as_slice(),
+        )
+        .unwrap()
+    };
+
+    // the released count is kept even without statistics, but nothing else is written
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetStatsResolution {
+            level: StatsLevel::None,
+        },
+    )
+    .unwrap();
+    mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+    let resp = mint_helper(&mut deps, "listing", &["bob"]).unwrap();
+    assert!(!resp
+        .log
+        .iter()
+        .any(|l| l.key.trim_end() == "listing_mint_count"));
+    assert_eq!(counts_helper(&deps), (2, 2));
+    assert!(stats_helper(&deps).is_none());
+    assert!(addr_count(&deps, "alice").is_none());
+    assert!(listing_count(&deps).is_none());
+    assert!(may_load::<u64, _>(&deps.storage, MINTS_SINCE_RESEED_KEY)
+        .unwrap()
+        .is_none());
+    assert!(may_load::<u64, _>(&deps.storage, MINT_LOG_HEAD_KEY)
+        .unwrap()
+        .is_none());
+
+    // an address limit still needs the per-address count
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetAddressMintLimit { limit: 5 },
+    )
+    .unwrap();
+    mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+    assert_eq!(addr_count(&deps, "alice"), Some(1));
+    assert!(may_load::<u64, _>(&deps.storage, MINT_LOG_HEAD_KEY)
+        .unwrap()
+        .is_none());
+
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetStatsResolution {
+            level: StatsLevel::Full,
+        },
+    )
+    .unwrap();
+    mint_helper(&mut deps, "listing", &["bob"]).unwrap();
+    assert_eq!(counts_helper(&deps), (0, 4));
+    assert_eq!(stats_helper(&deps).unwrap().listing, 1);
+    assert_eq!(addr_count(&deps, "bob"), Some(1));
+    assert_eq!(listing_count(&deps), Some(1));
+    assert_eq!(
+        may_load::<u64, _>(&deps.storage, MINTS_SINCE_RESEED_KEY).unwrap(),
+        Some(1)
+    );
+    assert_eq!(
+        may_load::<u64, _>(&deps.storage, MINT_LOG_HEAD_KEY).unwrap(),
+        Some(1)
+    );
+}
+
+#[test]
+fn test_released_cap_locked_without_stats() {
+    let mut deps = init_helper();
+    receive_helper(&mut deps, "admin", &["NFT1", "NFT2"]).unwrap();
+    handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetStatsResolution {
+            level: StatsLevel::None,
+        },
+    )
+    .unwrap();
+    mint_helper(&mut deps, "admin", &["alice"]).unwrap();
+    // the released count is kept without statistics, so the cap is still locked
+    assert_eq!(counts_helper(&deps).1, 1);
+    let error = extract_error_msg(handle(
+        &mut deps,
+        mock_env_sender("admin"),
+        HandleMsg::SetReleasedCap { cap: 1 },
+    ));
+    assert!(error.contains("E043: "));
+}
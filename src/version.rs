@@ -0,0 +1,4 @@
+/// name of this contract reported to integrators
+pub const CONTRACT_NAME: &str = "stashh-gumball";
+/// version of this contract reported to integrators
+pub const CONTRACT_VERSION: &str = "1.0.0";